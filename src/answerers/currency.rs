@@ -0,0 +1,234 @@
+use crate::answerers::Answerer;
+use crate::config::AnswererConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{error_for_status, http};
+use crate::models::{Answer, SearchQuery};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// A parsed conversion request such as `100 usd to eur`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conversion {
+    pub amount: f64,
+    pub from: String,
+    pub to: String,
+}
+
+/// Parses queries of the form `<amount> <from> (to|in) <to>`.
+/// The amount is optional and defaults to 1.
+pub fn parse_conversion(q: &str) -> Option<Conversion> {
+    let tokens: Vec<&str> = q.split_whitespace().collect();
+
+    let (amount, rest) = match tokens.first()?.parse::<f64>() {
+        Ok(amount) => (amount, &tokens[1..]),
+        Err(_) => (1.0, &tokens[..]),
+    };
+
+    let [from, sep, to] = rest else {
+        return None;
+    };
+
+    if !sep.eq_ignore_ascii_case("to") && !sep.eq_ignore_ascii_case("in") {
+        return None;
+    }
+
+    if !amount.is_finite() || amount < 0.0 || !is_currency_code(from) || !is_currency_code(to) {
+        return None;
+    }
+
+    Some(Conversion {
+        amount,
+        from: from.to_uppercase(),
+        to: to.to_uppercase(),
+    })
+}
+
+/// ISO 4217 codes in circulation, precious metals included.
+const ISO_4217: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT", "BGN", "BHD", "BIF",
+    "BMD", "BND", "BOB", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD", "CAD", "CDF", "CHF", "CLP", "CNY", "COP", "CRC",
+    "CUP", "CVE", "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL", "GHS",
+    "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR", "IQD", "IRR", "ISK", "JMD",
+    "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD", "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL",
+    "LYD", "MAD", "MDL", "MGA", "MKD", "MMK", "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MYR", "MZN", "NAD",
+    "NGN", "NIO", "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON", "RSD",
+    "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD", "SSP", "STN", "SVC", "SYP",
+    "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD", "TZS", "UAH", "UGX", "USD", "UYU", "UZS", "VES",
+    "VND", "VUV", "WST", "XAF", "XAG", "XAU", "XCD", "XOF", "XPF", "YER", "ZAR", "ZMW", "ZWL",
+];
+
+/// Widely traded cryptocurrencies.
+const CRYPTO: &[&str] = &[
+    "BTC", "ETH", "LTC", "XRP", "BCH", "ADA", "DOGE", "DOT", "SOL", "XMR", "USDT", "USDC", "BNB", "TRX", "XLM", "LINK",
+];
+
+/// Only known codes count, so ordinary queries ("fish to cook") never reach the rates API.
+fn is_currency_code(code: &str) -> bool {
+    let code = code.to_ascii_uppercase();
+    ISO_4217.contains(&code.as_str()) || CRYPTO.contains(&code.as_str())
+}
+
+/// Rates kept at most; the oldest is dropped to make room.
+const MAX_CACHED_RATES: usize = 512;
+
+/// Formats the converted amount, keeping more precision for small values
+/// (typical for crypto rates).
+pub fn format_conversion(conversion: &Conversion, rate: f64) -> String {
+    let converted = conversion.amount * rate;
+    let formatted = if converted.abs() >= 1.0 || converted == 0.0 {
+        format!("{:.2}", converted)
+    } else {
+        format!("{:.6}", converted).trim_end_matches('0').to_string()
+    };
+    format!("{} {} = {} {}", conversion.amount, conversion.from, formatted, conversion.to)
+}
+
+pub struct CurrencyAnswerer {
+    rates: Mutex<HashMap<(String, String), (f64, Instant)>>,
+}
+
+impl CurrencyAnswerer {
+    pub fn new() -> Self {
+        Self {
+            rates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn rate(
+        &self,
+        from: &str,
+        to: &str,
+        base_url: &str,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Result<f64, EngineError> {
+        let key = (from.to_string(), to.to_string());
+        let ttl = Duration::from_secs(config.cache_ttl);
+
+        if let Some((rate, fetched)) = self.rates.lock().await.get(&key) {
+            if fetched.elapsed() < ttl {
+                return Ok(*rate);
+            }
+        }
+
+        // The API is expected to answer `?from=USD&to=EUR` with `{"rates": {"EUR": 0.92}}`.
+        let mut request = client.get(base_url).query(&[("from", from), ("to", to)]);
+        if let Some(token) = &config.token {
+            request = request.bearer_auth(token);
+        }
+
+        let resp = http::send(request).await?;
+        error_for_status(&self.id(), &resp)?;
+
+        let body: serde_json::Value = resp.json().await?;
+        let rate = body["rates"][to]
+            .as_f64()
+            .ok_or_else(|| EngineError::Parsing(format!("No rate for {} -> {}", from, to)))?;
+
+        let mut rates = self.rates.lock().await;
+        rates.retain(|_, (_, fetched)| fetched.elapsed() < ttl);
+        if rates.len() >= MAX_CACHED_RATES {
+            let oldest = rates.iter().min_by_key(|(_, (_, fetched))| *fetched).map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                rates.remove(&oldest);
+            }
+        }
+        rates.insert(key, (rate, Instant::now()));
+        Ok(rate)
+    }
+}
+
+impl Default for CurrencyAnswerer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Answerer for CurrencyAnswerer {
+    fn id(&self) -> String {
+        "currency".to_string()
+    }
+
+    async fn answer(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Option<Answer> {
+        let base_url = config.base_url.as_deref()?;
+        let conversion = parse_conversion(&query.q)?;
+
+        let rate = match self.rate(&conversion.from, &conversion.to, base_url, client, config).await {
+            Ok(rate) => rate,
+            Err(e) => {
+                tracing::debug!("Currency answerer failed: {}", e);
+                return None;
+            }
+        };
+
+        Some(Answer {
+            answer: format_conversion(&conversion, rate),
+            url: None,
+            answerer: self.id(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conversion() {
+        assert_eq!(
+            parse_conversion("100 usd to eur"),
+            Some(Conversion {
+                amount: 100.0,
+                from: "USD".to_string(),
+                to: "EUR".to_string(),
+            })
+        );
+        assert_eq!(
+            parse_conversion("1 BTC in usd"),
+            Some(Conversion {
+                amount: 1.0,
+                from: "BTC".to_string(),
+                to: "USD".to_string(),
+            })
+        );
+        assert_eq!(parse_conversion("eur to gbp").map(|c| c.amount), Some(1.0));
+
+        assert_eq!(parse_conversion("how to cook rice"), None);
+        assert_eq!(parse_conversion("100 usd eur"), None);
+        assert_eq!(parse_conversion("100 u5d to eur"), None);
+        assert_eq!(parse_conversion("fish to cook"), None);
+        assert_eq!(parse_conversion("mile in feet"), None);
+        assert_eq!(parse_conversion("10 abc to xyz"), None);
+    }
+
+    #[test]
+    fn test_format_conversion() {
+        let conversion = parse_conversion("100 usd to eur").unwrap();
+        assert_eq!(format_conversion(&conversion, 0.92), "100 USD = 92.00 EUR");
+
+        let conversion = parse_conversion("1 usd to btc").unwrap();
+        assert_eq!(format_conversion(&conversion, 0.000016), "1 USD = 0.000016 BTC");
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_returns_none() {
+        let answerer = CurrencyAnswerer::new();
+        let query = SearchQuery {
+            q: "100 usd to eur".to_string(),
+            ..Default::default()
+        };
+        let answer = answerer
+            .answer(&query, &Client::new(), &AnswererConfig::default())
+            .await;
+        assert!(answer.is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use crate::answerers::Answerer;
+use crate::config::AnswererConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{error_for_status, http};
+use crate::models::{Answer, SearchQuery};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+
+const API_URL: &str = "https://api.duckduckgo.com/";
+
+/// Non-empty string field of an Instant Answer response.
+fn field<'a>(body: &'a Value, name: &str) -> Option<&'a str> {
+    body[name].as_str().map(str::trim).filter(|value| !value.is_empty())
+}
+
+/// Builds an answer from an Instant Answer API response, preferring a direct
+/// answer, then the abstract, then a definition. Empty responses give `None`.
+pub fn parse_instant_answer(body: &Value, answerer: &str) -> Option<Answer> {
+    let (answer, url) = if let Some(answer) = field(body, "Answer") {
+        (answer, None)
+    } else if let Some(text) = field(body, "AbstractText") {
+        (text, field(body, "AbstractURL"))
+    } else if let Some(definition) = field(body, "Definition") {
+        (definition, field(body, "DefinitionURL"))
+    } else {
+        return None;
+    };
+
+    Some(Answer {
+        answer: answer.to_string(),
+        url: url.map(|url| url.to_string()),
+        answerer: answerer.to_string(),
+    })
+}
+
+/// Abstracts, definitions and direct answers from DuckDuckGo's Instant Answer API.
+pub struct DuckDuckGoAnswerer;
+
+impl DuckDuckGoAnswerer {
+    async fn instant_answer(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Result<Option<Answer>, EngineError> {
+        let base_url = config.base_url.as_deref().unwrap_or(API_URL);
+        let request = client.get(base_url).query(&[
+            ("q", query.q.as_str()),
+            ("format", "json"),
+            ("no_html", "1"),
+            ("skip_disambig", "1"),
+        ]);
+        let resp = http::send(request).await?;
+        error_for_status(&self.id(), &resp)?;
+
+        // The API answers with `application/x-javascript`, so the body is parsed by hand.
+        let body: Value = serde_json::from_str(&resp.text().await?)
+            .map_err(|e| EngineError::Parsing(format!("Invalid instant answer response: {}", e)))?;
+        Ok(parse_instant_answer(&body, &self.id()))
+    }
+}
+
+#[async_trait]
+impl Answerer for DuckDuckGoAnswerer {
+    fn id(&self) -> String {
+        "duckduckgo".to_string()
+    }
+
+    async fn answer(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Option<Answer> {
+        if query.q.trim().is_empty() {
+            return None;
+        }
+
+        match self.instant_answer(query, client, config).await {
+            Ok(answer) => answer,
+            Err(e) => {
+                tracing::debug!("DuckDuckGo answerer failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_abstract() {
+        let body = json!({
+            "Abstract": "Rust is a general-purpose programming language.",
+            "AbstractText": "Rust is a general-purpose programming language.",
+            "AbstractURL": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+            "AbstractSource": "Wikipedia",
+            "Answer": "",
+            "Definition": "",
+            "Heading": "Rust (programming language)",
+            "Type": "A"
+        });
+
+        let answer = parse_instant_answer(&body, "duckduckgo").unwrap();
+        assert_eq!(answer.answer, "Rust is a general-purpose programming language.");
+        assert_eq!(
+            answer.url.as_deref(),
+            Some("https://en.wikipedia.org/wiki/Rust_(programming_language)")
+        );
+        assert_eq!(answer.answerer, "duckduckgo");
+    }
+
+    #[test]
+    fn test_parse_empty_response() {
+        let body = json!({
+            "Abstract": "",
+            "AbstractText": "",
+            "AbstractURL": "",
+            "Answer": "",
+            "Definition": "",
+            "RelatedTopics": [],
+            "Type": ""
+        });
+        assert!(parse_instant_answer(&body, "duckduckgo").is_none());
+    }
+}
@@ -0,0 +1,23 @@
+pub mod currency;
+pub mod duckduckgo;
+pub mod weather;
+
+use crate::config::AnswererConfig;
+use crate::models::{Answer, SearchQuery};
+use async_trait::async_trait;
+use reqwest::Client;
+
+#[async_trait]
+pub trait Answerer: Send + Sync {
+    /// Unique identifier for the answerer (e.g. "currency").
+    fn id(&self) -> String;
+
+    /// Returns an answer if the query is something this answerer understands.
+    /// Upstream failures are not surfaced; the answerer simply returns `None`.
+    async fn answer(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Option<Answer>;
+}
@@ -0,0 +1,231 @@
+use crate::answerers::Answerer;
+use crate::config::AnswererConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{error_for_status, http};
+use crate::engines::openstreetmap::{parse_coordinates, NOMINATIM_URL};
+use crate::models::{Answer, SearchQuery};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Extracts the place from `weather in <place>`, `weather for <place>` or `<place> weather`.
+pub fn parse_weather_query(q: &str) -> Option<String> {
+    let lower = q.trim().to_lowercase();
+
+    let place = if let Some(rest) = lower.strip_prefix("weather ") {
+        let rest = rest.trim_start();
+        rest.strip_prefix("in ")
+            .or_else(|| rest.strip_prefix("for "))
+            .unwrap_or(rest)
+    } else {
+        lower.strip_suffix(" weather")?
+    };
+
+    let place = place.trim();
+    if place.is_empty() || place == "in" || place == "for" {
+        None
+    } else {
+        Some(place.to_string())
+    }
+}
+
+/// Resolves a place name to `(latitude, longitude, display name)` via Nominatim.
+pub async fn geocode(client: &Client, place: &str) -> Result<Option<(f64, f64, String)>, EngineError> {
+    let request = client
+        .get(NOMINATIM_URL)
+        .query(&[("q", place), ("format", "json"), ("limit", "1")]);
+    let resp = http::send(request).await?;
+    error_for_status("nominatim", &resp)?;
+
+    let body: Value = resp.json().await?;
+    let first = &body[0];
+
+    let name = first["display_name"].as_str().unwrap_or(place).to_string();
+
+    Ok(parse_coordinates(first).map(|(lat, lon)| (lat, lon, name)))
+}
+
+/// Describes a WMO weather interpretation code as used by Open-Meteo.
+fn describe_weather_code(code: u64) -> &'static str {
+    match code {
+        0 => "Clear sky",
+        1..=3 => "Partly cloudy",
+        45 | 48 => "Fog",
+        51..=57 => "Drizzle",
+        61..=67 => "Rain",
+        71..=77 => "Snow",
+        80..=82 => "Rain showers",
+        85 | 86 => "Snow showers",
+        95..=99 => "Thunderstorm",
+        _ => "Unknown conditions",
+    }
+}
+
+/// Formats an Open-Meteo `current_weather` response.
+pub fn format_forecast(place: &str, body: &Value) -> Option<String> {
+    let current = &body["current_weather"];
+    let temperature = current["temperature"].as_f64()?;
+
+    let mut answer = format!("{}: {:.1} °C", place, temperature);
+    if let Some(code) = current["weathercode"].as_u64() {
+        answer.push_str(&format!(", {}", describe_weather_code(code)));
+    }
+    if let Some(wind) = current["windspeed"].as_f64() {
+        answer.push_str(&format!(", wind {:.1} km/h", wind));
+    }
+    Some(answer)
+}
+
+/// Forecasts kept at most; the oldest is dropped to make room.
+const MAX_CACHED_FORECASTS: usize = 256;
+
+/// Stores `answer` for `place`, first dropping forecasts older than `ttl` and,
+/// if still full, the oldest one.
+fn cache_forecast(forecasts: &mut HashMap<String, (String, Instant)>, place: &str, answer: String, ttl: Duration) {
+    forecasts.retain(|_, (_, fetched)| fetched.elapsed() < ttl);
+    if forecasts.len() >= MAX_CACHED_FORECASTS {
+        let oldest = forecasts.iter().min_by_key(|(_, (_, fetched))| *fetched).map(|(key, _)| key.clone());
+        if let Some(oldest) = oldest {
+            forecasts.remove(&oldest);
+        }
+    }
+    forecasts.insert(place.to_string(), (answer, Instant::now()));
+}
+
+pub struct WeatherAnswerer {
+    forecasts: Mutex<HashMap<String, (String, Instant)>>,
+}
+
+impl WeatherAnswerer {
+    pub fn new() -> Self {
+        Self {
+            forecasts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn forecast(
+        &self,
+        place: &str,
+        base_url: &str,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Result<Option<String>, EngineError> {
+        let ttl = Duration::from_secs(config.cache_ttl);
+        if let Some((answer, fetched)) = self.forecasts.lock().await.get(place) {
+            if fetched.elapsed() < ttl {
+                return Ok(Some(answer.clone()));
+            }
+        }
+
+        let (lat, lon, name) = match geocode(client, place).await? {
+            Some(location) => location,
+            None => return Ok(None),
+        };
+
+        let mut request = client.get(base_url).query(&[
+            ("latitude", lat.to_string()),
+            ("longitude", lon.to_string()),
+            ("current_weather", "true".to_string()),
+        ]);
+        if let Some(token) = &config.token {
+            request = request.bearer_auth(token);
+        }
+
+        let resp = http::send(request).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: Value = resp.json().await?;
+
+        let answer = format_forecast(&name, &body);
+        if let Some(answer) = &answer {
+            cache_forecast(&mut *self.forecasts.lock().await, place, answer.clone(), ttl);
+        }
+        Ok(answer)
+    }
+}
+
+impl Default for WeatherAnswerer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Answerer for WeatherAnswerer {
+    fn id(&self) -> String {
+        "weather".to_string()
+    }
+
+    async fn answer(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &AnswererConfig,
+    ) -> Option<Answer> {
+        let base_url = config.base_url.as_deref()?;
+        let place = parse_weather_query(&query.q)?;
+
+        match self.forecast(&place, base_url, client, config).await {
+            Ok(answer) => answer.map(|answer| Answer {
+                answer,
+                url: None,
+                answerer: self.id(),
+            }),
+            Err(e) => {
+                tracing::debug!("Weather answerer failed: {}", e);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_weather_query() {
+        assert_eq!(parse_weather_query("weather in Berlin"), Some("berlin".to_string()));
+        assert_eq!(parse_weather_query("Weather for New York"), Some("new york".to_string()));
+        assert_eq!(parse_weather_query("paris weather"), Some("paris".to_string()));
+        assert_eq!(parse_weather_query("weather"), None);
+        assert_eq!(parse_weather_query("weather in "), None);
+        assert_eq!(parse_weather_query("whether or not"), None);
+    }
+
+    #[test]
+    fn test_format_forecast() {
+        let body = json!({
+            "current_weather": {
+                "temperature": 12.34,
+                "windspeed": 10.0,
+                "weathercode": 61
+            }
+        });
+        assert_eq!(
+            format_forecast("Berlin", &body).as_deref(),
+            Some("Berlin: 12.3 °C, Rain, wind 10.0 km/h")
+        );
+
+        assert_eq!(format_forecast("Berlin", &json!({"error": true})), None);
+    }
+
+    #[test]
+    fn test_forecast_cache_is_bounded() {
+        let ttl = Duration::from_secs(600);
+        let mut forecasts = HashMap::new();
+        forecasts.insert("stale".to_string(), ("Stale: 1.0 °C".to_string(), Instant::now()));
+        cache_forecast(&mut forecasts, "berlin", "Berlin: 12.3 °C".to_string(), Duration::ZERO);
+        assert_eq!(forecasts.len(), 1);
+        assert!(forecasts.contains_key("berlin"));
+
+        for i in 0..MAX_CACHED_FORECASTS + 10 {
+            cache_forecast(&mut forecasts, &format!("place {}", i), "Somewhere".to_string(), ttl);
+        }
+        assert_eq!(forecasts.len(), MAX_CACHED_FORECASTS);
+        assert!(forecasts.contains_key(&format!("place {}", MAX_CACHED_FORECASTS + 9)));
+    }
+}
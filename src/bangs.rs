@@ -0,0 +1,105 @@
+use crate::models::SearchQuery;
+
+/// Short names accepted as bangs in addition to engine ids.
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("g", "google"),
+    ("ddg", "duckduckgo"),
+    ("b", "bing"),
+    ("br", "brave"),
+    ("sp", "startpage"),
+    ("mjk", "mojeek"),
+    ("ya", "yandex"),
+    ("qw", "qwant"),
+    ("wp", "wikipedia"),
+    ("wd", "wikidata"),
+    ("wt", "wiktionary"),
+    ("yt", "youtube"),
+    ("r", "reddit"),
+    ("so", "stackoverflow"),
+    ("osm", "openstreetmap"),
+];
+
+/// Strips a leading `!name` from `query.q` and routes the query accordingly:
+/// an engine id or shortcut (`!g`) runs only that engine whatever its
+/// categories, a category name (`!images`, `!social_media`) searches that
+/// category. Unknown or empty bangs leave the query untouched, so a literal
+/// `!` stays searchable. Returns whether a bang was applied.
+pub fn parse_bangs(query: &mut SearchQuery, engines: &[String], categories: &[String]) -> bool {
+    let q = query.q.trim_start();
+    let Some(rest) = q.strip_prefix('!') else {
+        return false;
+    };
+    let (name, remainder) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    if name.is_empty() {
+        return false;
+    }
+    let name = name.to_lowercase();
+
+    let engine = SHORTCUTS
+        .iter()
+        .find(|(shortcut, _)| *shortcut == name)
+        .map(|(_, id)| id.to_string())
+        .unwrap_or_else(|| name.clone());
+    if engines.contains(&engine) {
+        query.bang_engines = Some(vec![engine]);
+    } else {
+        let category = name.replace(['_', '-'], " ");
+        match categories.iter().find(|c| c.to_lowercase() == category) {
+            Some(category) => query.categories = category.clone(),
+            None => return false,
+        }
+    }
+
+    query.q = remainder.trim().to_string();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(q: &str) -> (bool, SearchQuery) {
+        let engines = ["google".to_string(), "wikipedia".to_string()];
+        let categories = ["general".to_string(), "images".to_string(), "social media".to_string()];
+        let mut query = SearchQuery {
+            q: q.to_string(),
+            ..Default::default()
+        };
+        let applied = parse_bangs(&mut query, &engines, &categories);
+        (applied, query)
+    }
+
+    #[test]
+    fn test_engine_shortcut_bang() {
+        let (applied, query) = parse("!g rust async");
+        assert!(applied);
+        assert_eq!(query.q, "rust async");
+        assert_eq!(query.bang_engines, Some(vec!["google".to_string()]));
+
+        let (_, query) = parse("!wikipedia  Ada Lovelace");
+        assert_eq!(query.q, "Ada Lovelace");
+        assert_eq!(query.bang_engines, Some(vec!["wikipedia".to_string()]));
+    }
+
+    #[test]
+    fn test_category_bang() {
+        let (applied, query) = parse("!images cats");
+        assert!(applied);
+        assert_eq!(query.q, "cats");
+        assert_eq!(query.categories, "images");
+        assert_eq!(query.bang_engines, None);
+
+        let (_, query) = parse("!social_media rust");
+        assert_eq!(query.categories, "social media");
+    }
+
+    #[test]
+    fn test_malformed_and_unknown_bangs_are_ignored() {
+        for q in ["!", "! rust", "!nosuchengine rust", "rust !g"] {
+            let (applied, query) = parse(q);
+            assert!(!applied, "{:?} should not be a bang", q);
+            assert_eq!(query.q, q);
+            assert_eq!(query.bang_engines, None);
+        }
+    }
+}
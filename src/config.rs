@@ -3,6 +3,9 @@ use serde::Deserialize;
 use std::collections::HashMap;
 use std::env;
 
+/// Placeholder `server.secret_key`. Features that trust the key stay off while it is set.
+pub const DEFAULT_SECRET_KEY: &str = "changeme";
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct EngineConfig {
     #[serde(default = "default_engine_enabled")]
@@ -17,9 +20,28 @@ pub struct EngineConfig {
     pub failure_threshold: u32,
     #[serde(default = "default_engine_cooldown")]
     pub cooldown: u64, // seconds
+    #[serde(default = "default_engine_success_threshold")]
+    pub success_threshold: u32,
+    /// Extra attempts after a failed or timed out request.
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_engine_retry_delay")]
+    pub retry_delay: u64, // milliseconds
     pub proxy: Option<String>,
+    /// Run for every query, even when the engine's categories do not match.
+    #[serde(default)]
+    pub always_run: bool,
+    /// Skip the engine when the query asks for more than one category, e.g.
+    /// a heavy image engine that should not run for `general,images`.
+    #[serde(default)]
+    pub single_category_only: bool,
+    #[serde(default)]
+    pub safesearch_mode: SafesearchMode,
     #[serde(default)]
     pub tokens: Vec<String>,
+    /// Extra request headers, overriding any the engine sets itself.
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
     #[serde(default)]
     pub extra: HashMap<String, String>,
 }
@@ -42,6 +64,25 @@ fn default_engine_failure_threshold() -> u32 {
 fn default_engine_cooldown() -> u64 {
     60
 }
+fn default_engine_success_threshold() -> u32 {
+    1
+}
+fn default_engine_retry_delay() -> u64 {
+    200
+}
+
+/// How safe search is applied to an engine's results.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SafesearchMode {
+    /// Pass the level to the engine and trust it to filter.
+    #[default]
+    Passthrough,
+    /// Search unfiltered and drop results matching the NSFW hosts and keywords.
+    Clientside,
+    /// Never filter this engine's results.
+    Off,
+}
 
 impl Default for EngineConfig {
     fn default() -> Self {
@@ -52,16 +93,58 @@ impl Default for EngineConfig {
             throttle: default_engine_throttle(),
             failure_threshold: default_engine_failure_threshold(),
             cooldown: default_engine_cooldown(),
+            success_threshold: default_engine_success_threshold(),
+            retries: 0,
+            retry_delay: default_engine_retry_delay(),
             proxy: None,
+            always_run: false,
+            single_category_only: false,
+            safesearch_mode: SafesearchMode::default(),
             tokens: Vec::new(),
+            headers: HashMap::new(),
             extra: HashMap::new(),
         }
     }
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct AnswererConfig {
+    #[serde(default = "default_engine_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_engine_timeout")]
+    pub timeout: u64, // seconds
+    /// Base URL of the upstream API. Answerers that need one stay silent when unset.
+    pub base_url: Option<String>,
+    pub token: Option<String>,
+    #[serde(default = "default_answerer_cache_ttl")]
+    pub cache_ttl: u64, // seconds
+}
+
+fn default_answerer_cache_ttl() -> u64 {
+    300
+}
+
+impl Default for AnswererConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_engine_enabled(),
+            timeout: default_engine_timeout(),
+            base_url: None,
+            token: None,
+            cache_ttl: default_answerer_cache_ttl(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct GeneralSettings {
     pub instance_name: String,
+    /// Serve a "search unavailable" response instead of querying engines.
+    #[serde(default)]
+    pub maintenance_mode: bool,
+    /// Open a connection to each enabled engine's host at startup.
+    #[serde(default)]
+    pub warmup_engines: bool,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -70,16 +153,480 @@ pub struct ServerSettings {
     pub port: u16,
     pub base_url: String,
     pub secret_key: String,
+    /// Require `Authorization: Bearer <api_token>` for the json/rss/atom formats.
+    #[serde(default)]
+    pub api_requires_token: bool,
+    pub api_token: Option<String>,
+    /// Seconds graceful shutdown waits for in-flight requests before forcing exit.
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+    /// Content-Type sent with `format=json` responses.
+    #[serde(default = "default_json_content_type")]
+    pub json_content_type: String,
+    /// Output formats served by `/search`: `html`, `json`, `rss`, `atom` and `csv`.
+    /// Requests for any other format get a 404.
+    #[serde(default = "default_enabled_formats")]
+    pub enabled_formats: Vec<String>,
+    /// Searches processed at once across the instance. Unlimited when unset.
+    /// Read at startup.
+    pub max_concurrent_searches: Option<usize>,
+    /// How long a search waits for a free slot before it is refused with 503.
+    #[serde(default = "default_search_queue_timeout")]
+    pub search_queue_timeout: u64, // milliseconds
+}
+
+fn default_search_queue_timeout() -> u64 {
+    500
+}
+
+fn default_shutdown_timeout() -> u64 {
+    10
+}
+
+fn default_enabled_formats() -> Vec<String> {
+    ["html", "json", "rss", "atom", "csv"].iter().map(|format| format.to_string()).collect()
+}
+
+fn default_json_content_type() -> String {
+    "application/json".to_string()
+}
+
+/// Rewrites result URLs on `host` (and its subdomains) to `replacement`,
+/// e.g. `twitter.com` to a Nitter instance.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct UrlRewrite {
+    pub host: String,
+    pub replacement: String,
+}
+
+/// How the contributing engines of a merged result are ordered.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum EngineOrder {
+    /// Alphabetically by engine id.
+    #[default]
+    Alpha,
+    /// By configured engine weight, heaviest first, then alphabetically.
+    Weight,
+}
+
+/// How the scores of a result found by several engines are combined.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ScoreMerge {
+    /// Sum of the engines' scores, so agreement and weight count alike.
+    #[default]
+    Sum,
+    /// Best engine score plus `frequency_boost` for each additional engine.
+    Max,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SearchSettings {
+    /// Lowercase the query before dispatching it to the engines.
+    #[serde(default)]
+    pub lowercase_query: bool,
+    /// Guess categories from the query text when the request names none.
+    #[serde(default)]
+    pub auto_category: bool,
+    /// Score multiplier applied to HTTPS results.
+    #[serde(default = "default_https_boost")]
+    pub https_boost: f64,
+    /// Hosts that are ranked lower without being blocked outright.
+    #[serde(default)]
+    pub downrank_hosts: Vec<String>,
+    /// Score multiplier applied to results from `downrank_hosts`.
+    #[serde(default = "default_downrank_penalty")]
+    pub downrank_penalty: f64,
+    #[serde(default)]
+    pub score_merge: ScoreMerge,
+    /// With `score_merge = "max"`, added to a result's score for every engine
+    /// beyond the first that found it.
+    #[serde(default)]
+    pub frequency_boost: f64,
+    /// Aggregated results scoring below this are dropped. 0 disables filtering.
+    #[serde(default)]
+    pub min_score: f64,
+    /// Rescale final scores so the top result scores 1.0 and the rest proportionally.
+    #[serde(default)]
+    pub normalize_scores: bool,
+    /// Upper bound on the number of aggregated results returned. Unbounded when unset.
+    pub max_total_results: Option<usize>,
+    /// With strict safe search, skip engines that cannot apply it upstream.
+    #[serde(default)]
+    pub strict_safesearch_only: bool,
+    /// Engines whose results win score ties, in order of preference.
+    #[serde(default)]
+    pub preferred_engines: Vec<String>,
+    /// Rewrite AMP result URLs to the canonical publisher URL.
+    #[serde(default = "default_resolve_amp")]
+    pub resolve_amp: bool,
+    #[serde(default)]
+    pub engine_order: EngineOrder,
+    /// Drop results whose detected language differs from the requested one.
+    #[serde(default)]
+    pub enforce_language: bool,
+    /// Query parameters stripped from result URLs. Entries are exact names or
+    /// prefixes ending in `*`, e.g. `utm_*`.
+    #[serde(default = "default_tracking_params")]
+    pub tracking_params: Vec<String>,
+    /// Hide results on later pages that were already shown on earlier pages of the same query.
+    #[serde(default)]
+    pub dedup_across_pages: bool,
+    /// Engines whose failure makes a `strict=1` request fail with 502.
+    #[serde(default)]
+    pub critical_engines: Vec<String>,
+    /// Upstream requests allowed in flight to one host at a time, across all
+    /// engines and searches. Unlimited when unset.
+    pub max_requests_per_host: Option<usize>,
+    /// Results accepted from a single engine before aggregation; the rest are dropped.
+    #[serde(default = "default_max_results_per_engine")]
+    pub max_results_per_engine: usize,
+    #[serde(default)]
+    pub url_rewrites: Vec<UrlRewrite>,
+    /// Overall deadline for querying engines, including retries.
+    pub request_timeout: Option<u64>, // milliseconds
+    /// Early return point: once this has passed, the search stops waiting if
+    /// engines holding `soft_timeout_coverage` of the total weight have answered.
+    pub soft_timeout: Option<u64>, // milliseconds
+    /// Point where the search stops waiting for slow engines regardless of
+    /// coverage. Unset waits for every engine's own timeout.
+    pub hard_timeout: Option<u64>, // milliseconds
+    /// Share of the queried engines' weight, from 0 to 1, that must have
+    /// answered for the search to return at `soft_timeout`.
+    #[serde(default = "default_soft_timeout_coverage")]
+    pub soft_timeout_coverage: f64,
+    /// Hosts (and their subdomains) treated as NSFW by client-side safe search.
+    #[serde(default)]
+    pub nsfw_hosts: Vec<String>,
+    /// Words that mark a result as NSFW for client-side safe search when they
+    /// appear in its title, content or URL.
+    #[serde(default = "default_nsfw_keywords")]
+    pub nsfw_keywords: Vec<String>,
+    /// Fetch the pages of top results that have no snippet and fill it from
+    /// their OpenGraph tags. Off by default, as it adds latency and contacts
+    /// result sites on the user's behalf.
+    #[serde(default)]
+    pub enrich_opengraph: bool,
+    /// Top results considered for OpenGraph enrichment.
+    #[serde(default = "default_opengraph_max_results")]
+    pub opengraph_max_results: usize,
+    /// Result pages fetched at once for OpenGraph enrichment.
+    #[serde(default = "default_opengraph_concurrency")]
+    pub opengraph_concurrency: usize,
+    /// Time allowed for each OpenGraph page fetch.
+    #[serde(default = "default_opengraph_timeout")]
+    pub opengraph_timeout: u64, // milliseconds
+    /// SearXNG instances the federation engine must never query, in addition
+    /// to this instance's own `server.base_url`.
+    #[serde(default)]
+    pub federation_blocklist: Vec<String>,
+    /// How many SearXNG instances a query may pass through before it is refused.
+    #[serde(default = "default_max_federation_hops")]
+    pub max_federation_hops: u8,
+    /// Engine answering `/autocomplete`. Empty asks every enabled engine that
+    /// offers suggestions and merges their answers.
+    #[serde(default = "default_autocomplete")]
+    pub autocomplete: String,
+}
+
+fn default_autocomplete() -> String {
+    "duckduckgo".to_string()
+}
+
+fn default_nsfw_keywords() -> Vec<String> {
+    ["porn", "xxx", "nsfw", "hentai"].iter().map(|word| word.to_string()).collect()
+}
+
+fn default_opengraph_max_results() -> usize {
+    5
+}
+
+fn default_opengraph_concurrency() -> usize {
+    3
+}
+
+fn default_opengraph_timeout() -> u64 {
+    1000
+}
+
+fn default_soft_timeout_coverage() -> f64 {
+    0.5
+}
+
+fn default_max_federation_hops() -> u8 {
+    2
+}
+
+fn default_max_results_per_engine() -> usize {
+    100
+}
+
+fn default_resolve_amp() -> bool {
+    true
+}
+
+fn default_tracking_params() -> Vec<String> {
+    [
+        "utm_source",
+        "utm_medium",
+        "utm_campaign",
+        "utm_term",
+        "utm_content",
+        "fbclid",
+        "gclid",
+        "msclkid",
+    ]
+    .iter()
+    .map(|param| param.to_string())
+    .collect()
+}
+
+fn default_https_boost() -> f64 {
+    1.05
+}
+fn default_downrank_penalty() -> f64 {
+    0.5
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            lowercase_query: false,
+            auto_category: false,
+            https_boost: default_https_boost(),
+            downrank_hosts: Vec::new(),
+            downrank_penalty: default_downrank_penalty(),
+            score_merge: ScoreMerge::default(),
+            frequency_boost: 0.0,
+            min_score: 0.0,
+            normalize_scores: false,
+            max_total_results: None,
+            strict_safesearch_only: false,
+            preferred_engines: Vec::new(),
+            resolve_amp: default_resolve_amp(),
+            engine_order: EngineOrder::default(),
+            enforce_language: false,
+            tracking_params: default_tracking_params(),
+            dedup_across_pages: false,
+            critical_engines: Vec::new(),
+            max_requests_per_host: None,
+            max_results_per_engine: default_max_results_per_engine(),
+            url_rewrites: Vec::new(),
+            request_timeout: None,
+            soft_timeout: None,
+            hard_timeout: None,
+            soft_timeout_coverage: default_soft_timeout_coverage(),
+            nsfw_hosts: Vec::new(),
+            nsfw_keywords: default_nsfw_keywords(),
+            enrich_opengraph: false,
+            opengraph_max_results: default_opengraph_max_results(),
+            opengraph_concurrency: default_opengraph_concurrency(),
+            opengraph_timeout: default_opengraph_timeout(),
+            federation_blocklist: Vec::new(),
+            max_federation_hops: default_max_federation_hops(),
+            autocomplete: default_autocomplete(),
+        }
+    }
+}
+
+/// Serving result images through `/image_proxy`, so clients never contact image hosts.
+#[derive(Debug, Deserialize, Clone)]
+pub struct ProxySettings {
+    /// Rewrite image and video thumbnail URLs in result pages to the proxy.
+    #[serde(default)]
+    pub image_proxy: bool,
+    /// Largest image the proxy relays.
+    #[serde(default = "default_proxy_max_bytes")]
+    pub max_bytes: usize,
+    /// Let the image proxy and OpenGraph enrichment fetch from loopback,
+    /// private and link-local addresses. Off by default so neither can be
+    /// used to reach the instance's own network.
+    #[serde(default)]
+    pub allow_private_hosts: bool,
+}
+
+fn default_proxy_max_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+impl Default for ProxySettings {
+    fn default() -> Self {
+        Self {
+            image_proxy: false,
+            max_bytes: default_proxy_max_bytes(),
+            allow_private_hosts: false,
+        }
+    }
+}
+
+/// Per-client rate limit on the search and autocomplete endpoints.
+#[derive(Debug, Deserialize, Clone)]
+pub struct LimiterSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Sustained rate each client address is refilled at.
+    #[serde(default = "default_limiter_requests_per_minute")]
+    pub requests_per_minute: u32,
+    /// Requests a client can send at once before the rate applies.
+    #[serde(default = "default_limiter_burst")]
+    pub burst: u32,
+    /// Take the client address from `X-Forwarded-For`. Only set this when a
+    /// reverse proxy overwrites the header, as clients can forge it.
+    #[serde(default)]
+    pub behind_proxy: bool,
+}
+
+fn default_limiter_requests_per_minute() -> u32 {
+    60
+}
+
+fn default_limiter_burst() -> u32 {
+    10
+}
+
+impl Default for LimiterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            requests_per_minute: default_limiter_requests_per_minute(),
+            burst: default_limiter_burst(),
+            behind_proxy: false,
+        }
+    }
+}
+
+/// Cache of aggregated result pages, so repeated queries skip the engines.
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheSettings {
+    /// How long a result page is served from the cache. `0` disables it.
+    #[serde(default)]
+    pub ttl: u64, // seconds
+    /// Pages kept before the least recently used one is evicted.
+    #[serde(default = "default_cache_max_entries")]
+    pub max_entries: usize,
+}
+
+fn default_cache_max_entries() -> usize {
+    1000
+}
+
+impl Default for CacheSettings {
+    fn default() -> Self {
+        Self {
+            ttl: 0,
+            max_entries: default_cache_max_entries(),
+        }
+    }
+}
+
+/// Behavior shared by every request engines and answerers send upstream.
+#[derive(Debug, Deserialize, Clone)]
+pub struct OutboundSettings {
+    /// Log the method, target and status of each upstream request. Query
+    /// strings are left out, as they carry the user's search terms.
+    #[serde(default = "default_log_requests")]
+    pub log_requests: bool,
+    /// Extra attempts for GET and HEAD requests failing with a connection
+    /// error, a timeout, or a 502, 503 or 504. Engine searches do not retry
+    /// past `search.request_timeout`.
+    #[serde(default)]
+    pub retries: u32,
+    #[serde(default = "default_outbound_retry_delay")]
+    pub retry_delay: u64, // milliseconds
+    /// Drop `Referer` and send the same `Accept` and `Accept-Language` on every
+    /// upstream request, so requests do not reveal where they came from or
+    /// which engine sent them.
+    #[serde(default = "default_normalize_headers")]
+    pub normalize_headers: bool,
+    /// Headers, by name, left as the engine set them when normalizing.
+    /// Headers in an engine's `headers` setting are always left alone.
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+}
+
+fn default_normalize_headers() -> bool {
+    true
+}
+
+fn default_log_requests() -> bool {
+    true
+}
+
+fn default_outbound_retry_delay() -> u64 {
+    100
+}
+
+impl Default for OutboundSettings {
+    fn default() -> Self {
+        Self {
+            log_requests: default_log_requests(),
+            retries: 0,
+            retry_delay: default_outbound_retry_delay(),
+            normalize_headers: default_normalize_headers(),
+            allowed_headers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UiSettings {
+    /// When disabled, `/` describes the API as JSON instead of serving the HTML index.
+    #[serde(default = "default_ui_enabled")]
+    pub enabled: bool,
+    /// Words of a result snippet shown on the results page. 0 shows snippets in full.
+    #[serde(default = "default_snippet_length")]
+    pub snippet_length: usize,
+    /// Engines named in the results footer before the rest collapse into
+    /// "and N more". 0 lists them all.
+    #[serde(default = "default_max_footer_engines")]
+    pub max_footer_engines: usize,
+}
+
+fn default_ui_enabled() -> bool {
+    true
+}
+
+fn default_snippet_length() -> usize {
+    60
+}
+
+fn default_max_footer_engines() -> usize {
+    5
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_ui_enabled(),
+            snippet_length: default_snippet_length(),
+            max_footer_engines: default_max_footer_engines(),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Settings {
     pub general: GeneralSettings,
     pub server: ServerSettings,
+    #[serde(default)]
+    pub search: SearchSettings,
+    #[serde(default)]
+    pub ui: UiSettings,
+    #[serde(default)]
+    pub outbound: OutboundSettings,
+    #[serde(default)]
+    pub cache: CacheSettings,
+    #[serde(default)]
+    pub limiter: LimiterSettings,
+    #[serde(default)]
+    pub proxy: ProxySettings,
     pub debug: bool,
     #[serde(default)]
     pub engines: HashMap<String, EngineConfig>,
     #[serde(default)]
+    pub answerers: HashMap<String, AnswererConfig>,
+    #[serde(default)]
     pub blocklist: Vec<String>,
 }
 
@@ -94,7 +641,7 @@ impl Settings {
             .set_default("server.bind_address", "127.0.0.1")?
             .set_default("server.port", 8080)?
             .set_default("server.base_url", "http://localhost:8080")?
-            .set_default("server.secret_key", "changeme")?
+            .set_default("server.secret_key", DEFAULT_SECRET_KEY)?
             // Merge with config file (if exists)
             .add_source(File::with_name("settings").required(false))
             .add_source(File::with_name(&format!("settings.{}", run_mode)).required(false))
@@ -104,4 +651,75 @@ impl Settings {
 
         s.try_deserialize()
     }
+
+    /// Checks invariants the types cannot express.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.server.api_requires_token && self.server.api_token.as_deref().unwrap_or_default().is_empty() {
+            return Err(ConfigError::Message(
+                "server.api_requires_token is set but server.api_token is empty".to_string(),
+            ));
+        }
+
+        // Anyone can sign image URLs with the published default key.
+        if self.proxy.image_proxy && self.server.secret_key == DEFAULT_SECRET_KEY {
+            return Err(ConfigError::Message(
+                "proxy.image_proxy requires server.secret_key to be changed from the default".to_string(),
+            ));
+        }
+
+        // A zero rate never refills, so every wait would be infinite.
+        if self.limiter.enabled && self.limiter.requests_per_minute == 0 {
+            return Err(ConfigError::Message(
+                "limiter.requests_per_minute must be positive when the limiter is enabled".to_string(),
+            ));
+        }
+
+        for (id, engine) in &self.engines {
+            if !engine.weight.is_finite() || engine.weight < 0.0 {
+                return Err(ConfigError::Message(format!(
+                    "engines.{}.weight must be a non-negative number",
+                    id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+impl Settings {
+    /// Minimal settings for unit tests, bypassing config files and the environment.
+    pub fn for_tests(engines: HashMap<String, EngineConfig>) -> Self {
+        Self {
+            general: GeneralSettings {
+                instance_name: "SearXNG".into(),
+                maintenance_mode: false,
+                warmup_engines: false,
+            },
+            server: ServerSettings {
+                bind_address: "127.0.0.1".into(),
+                port: 8080,
+                base_url: "http://localhost:8080".into(),
+                secret_key: "secret".into(),
+                api_requires_token: false,
+                api_token: None,
+                shutdown_timeout: default_shutdown_timeout(),
+                json_content_type: default_json_content_type(),
+                enabled_formats: default_enabled_formats(),
+                max_concurrent_searches: None,
+                search_queue_timeout: default_search_queue_timeout(),
+            },
+            search: SearchSettings::default(),
+            ui: UiSettings::default(),
+            outbound: OutboundSettings::default(),
+            cache: CacheSettings::default(),
+            limiter: LimiterSettings::default(),
+            proxy: ProxySettings::default(),
+            debug: false,
+            engines,
+            answerers: HashMap::new(),
+            blocklist: Vec::new(),
+        }
+    }
 }
@@ -1,32 +1,37 @@
-use crate::models::{ResultContent, SearchResult};
-use std::collections::HashMap;
+use crate::config::{EngineOrder, ScoreMerge, SearchSettings, Settings, UrlRewrite};
+use crate::language::{primary_subtag, result_language};
+use crate::models::{metadata_keys, ResultContent, SearchResult};
+use publicsuffix::{List, Psl};
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
 use url::Url;
 
+/// Snapshot of https://publicsuffix.org/list/public_suffix_list.dat.
+const PUBLIC_SUFFIX_LIST: &str = include_str!("../../data/public_suffix_list.dat");
+
+/// Whether a query parameter name matches a removal pattern: either the exact
+/// name, or a prefix when the pattern ends in `*` (`utm_*` matches `utm_foobar`).
+fn param_matches(name: &str, pattern: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == pattern,
+    }
+}
+
 /// Normalizes a URL by:
 /// 1. Lowercasing the scheme and host.
 /// 2. Removing fragments.
-/// 3. Removing common tracking parameters.
-fn normalize_url(url_str: &str) -> String {
+/// 3. Removing tracking parameters matching `params_to_remove`.
+fn normalize_url(url_str: &str, params_to_remove: &[String]) -> String {
     match Url::parse(url_str) {
         Ok(mut url) => {
             // Remove fragment
             url.set_fragment(None);
 
             // Remove tracking parameters
-            let params_to_remove = [
-                "utm_source",
-                "utm_medium",
-                "utm_campaign",
-                "utm_term",
-                "utm_content",
-                "fbclid",
-                "gclid",
-                "msclkid",
-            ];
-
             let pairs: Vec<(String, String)> = url
                 .query_pairs()
-                .filter(|(k, _)| !params_to_remove.contains(&k.as_ref()))
+                .filter(|(k, _)| !params_to_remove.iter().any(|pattern| param_matches(k, pattern)))
                 .map(|(k, v)| (k.into_owned(), v.into_owned()))
                 .collect();
 
@@ -42,20 +47,164 @@ fn normalize_url(url_str: &str) -> String {
     }
 }
 
+/// Resolves common AMP URL forms to the canonical publisher URL without a network request:
+/// - AMP cache: `https://www-example-com.cdn.ampproject.org/c/s/www.example.com/a`
+/// - Google AMP viewer: `https://www.google.com/amp/s/www.example.com/a`
+/// - A trailing `/amp` path segment, or an `amp=1` or empty `amp=` query
+///   parameter, on the publisher's own site. Other `amp` segments and values are
+///   left alone, as they are as likely to be content (`/wiki/AMP`, `/docs/amp/intro`).
+///
+/// Returns `None` if the URL is not an AMP URL.
+pub fn resolve_amp_url(url_str: &str) -> Option<String> {
+    let url = Url::parse(url_str).ok()?;
+    let host = url.host_str()?;
+    let path = url.path();
+
+    let cached = if host.ends_with(".cdn.ampproject.org") {
+        // /c/s/<host>/<path> (https) or /c/<host>/<path> (http); also /v/ and /i/ prefixes.
+        let rest = path
+            .strip_prefix("/c/")
+            .or_else(|| path.strip_prefix("/v/"))
+            .or_else(|| path.strip_prefix("/i/"))?;
+        Some(rest)
+    } else if host.starts_with("www.google.") || host == "google.com" {
+        path.strip_prefix("/amp/")
+    } else {
+        None
+    };
+
+    if let Some(rest) = cached {
+        let (scheme, target) = match rest.strip_prefix("s/") {
+            Some(target) => ("https", target),
+            None => ("http", rest),
+        };
+        let mut canonical = format!("{}://{}", scheme, target);
+        if let Some(query) = url.query() {
+            canonical.push('?');
+            canonical.push_str(query);
+        }
+        return Url::parse(&canonical).ok().map(|u| u.to_string());
+    }
+
+    let is_amp_param = |key: &str, value: &str| key == "amp" && (value.is_empty() || value == "1");
+    // `/story/amp` and `/story/amp/` both become `/story/`; a bare `/amp` is a page of its own.
+    let stripped_path = path
+        .strip_suffix("/amp/")
+        .or_else(|| path.strip_suffix("/amp"))
+        .filter(|rest| !rest.is_empty())
+        .map(|rest| format!("{}/", rest));
+    let has_amp_param = url.query_pairs().any(|(k, v)| is_amp_param(&k, &v));
+    if stripped_path.is_none() && !has_amp_param {
+        return None;
+    }
+
+    let mut canonical = url.clone();
+    if let Some(new_path) = &stripped_path {
+        canonical.set_path(new_path);
+    }
+    if has_amp_param {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, v)| !is_amp_param(k, v))
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect();
+        if pairs.is_empty() {
+            canonical.set_query(None);
+        } else {
+            canonical.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+    }
+
+    Some(canonical.to_string())
+}
+
+/// Applies the first rewrite whose host matches the URL's host or one of its
+/// parent domains, replacing the host and keeping path and query.
+/// Returns `None` if no rewrite applies.
+pub fn rewrite_url(url_str: &str, rewrites: &[UrlRewrite]) -> Option<String> {
+    let mut url = Url::parse(url_str).ok()?;
+    let host = url.host_str()?.to_lowercase();
+
+    let rewrite = rewrites.iter().find(|rewrite| {
+        let target = rewrite.host.to_lowercase();
+        host == target || host.ends_with(&format!(".{}", target))
+    })?;
+
+    url.set_host(Some(&rewrite.replacement)).ok()?;
+    Some(url.to_string())
+}
+
+fn suffix_list() -> &'static List {
+    static LIST: OnceLock<List> = OnceLock::new();
+    LIST.get_or_init(|| PUBLIC_SUFFIX_LIST.parse().expect("bundled public suffix list is valid"))
+}
+
+/// Registrable domain of a URL's host (`www.bbc.co.uk` -> `bbc.co.uk`), or the
+/// address itself for IP hosts.
+pub fn source_name(url_str: &str) -> Option<String> {
+    let url = Url::parse(url_str).ok()?;
+    let Some(host) = url.domain() else {
+        return url.host_str().map(str::to_string);
+    };
+    let host = host.trim_end_matches('.').to_lowercase();
+    let domain = suffix_list().domain(host.as_bytes())?;
+    String::from_utf8(domain.as_bytes().to_vec()).ok()
+}
+
+/// Merges autocomplete suggestions from several providers, in provider order.
+/// Blank entries are dropped and repeats, ignoring case and surrounding
+/// whitespace, keep their first spelling. At most `limit` are returned.
+pub fn merge_suggestions(lists: Vec<Vec<String>>, limit: usize) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut merged = Vec::new();
+
+    for suggestion in lists.into_iter().flatten() {
+        if merged.len() >= limit {
+            break;
+        }
+        let suggestion = suggestion.trim();
+        if !suggestion.is_empty() && seen.insert(suggestion.to_lowercase()) {
+            merged.push(suggestion.to_string());
+        }
+    }
+
+    merged
+}
+
 /// Aggregates search results from multiple engines.
 ///
 /// It performs the following operations:
-/// 1. Filters results based on the blocklist.
+/// 1. Resolves AMP URLs, applies URL rewrites, then filters results based on
+///    the blocklist.
 /// 2. Deduplicates results based on normalized URL.
 /// 3. Merges results:
-///    - Sums up scores (frequency boost).
+///    - Sums up scores (frequency boost), or with `score_merge = "max"` keeps
+///      the best score plus `frequency_boost` per additional engine.
 ///    - Combines engine lists.
-/// 4. Sorts results by score in descending order.
-pub fn aggregate(results: Vec<SearchResult>, blocklist: &[String]) -> Vec<SearchResult> {
-    let mut unique_results: HashMap<String, SearchResult> = HashMap::new();
+///    - Records the registrable domain as `source_name` metadata.
+/// 4. Adjusts scores for HTTPS and down-ranked hosts.
+/// 5. Drops results scoring below `min_score`.
+/// 6. Sorts results by score in descending order. Ties go to results from an
+///    earlier-listed `preferred_engines` entry, then to first-seen order.
+/// 7. Keeps at most `max_total_results` of the top-scored results.
+pub fn aggregate(results: Vec<SearchResult>, settings: &Settings) -> Vec<SearchResult> {
+    let blocklist = &settings.blocklist;
+    // Keyed by normalized URL, pointing into `final_results` to keep first-seen order.
+    let mut index_by_url: HashMap<String, usize> = HashMap::new();
+    let mut final_results: Vec<SearchResult> = Vec::new();
 
     for mut res in results {
-        // Host Blocking
+        if settings.search.resolve_amp {
+            if let Some(canonical) = resolve_amp_url(&res.url) {
+                res.url = canonical;
+            }
+        }
+
+        if let Some(rewritten) = rewrite_url(&res.url, &settings.search.url_rewrites) {
+            res.url = rewritten;
+        }
+
+        // Host Blocking, on the final URL so AMP caches and rewrites cannot unblock a host.
         if let Ok(url) = Url::parse(&res.url) {
             if let Some(host) = url.host_str() {
                 if blocklist.iter().any(|blocked| host.contains(blocked)) {
@@ -69,14 +218,23 @@ pub fn aggregate(results: Vec<SearchResult>, blocklist: &[String]) -> Vec<Search
             res.content = ResultContent::Text(ammonia::clean(text));
         }
 
-        let normalized_url = normalize_url(&res.url);
+        let normalized_url = normalize_url(&res.url, &settings.search.tracking_params);
 
-        match unique_results.get_mut(&normalized_url) {
-            Some(existing) => {
-                // Merge scores: Sum them up.
-                // This assumes scores already include weight and position decay.
-                // Summing them boosts results found by multiple engines (Frequency).
-                existing.score += res.score;
+        match index_by_url.get(&normalized_url) {
+            Some(&index) => {
+                let existing = &mut final_results[index];
+                // Merge scores. They already include weight and position decay.
+                // Summing them boosts results found by multiple engines (Frequency);
+                // in max mode the boost is added once all engines are merged.
+                match settings.search.score_merge {
+                    ScoreMerge::Sum => existing.score += res.score,
+                    ScoreMerge::Max => existing.score = existing.score.max(res.score),
+                }
+
+                // An infobox for the same page is richer than a plain snippet.
+                if res.is_infobox() && !existing.is_infobox() {
+                    existing.content = res.content;
+                }
 
                 // Merge engines
                 for engine in res.engines {
@@ -84,6 +242,11 @@ pub fn aggregate(results: Vec<SearchResult>, blocklist: &[String]) -> Vec<Search
                         existing.engines.push(engine);
                     }
                 }
+
+                // Keep metadata only the later engine provided, such as its debug position.
+                for (key, value) in res.metadata {
+                    existing.metadata.entry(key).or_insert(value);
+                }
             }
             None => {
                 // Use normalized URL for the result too?
@@ -92,37 +255,230 @@ pub fn aggregate(results: Vec<SearchResult>, blocklist: &[String]) -> Vec<Search
                 // Roadmap says "Canonicalize URLs before deduplication".
                 // Usually we want the cleanest URL.
                 res.url = normalized_url.clone();
-                unique_results.insert(normalized_url, res);
+                if !res.metadata.contains_key(metadata_keys::SOURCE_NAME) {
+                    if let Some(source) = source_name(&res.url) {
+                        res.metadata.insert(metadata_keys::SOURCE_NAME.to_string(), source);
+                    }
+                }
+                index_by_url.insert(normalized_url, final_results.len());
+                final_results.push(res);
             }
         }
     }
 
-    let mut final_results: Vec<SearchResult> = unique_results.into_values().collect();
+    // Engines are merged in arrival order, which depends on task scheduling.
+    for res in final_results.iter_mut() {
+        sort_engines(&mut res.engines, settings);
+    }
+
+    if settings.search.score_merge == ScoreMerge::Max {
+        for res in final_results.iter_mut() {
+            let extra_engines = res.engines.len().saturating_sub(1) as f64;
+            res.score += settings.search.frequency_boost * extra_engines;
+        }
+    }
+
+    adjust_scores(&mut final_results, &settings.search);
+    final_results.retain(|res| res.score >= settings.search.min_score);
 
-    // Sort by score descending
+    // Sort by score descending. `sort_by` is stable, so remaining ties keep first-seen order.
+    let preferred = &settings.search.preferred_engines;
     final_results.sort_by(|a, b| {
         b.score
             .partial_cmp(&a.score)
             .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| preferred_rank(a, preferred).cmp(&preferred_rank(b, preferred)))
     });
 
+    if let Some(max) = settings.search.max_total_results {
+        final_results.truncate(max);
+    }
+
     final_results
 }
 
+/// Orders a merged result's engines deterministically according to `engine_order`.
+fn sort_engines(engines: &mut [String], settings: &Settings) {
+    match settings.search.engine_order {
+        EngineOrder::Alpha => engines.sort(),
+        EngineOrder::Weight => {
+            let weight = |id: &String| settings.engines.get(id).map(|c| c.weight).unwrap_or(1.0);
+            engines.sort_by(|a, b| {
+                weight(b)
+                    .partial_cmp(&weight(a))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    .then_with(|| a.cmp(b))
+            });
+        }
+    }
+}
+
+/// Position of the earliest preferred engine that contributed the result.
+fn preferred_rank(res: &SearchResult, preferred: &[String]) -> usize {
+    preferred
+        .iter()
+        .position(|engine| res.engines.contains(engine))
+        .unwrap_or(usize::MAX)
+}
+
+/// Boosts HTTPS results and penalizes results from down-ranked hosts.
+fn adjust_scores(results: &mut [SearchResult], settings: &SearchSettings) {
+    for res in results.iter_mut() {
+        let Ok(url) = Url::parse(&res.url) else {
+            continue;
+        };
+
+        if url.scheme() == "https" {
+            res.score *= settings.https_boost;
+        }
+
+        if let Some(host) = url.host_str() {
+            if settings.downrank_hosts.iter().any(|downranked| host.contains(downranked)) {
+                res.score *= settings.downrank_penalty;
+            }
+        }
+    }
+}
+
+/// Rescales scores relative to the highest one, so the top result scores 1.0.
+/// Ordering is unchanged; results with a non-positive top score are left as-is.
+pub fn normalize_scores(results: &mut [SearchResult]) {
+    let max = results.iter().map(|res| res.score).fold(f64::NEG_INFINITY, f64::max);
+    if !(max.is_finite() && max > 0.0) {
+        return;
+    }
+    for res in results.iter_mut() {
+        res.score /= max;
+    }
+}
+
+/// Whether a result is on an NSFW host or mentions an NSFW keyword as a whole
+/// word in its title, content or URL.
+pub fn is_nsfw(res: &SearchResult, settings: &SearchSettings) -> bool {
+    if let Some(host) = Url::parse(&res.url).ok().and_then(|url| url.host_str().map(str::to_lowercase)) {
+        let host_blocked = settings.nsfw_hosts.iter().any(|nsfw| {
+            let nsfw = nsfw.to_lowercase();
+            host == nsfw || host.ends_with(&format!(".{}", nsfw))
+        });
+        if host_blocked {
+            return true;
+        }
+    }
+
+    let content = match &res.content {
+        ResultContent::Text(text) => text.as_str(),
+        ResultContent::Infobox { description, .. } => description.as_str(),
+        _ => "",
+    };
+    let text = format!("{} {} {}", res.title, content, res.url).to_lowercase();
+    text.split(|c: char| !c.is_alphanumeric())
+        .any(|word| settings.nsfw_keywords.iter().any(|keyword| keyword.eq_ignore_ascii_case(word)))
+}
+
+/// Drops results whose language is known and differs from `language`.
+///
+/// Results whose language cannot be determined are kept. An empty or `all`
+/// language disables the filter. Returns the kept results and the number dropped.
+pub fn enforce_language(results: Vec<SearchResult>, language: &str) -> (Vec<SearchResult>, usize) {
+    let wanted = primary_subtag(language);
+    if wanted.is_empty() || wanted == "all" {
+        return (results, 0);
+    }
+
+    let before = results.len();
+    let kept: Vec<SearchResult> = results
+        .into_iter()
+        .filter(|res| result_language(res).is_none_or(|lang| lang == wanted))
+        .collect();
+    let dropped = before - kept.len();
+    (kept, dropped)
+}
+
+/// Sorts results by their `published_date` metadata, newest first.
+///
+/// Results without a parseable date keep their relative (score) order and sort last.
+pub fn sort_by_date(results: &mut [SearchResult]) {
+    results.sort_by_cached_key(|res| std::cmp::Reverse(res.published_date()));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::models::{ResultContent, SearchResult};
     use std::collections::HashMap;
 
+    fn neutral_settings() -> Settings {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.https_boost = 1.0;
+        settings
+    }
+
     #[test]
     fn test_normalize_url() {
+        let params = neutral_settings().search.tracking_params;
         let url = "https://Example.com/Path?utm_source=google&q=test#fragment";
-        let normalized = normalize_url(url);
+        let normalized = normalize_url(url, &params);
         assert_eq!(normalized, "https://example.com/Path?q=test");
 
         let url_simple = "https://example.com";
-        assert_eq!(normalize_url(url_simple), "https://example.com/");
+        assert_eq!(normalize_url(url_simple, &params), "https://example.com/");
+    }
+
+    #[test]
+    fn test_normalize_url_prefix_patterns() {
+        let params = vec!["utm_*".to_string(), "_hs*".to_string(), "fbclid".to_string()];
+        let url = "https://example.com/?utm_foobar=x&_hsenc=y&fbclid=z&number=42";
+        assert_eq!(normalize_url(url, &params), "https://example.com/?number=42");
+
+        // Exact entries do not act as prefixes.
+        let url = "https://example.com/?fbclid_extra=1";
+        assert_eq!(normalize_url(url, &params), "https://example.com/?fbclid_extra=1");
+    }
+
+    #[test]
+    fn test_resolve_amp_cache_urls() {
+        assert_eq!(
+            resolve_amp_url("https://www-example-com.cdn.ampproject.org/c/s/www.example.com/news/story.html").as_deref(),
+            Some("https://www.example.com/news/story.html")
+        );
+        assert_eq!(
+            resolve_amp_url("https://www.google.com/amp/s/www.example.com/news/story?id=1").as_deref(),
+            Some("https://www.example.com/news/story?id=1")
+        );
+        assert_eq!(
+            resolve_amp_url("https://example-org.cdn.ampproject.org/c/example.org/page").as_deref(),
+            Some("http://example.org/page")
+        );
+    }
+
+    #[test]
+    fn test_resolve_amp_path_stripping() {
+        assert_eq!(
+            resolve_amp_url("https://example.com/story/amp").as_deref(),
+            Some("https://example.com/story/")
+        );
+        assert_eq!(
+            resolve_amp_url("https://example.com/story/amp/").as_deref(),
+            Some("https://example.com/story/")
+        );
+        assert_eq!(
+            resolve_amp_url("https://example.com/story?amp=1&ref=x").as_deref(),
+            Some("https://example.com/story?ref=x")
+        );
+        assert_eq!(
+            resolve_amp_url("https://example.com/story?amp=&ref=x").as_deref(),
+            Some("https://example.com/story?ref=x")
+        );
+        assert_eq!(resolve_amp_url("https://example.com/ampersand"), None);
+    }
+
+    #[test]
+    fn test_resolve_amp_leaves_content_alone() {
+        assert_eq!(resolve_amp_url("https://en.wikipedia.org/wiki/AMP"), None);
+        assert_eq!(resolve_amp_url("https://example.com/wiki/Amp"), None);
+        assert_eq!(resolve_amp_url("https://example.com/docs/amp/intro"), None);
+        assert_eq!(resolve_amp_url("https://example.com/search?amp=voltage"), None);
+        assert_eq!(resolve_amp_url("https://example.com/amp"), None);
     }
 
     #[test]
@@ -153,7 +509,7 @@ mod tests {
         };
 
         let results = vec![res1, res2, res3];
-        let aggregated = aggregate(results, &[]);
+        let aggregated = aggregate(results, &neutral_settings());
 
         assert_eq!(aggregated.len(), 2);
 
@@ -191,7 +547,7 @@ mod tests {
             metadata: HashMap::new(),
         };
 
-        let aggregated = aggregate(vec![res], &[]);
+        let aggregated = aggregate(vec![res], &neutral_settings());
         if let ResultContent::Text(ref text) = aggregated[0].content {
             assert!(!text.contains("<script>"));
             assert!(text.contains("Safe content"));
@@ -219,11 +575,383 @@ mod tests {
             metadata: HashMap::new(),
         };
 
-        let blocklist = vec!["blocked.com".to_string()];
+        let mut settings = neutral_settings();
+        settings.blocklist = vec!["blocked.com".to_string()];
         let results = vec![res1, res2];
-        let aggregated = aggregate(results, &blocklist);
+        let aggregated = aggregate(results, &settings);
 
         assert_eq!(aggregated.len(), 1);
         assert_eq!(aggregated[0].url, "https://allowed.com/path");
     }
+
+    #[test]
+    fn test_aggregate_blocks_hosts_behind_amp_caches() {
+        let result = |url: &str| SearchResult {
+            url: url.to_string(),
+            title: "Blocked".to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["engine1".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+
+        let mut settings = neutral_settings();
+        settings.search.resolve_amp = true;
+        settings.blocklist = vec!["blocked.com".to_string()];
+        let results = vec![
+            result("https://www.google.com/amp/s/blocked.com/story"),
+            result("https://blocked-com.cdn.ampproject.org/c/s/blocked.com/story"),
+        ];
+        assert!(aggregate(results, &settings).is_empty());
+    }
+
+    #[test]
+    fn test_aggregate_ranking_adjustments() {
+        let make = |url: &str| SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text("Content".to_string()),
+            engines: vec!["engine1".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.downrank_hosts = vec!["contentfarm.com".to_string()];
+
+        let results = vec![
+            make("https://www.contentfarm.com/article"),
+            make("https://neutral.com/article"),
+            make("http://plain.com/article"),
+        ];
+        let aggregated = aggregate(results, &settings);
+
+        let urls: Vec<&str> = aggregated.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://neutral.com/article",
+                "http://plain.com/article",
+                "https://www.contentfarm.com/article"
+            ]
+        );
+        assert!(aggregated[0].score > 1.0, "HTTPS results should be boosted");
+    }
+
+    #[test]
+    fn test_aggregate_min_score() {
+        let results = [1.0, 0.5, 0.25, 0.1]
+            .iter()
+            .enumerate()
+            .map(|(i, score)| SearchResult {
+                url: format!("https://example{}.com/", i),
+                title: format!("Result {}", i),
+                content: ResultContent::Text("Content".to_string()),
+                engines: vec!["engine1".to_string()],
+                score: *score,
+                metadata: HashMap::new(),
+            })
+            .collect();
+
+        let mut settings = neutral_settings();
+        settings.search.min_score = 0.3;
+        let aggregated = aggregate(results, &settings);
+
+        assert_eq!(aggregated.len(), 2);
+        assert!(aggregated.iter().all(|r| r.score >= 0.3));
+    }
+
+    #[test]
+    fn test_merge_suggestions_dedups_across_providers() {
+        let to_strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let merged = merge_suggestions(
+            vec![
+                to_strings(&["rust async", "Rust Book", "rust async"]),
+                to_strings(&["rust book", "  ", " rust analyzer ", "RUST ASYNC"]),
+            ],
+            10,
+        );
+        assert_eq!(merged, vec!["rust async", "Rust Book", "rust analyzer"]);
+
+        let many = (0..20).map(|i| format!("rust {}", i)).collect();
+        assert_eq!(merge_suggestions(vec![many], 3), vec!["rust 0", "rust 1", "rust 2"]);
+        assert!(merge_suggestions(vec![], 10).is_empty());
+    }
+
+    #[test]
+    fn test_frequency_boost_against_engine_weight() {
+        let hit = |url: &str, engine: &str, score: f64| SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text("Content".to_string()),
+            engines: vec![engine.to_string()],
+            score,
+            metadata: HashMap::new(),
+        };
+        // `popular` is found by three light engines, `heavy` by one heavy engine.
+        let results = || {
+            vec![
+                hit("https://popular.example.com/", "a", 0.4),
+                hit("https://popular.example.com/", "b", 0.4),
+                hit("https://popular.example.com/", "c", 0.4),
+                hit("https://heavy.example.com/", "d", 1.0),
+            ]
+        };
+        let ranking = |merge: ScoreMerge, boost: f64| {
+            let mut settings = neutral_settings();
+            settings.search.score_merge = merge;
+            settings.search.frequency_boost = boost;
+            aggregate(results(), &settings)
+                .into_iter()
+                .map(|res| (res.url, (res.score * 100.0).round() / 100.0))
+                .collect::<Vec<_>>()
+        };
+
+        let popular = "https://popular.example.com/".to_string();
+        let heavy = "https://heavy.example.com/".to_string();
+        assert_eq!(ranking(ScoreMerge::Sum, 0.0), vec![(popular.clone(), 1.2), (heavy.clone(), 1.0)]);
+        assert_eq!(ranking(ScoreMerge::Max, 0.0), vec![(heavy.clone(), 1.0), (popular.clone(), 0.4)]);
+        assert_eq!(ranking(ScoreMerge::Max, 0.2), vec![(heavy.clone(), 1.0), (popular.clone(), 0.8)]);
+        assert_eq!(ranking(ScoreMerge::Max, 0.5), vec![(popular, 1.4), (heavy, 1.0)]);
+    }
+
+    #[test]
+    fn test_aggregate_max_total_results() {
+        let results = (0..10)
+            .map(|i| SearchResult {
+                url: format!("https://example{}.com/", i),
+                title: format!("Result {}", i),
+                content: ResultContent::Text("Content".to_string()),
+                engines: vec!["engine1".to_string()],
+                score: 1.0 / (i as f64 + 1.0),
+                metadata: HashMap::new(),
+            })
+            .collect();
+
+        let mut settings = neutral_settings();
+        settings.search.max_total_results = Some(3);
+        let aggregated = aggregate(results, &settings);
+
+        let urls: Vec<&str> = aggregated.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://example0.com/", "https://example1.com/", "https://example2.com/"]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_preferred_engine_tiebreak() {
+        let make = |url: &str, engine: &str| SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text("Content".to_string()),
+            engines: vec![engine.to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+
+        let results = vec![
+            make("https://first.com/", "engine1"),
+            make("https://second.com/", "trusted"),
+        ];
+
+        // Without preference, ties keep first-seen order
+        let aggregated = aggregate(results.clone(), &neutral_settings());
+        assert_eq!(aggregated[0].url, "https://first.com/");
+
+        let mut settings = neutral_settings();
+        settings.search.preferred_engines = vec!["trusted".to_string()];
+        let aggregated = aggregate(results, &settings);
+        assert_eq!(aggregated[0].url, "https://second.com/");
+        assert_eq!(aggregated[1].url, "https://first.com/");
+    }
+
+    #[test]
+    fn test_aggregate_engine_order_is_deterministic() {
+        let make = |engine: &str| SearchResult {
+            url: "https://example.com/".to_string(),
+            title: "Example".to_string(),
+            content: ResultContent::Text("Content".to_string()),
+            engines: vec![engine.to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+
+        let arrivals = [
+            vec![make("qwant"), make("bing"), make("google")],
+            vec![make("google"), make("qwant"), make("bing")],
+        ];
+
+        for results in arrivals.clone() {
+            let aggregated = aggregate(results, &neutral_settings());
+            assert_eq!(aggregated[0].engines, vec!["bing", "google", "qwant"]);
+        }
+
+        let mut settings = neutral_settings();
+        settings.search.engine_order = crate::config::EngineOrder::Weight;
+        settings.engines.insert(
+            "qwant".to_string(),
+            crate::config::EngineConfig {
+                weight: 2.0,
+                ..Default::default()
+            },
+        );
+        for results in arrivals {
+            let aggregated = aggregate(results, &settings);
+            assert_eq!(aggregated[0].engines, vec!["qwant", "bing", "google"]);
+        }
+    }
+
+    #[test]
+    fn test_enforce_language_keeps_only_requested_language() {
+        let make = |url: &str, title: &str, content: &str| SearchResult {
+            url: url.to_string(),
+            title: title.to_string(),
+            content: ResultContent::Text(content.to_string()),
+            engines: vec!["test".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+
+        let mut tagged = make("https://de.example.com/", "Rust", "Rust");
+        tagged.metadata.insert("language".to_string(), "de-DE".to_string());
+
+        let results = vec![
+            make("https://en.example.com/", "The Rust book", "This is the guide to the language"),
+            make("https://fr.example.com/", "Le livre", "Le guide est dans la documentation"),
+            make("https://ru.example.com/", "Язык Rust", "Системный язык программирования"),
+            make("https://es.example.com/", "La casa", "La casa de la familia de mi madre"),
+            tagged,
+        ];
+
+        let (kept, dropped) = enforce_language(aggregate(results.clone(), &neutral_settings()), "en-US");
+        assert_eq!(dropped, 4);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].url, "https://en.example.com/");
+
+        let (kept, dropped) = enforce_language(aggregate(results, &neutral_settings()), "es");
+        assert_eq!(dropped, 4);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].url, "https://es.example.com/");
+
+        let undetectable = vec![make("https://example.com/", "Rust", "")];
+        assert_eq!(enforce_language(undetectable, "en").1, 0);
+    }
+
+    #[test]
+    fn test_normalize_scores_scales_top_to_one() {
+        let make = |url: &str, score: f64| SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["test".to_string()],
+            score,
+            metadata: HashMap::new(),
+        };
+        let mut results = aggregate(
+            vec![
+                make("https://a.example/", 2.5),
+                make("https://b.example/", 4.0),
+                make("https://c.example/", 1.0),
+                make("https://b.example/", 1.0),
+            ],
+            &neutral_settings(),
+        );
+        let order: Vec<String> = results.iter().map(|r| r.url.clone()).collect();
+
+        normalize_scores(&mut results);
+
+        assert_eq!(results[0].score, 1.0);
+        assert_eq!(results[1].score, 0.5);
+        assert_eq!(results[2].score, 0.2);
+        assert_eq!(results.iter().map(|r| r.url.clone()).collect::<Vec<_>>(), order);
+    }
+
+    #[test]
+    fn test_source_name_is_registrable_domain() {
+        assert_eq!(source_name("https://www.bbc.co.uk/news").as_deref(), Some("bbc.co.uk"));
+        assert_eq!(source_name("https://docs.example.com/a?b=c").as_deref(), Some("example.com"));
+        assert_eq!(source_name("http://127.0.0.1:8080/").as_deref(), Some("127.0.0.1"));
+        assert_eq!(source_name("not a url"), None);
+
+        let results = aggregate(
+            vec![SearchResult {
+                url: "https://www.bbc.co.uk/news".to_string(),
+                title: "BBC News".to_string(),
+                content: ResultContent::Text(String::new()),
+                engines: vec!["test".to_string()],
+                score: 1.0,
+                metadata: HashMap::new(),
+            }],
+            &neutral_settings(),
+        );
+        assert_eq!(results[0].metadata[metadata_keys::SOURCE_NAME], "bbc.co.uk");
+    }
+
+    #[test]
+    fn test_rewrite_url() {
+        let rewrites = vec![UrlRewrite {
+            host: "twitter.com".to_string(),
+            replacement: "nitter.net".to_string(),
+        }];
+
+        assert_eq!(
+            rewrite_url("https://twitter.com/rustlang/status/1?s=20", &rewrites).as_deref(),
+            Some("https://nitter.net/rustlang/status/1?s=20")
+        );
+        assert_eq!(
+            rewrite_url("https://mobile.twitter.com/rustlang", &rewrites).as_deref(),
+            Some("https://nitter.net/rustlang")
+        );
+        assert_eq!(rewrite_url("https://nottwitter.com/rustlang", &rewrites), None);
+        assert_eq!(rewrite_url("https://example.com/", &rewrites), None);
+
+        let mut settings = neutral_settings();
+        settings.search.url_rewrites = rewrites;
+        let results = vec![SearchResult {
+            url: "https://twitter.com/rustlang".to_string(),
+            title: "Rust".to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["test".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        }];
+        assert_eq!(aggregate(results.clone(), &settings)[0].url, "https://nitter.net/rustlang");
+
+        // A rewrite onto a blocked host is filtered like any other result there.
+        settings.blocklist = vec!["nitter.net".to_string()];
+        assert!(aggregate(results, &settings).is_empty());
+    }
+
+    #[test]
+    fn test_sort_by_date() {
+        let make = |url: &str, date: Option<&str>| {
+            let mut metadata = HashMap::new();
+            if let Some(date) = date {
+                metadata.insert("published_date".to_string(), date.to_string());
+            }
+            SearchResult {
+                url: url.to_string(),
+                title: url.to_string(),
+                content: ResultContent::Text("Content".to_string()),
+                engines: vec!["engine1".to_string()],
+                score: 1.0,
+                metadata,
+            }
+        };
+
+        let mut results = vec![
+            make("https://undated-a.com", None),
+            make("https://old.com", Some("2023-01-05")),
+            make("https://undated-b.com", None),
+            make("https://new.com", Some("2024-06-01T12:00:00Z")),
+        ];
+        sort_by_date(&mut results);
+
+        let urls: Vec<&str> = results.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec!["https://new.com", "https://old.com", "https://undated-a.com", "https://undated-b.com"]
+        );
+    }
 }
@@ -0,0 +1,233 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{
+    apply_headers, error_for_status, http, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use crate::query::{ParsedQuery, QueryToken};
+use async_trait::async_trait;
+use reqwest::Client;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+
+const API_URL: &str = "http://export.arxiv.org/api/query";
+
+const PAGE_SIZE: u32 = 10;
+
+/// Preprints from the arXiv Atom API.
+pub struct Arxiv;
+
+/// Builds the API's `search_query`: every term is searched in all fields,
+/// terms are ANDed and `OR` alternatives are parenthesized, since the API
+/// applies its boolean operators left to right.
+fn search_query(parsed: &ParsedQuery) -> String {
+    let alternatives: Vec<String> = parsed
+        .alternatives()
+        .iter()
+        .filter(|group| !group.is_empty())
+        .map(|group| {
+            group
+                .iter()
+                .filter_map(|token| match token {
+                    QueryToken::Word(w) => Some(format!("all:{}", w)),
+                    QueryToken::Phrase(p) => Some(format!("all:\"{}\"", p)),
+                    QueryToken::Or => None,
+                })
+                .collect::<Vec<_>>()
+                .join(" AND ")
+        })
+        .collect();
+
+    match alternatives.as_slice() {
+        [single] => single.clone(),
+        _ => alternatives.iter().map(|a| format!("({})", a)).collect::<Vec<_>>().join(" OR "),
+    }
+}
+
+/// Text of the first `selector` match, with whitespace collapsed, as arXiv
+/// wraps titles and abstracts over several lines.
+fn first_text(element: &ElementRef, selector: &Selector) -> Option<String> {
+    let text = element.select(selector).next()?.text().collect::<String>();
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!text.is_empty()).then_some(text)
+}
+
+fn selector(css: &str) -> Result<Selector, EngineError> {
+    Selector::parse(css).map_err(|e| EngineError::Parsing(format!("Invalid selector {}: {:?}", css, e)))
+}
+
+impl Arxiv {
+    fn parse_feed(&self, xml: &str) -> Result<Vec<SearchResult>, EngineError> {
+        let document = Html::parse_document(xml);
+        let entry_selector = selector("entry")?;
+        let title_selector = selector("title")?;
+        let id_selector = selector("id")?;
+        let link_selector = selector(r#"link[rel="alternate"]"#)?;
+        let pdf_selector = selector(r#"link[title="pdf"]"#)?;
+        let summary_selector = selector("summary")?;
+        let author_selector = selector("author > name")?;
+        let published_selector = selector("published")?;
+
+        let mut results = Vec::new();
+
+        for entry in document.select(&entry_selector) {
+            let Some(title) = first_text(&entry, &title_selector) else {
+                continue;
+            };
+            let url = entry
+                .select(&link_selector)
+                .next()
+                .and_then(|link| link.value().attr("href"))
+                .map(|href| href.to_string())
+                .or_else(|| first_text(&entry, &id_selector));
+            let Some(url) = url else {
+                continue;
+            };
+
+            let mut metadata = HashMap::new();
+            let authors: Vec<String> = entry
+                .select(&author_selector)
+                .map(|name| name.text().collect::<String>().trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+            if !authors.is_empty() {
+                metadata.insert("authors".to_string(), authors.join(", "));
+            }
+            if let Some(published) = first_text(&entry, &published_selector) {
+                metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), published);
+            }
+            if let Some(pdf) = entry.select(&pdf_selector).next().and_then(|link| link.value().attr("href")) {
+                metadata.insert("pdf_url".to_string(), pdf.to_string());
+            }
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Text(first_text(&entry, &summary_selector).unwrap_or_default()),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Arxiv {
+    fn id(&self) -> String {
+        "arxiv".to_string()
+    }
+
+    fn name(&self) -> String {
+        "arXiv".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://arxiv.org", true, "XML")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["science".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("http://export.arxiv.org/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let params = [
+            ("search_query", search_query(&query.parsed())),
+            ("start", (query.page.saturating_sub(1) * PAGE_SIZE).to_string()),
+            ("max_results", PAGE_SIZE.to_string()),
+        ];
+
+        let resp = http::send(apply_headers(client.get(API_URL).query(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let xml = response_text(resp, config).await?;
+
+        self.parse_feed(&xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <link href="http://arxiv.org/api/query?search_query%3Dall%3Aattention" rel="self" type="application/atom+xml"/>
+  <title type="html">ArXiv Query: search_query=all:attention&amp;id_list=&amp;start=0&amp;max_results=10</title>
+  <id>http://arxiv.org/api/cHxbiOdZaP56ODnBPIenZhzg5f8</id>
+  <updated>2024-01-01T00:00:00-05:00</updated>
+  <opensearch:totalResults xmlns:opensearch="http://a9.com/-/spec/opensearch/1.1/">2</opensearch:totalResults>
+  <entry>
+    <id>http://arxiv.org/abs/1706.03762v7</id>
+    <updated>2023-08-02T00:41:18Z</updated>
+    <published>2017-06-12T17:57:34Z</published>
+    <title>Attention Is All You
+  Need</title>
+    <summary>  The dominant sequence transduction models are based on complex recurrent or
+convolutional neural networks.
+</summary>
+    <author>
+      <name>Ashish Vaswani</name>
+    </author>
+    <author>
+      <name>Noam Shazeer</name>
+    </author>
+    <arxiv:comment xmlns:arxiv="http://arxiv.org/schemas/atom">15 pages, 5 figures</arxiv:comment>
+    <link href="http://arxiv.org/abs/1706.03762v7" rel="alternate" type="text/html"/>
+    <link title="pdf" href="http://arxiv.org/pdf/1706.03762v7" rel="related" type="application/pdf"/>
+    <arxiv:primary_category xmlns:arxiv="http://arxiv.org/schemas/atom" term="cs.CL" scheme="http://arxiv.org/schemas/atom"/>
+  </entry>
+  <entry>
+    <id>http://arxiv.org/abs/2101.00001v1</id>
+    <published>2021-01-01T00:00:00Z</published>
+    <title>No Links Here</title>
+    <summary>Falls back to the entry id.</summary>
+  </entry>
+</feed>
+"#;
+
+    #[test]
+    fn test_search_query_encodes_phrases_and_or() {
+        assert_eq!(search_query(&ParsedQuery::parse("attention")), "all:attention");
+        assert_eq!(
+            search_query(&ParsedQuery::parse("\"large language\" models")),
+            "all:\"large language\" AND all:models"
+        );
+        assert_eq!(
+            search_query(&ParsedQuery::parse("transformer OR \"state space\" model")),
+            "(all:transformer) OR (all:\"state space\" AND all:model)"
+        );
+    }
+
+    #[test]
+    fn test_parse_feed() {
+        let results = Arxiv.parse_feed(FEED).unwrap();
+        assert_eq!(results.len(), 2);
+
+        let paper = &results[0];
+        assert_eq!(paper.title, "Attention Is All You Need");
+        assert_eq!(paper.url, "http://arxiv.org/abs/1706.03762v7");
+        assert_eq!(paper.metadata["authors"], "Ashish Vaswani, Noam Shazeer");
+        assert_eq!(paper.metadata["pdf_url"], "http://arxiv.org/pdf/1706.03762v7");
+        assert_eq!(paper.metadata[metadata_keys::PUBLISHED_DATE], "2017-06-12T17:57:34Z");
+        assert!(paper.published_date().is_some());
+        assert!(matches!(&paper.content, ResultContent::Text(text)
+            if text == "The dominant sequence transduction models are based on complex recurrent or convolutional neural networks."));
+
+        let fallback = &results[1];
+        assert_eq!(fallback.url, "http://arxiv.org/abs/2101.00001v1");
+        assert!(!fallback.metadata.contains_key("authors"));
+    }
+}
@@ -1,14 +1,27 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
-use crate::models::{ResultContent, SearchQuery, SearchResult};
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+
+const SEARCH_URL: &str = "https://www.bing.com/search";
 
 pub struct Bing;
 
+impl Bing {
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new("li.b_algo", "h2 > a", "h2 > a", ".b_caption p, .b_algo_text")
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        parse_with_selectors(html, selectors, SEARCH_URL, &self.id())
+    }
+}
+
 #[async_trait]
 impl SearchEngine for Bing {
     fn id(&self) -> String {
@@ -19,18 +32,29 @@ impl SearchEngine for Bing {
         "Bing".to_string()
     }
 
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.bing.com", false, "HTML")
+            .with_privacy_policy("https://privacy.microsoft.com/privacystatement")
+    }
+
     fn categories(&self) -> Vec<String> {
         vec!["general".to_string()]
     }
 
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.bing.com/".to_string())
+    }
+
     async fn search(
         &self,
         query: &SearchQuery,
         client: &Client,
-        _config: &EngineConfig,
+        config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError> {
-        let url = "https://www.bing.com/search";
-
         let first = (query.page - 1) * 10 + 1;
 
         let mut params = vec![
@@ -38,57 +62,68 @@ impl SearchEngine for Bing {
             ("first", first.to_string()),
         ];
 
+        if let Some(language) = query.language_code() {
+            params.push(("setlang", language));
+        }
+        if let Some(country) = query.country_code() {
+            params.push(("cc", country));
+        }
+
         if query.safesearch > 0 {
             params.push(("adlt", if query.safesearch == 1 { "moderate".to_string() } else { "strict".to_string() }));
         } else {
             params.push(("adlt", "off".to_string()));
         }
 
-        let resp = client.get(url).query(&params).send().await?;
+        let resp = http::send(apply_headers(client.get(SEARCH_URL).query(&params), config)).await?;
 
-        if !resp.status().is_success() {
-             return Err(EngineError::Unexpected(anyhow::anyhow!("Bing returned {}", resp.status())));
-        }
+        error_for_status(&self.id(), &resp)?;
 
-        let text = resp.text().await?;
-        let document = Html::parse_document(&text);
-
-        let result_selector = Selector::parse("li.b_algo")
-            .map_err(|e| EngineError::Parsing(format!("Invalid result selector: {:?}", e)))?;
-        let title_selector = Selector::parse("h2 > a")
-            .map_err(|e| EngineError::Parsing(format!("Invalid title selector: {:?}", e)))?;
-        let snippet_selector = Selector::parse(".b_caption p, .b_algo_text")
-            .map_err(|e| EngineError::Parsing(format!("Invalid snippet selector: {:?}", e)))?;
-
-        let mut results = Vec::new();
-
-        for element in document.select(&result_selector) {
-            let title_element = match element.select(&title_selector).next() {
-                Some(el) => el,
-                None => continue,
-            };
-
-            let title = title_element.text().collect::<Vec<_>>().join(" ");
-            let url = match title_element.value().attr("href") {
-                Some(href) => href.to_string(),
-                None => continue,
-            };
-
-            let content_text = match element.select(&snippet_selector).next() {
-                Some(el) => el.text().collect::<Vec<_>>().join(" "),
-                None => String::new(),
-            };
-
-            results.push(SearchResult {
-                url,
-                title,
-                content: ResultContent::Text(content_text),
-                engines: vec![self.id()],
-                score: 1.0,
-                metadata: HashMap::new(),
-            });
-        }
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+}
 
-        Ok(results)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::selectors::SelectorOverride;
+
+    const PAGE: &str = r#"
+        <ol id="b_results">
+            <li class="b_algo">
+                <h2><a href="https://example.com/old">Old layout</a></h2>
+                <div class="b_caption"><p>Matched by the default selectors</p></div>
+            </li>
+            <div class="b_algo_v2">
+                <h2><a href="https://example.com/new">New layout</a></h2>
+                <div class="b_caption"><p>Only matched by the override</p></div>
+            </div>
+        </ol>
+    "#;
+
+    #[test]
+    fn test_selector_override_changes_parsing() {
+        let results = Bing.parse_results(PAGE, &Bing::selectors()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com/old");
+
+        let overrides = SelectorOverride {
+            result: Some("div.b_algo_v2".to_string()),
+            ..Default::default()
+        };
+        let selectors = Bing::selectors().with_override(Some(&overrides));
+        let results = Bing.parse_results(PAGE, &selectors).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com/new");
+        assert_eq!(results[0].title, "New layout");
+
+        let invalid = SelectorOverride {
+            title: Some("h2 >".to_string()),
+            ..Default::default()
+        };
+        assert!(Bing.parse_results(PAGE, &Bing::selectors().with_override(Some(&invalid))).is_err());
     }
 }
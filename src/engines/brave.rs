@@ -0,0 +1,150 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+
+const SEARCH_URL: &str = "https://search.brave.com/search";
+
+pub struct Brave;
+
+impl Brave {
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new("#results .snippet", ".title", "a", ".snippet-description")
+    }
+
+    fn safesearch(query: &SearchQuery) -> &'static str {
+        match query.safesearch {
+            0 => "off",
+            1 => "moderate",
+            _ => "strict",
+        }
+    }
+
+    /// Brave's `offset` counts pages from 0 rather than results.
+    fn params(query: &SearchQuery) -> Vec<(&'static str, String)> {
+        vec![
+            ("q", query.q.clone()),
+            ("offset", query.page.saturating_sub(1).to_string()),
+            ("safesearch", Self::safesearch(query).to_string()),
+        ]
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        parse_with_selectors(html, selectors, SEARCH_URL, &self.id())
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Brave {
+    fn id(&self) -> String {
+        "brave".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Brave".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://search.brave.com", false, "HTML")
+            .with_privacy_policy("https://search.brave.com/help/privacy-policy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://search.brave.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        // Brave reads the level from its preference cookie as well as the parameter.
+        let cookie = format!("safesearch={}", Self::safesearch(query));
+
+        let request = client
+            .get(SEARCH_URL)
+            .query(&Self::params(query))
+            .header(reqwest::header::COOKIE, cookie);
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        // A 429 becomes `EngineError::RateLimited`, suspending the engine.
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    const FIXTURE: &str = r#"
+        <html><body>
+        <div id="results">
+            <div class="snippet" data-type="web" data-pos="1">
+                <a href="https://www.rust-lang.org/" class="h">
+                    <div class="title">Rust Programming Language</div>
+                </a>
+                <div class="snippet-description">A language empowering everyone to build reliable and efficient software.</div>
+            </div>
+            <div class="snippet" data-type="web" data-pos="2">
+                <div class="title">No link, skipped</div>
+            </div>
+            <div class="snippet" data-type="web" data-pos="3">
+                <a href="https://doc.rust-lang.org/book/" class="h">
+                    <div class="title">The Rust Programming Language - The Book</div>
+                </a>
+            </div>
+        </div>
+        </body></html>
+    "#;
+
+    #[test]
+    fn test_parse_results_fixture() {
+        let results = Brave.parse_results(FIXTURE, &Brave::selectors()).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text.starts_with("A language empowering")));
+
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        assert!(matches!(&results[1].content, ResultContent::Text(text) if text.is_empty()));
+    }
+
+    #[test]
+    fn test_params_use_page_offset_and_safesearch() {
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            page: 3,
+            safesearch: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            Brave::params(&query),
+            vec![
+                ("q", "rust".to_string()),
+                ("offset", "2".to_string()),
+                ("safesearch", "moderate".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use crate::models::{SearchQuery, SearchResult};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Identifies a result page. The query text is trimmed and lowercased and the
+/// categories sorted, so trivially different spellings share an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CacheKey {
+    q: String,
+    language: String,
+    page: u32,
+    safesearch: u8,
+    categories: Vec<String>,
+    /// Engines picked by a bang, the `engines` parameter or the cookie, which
+    /// change the page as much as the categories do.
+    engines: Vec<String>,
+    time_range: String,
+    country: String,
+}
+
+impl CacheKey {
+    pub fn new(query: &SearchQuery) -> Self {
+        let sorted = |list: &str| {
+            let mut items: Vec<String> = list
+                .split(',')
+                .map(|item| item.trim().to_lowercase())
+                .filter(|item| !item.is_empty())
+                .collect();
+            items.sort();
+            items.dedup();
+            items
+        };
+        let engines = match &query.bang_engines {
+            Some(engines) => engines.clone(),
+            None => sorted(query.engines.as_deref().or(query.cookie_engines.as_deref()).unwrap_or_default()),
+        };
+        Self {
+            q: query.q.trim().to_lowercase(),
+            language: query.language.to_lowercase(),
+            page: query.page,
+            safesearch: query.safesearch,
+            categories: sorted(&query.categories),
+            engines,
+            time_range: query.time_range.clone(),
+            country: query.country.to_lowercase(),
+        }
+    }
+}
+
+struct CachedPage {
+    results: Vec<SearchResult>,
+    stored: Instant,
+    /// Position in the recency order; higher is more recent.
+    tick: u64,
+}
+
+#[derive(Default)]
+struct CacheState {
+    pages: HashMap<CacheKey, CachedPage>,
+    /// Tick to key, oldest first, for picking eviction victims.
+    recency: BTreeMap<u64, CacheKey>,
+    next_tick: u64,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: &CacheKey) {
+        let tick = self.next_tick;
+        self.next_tick += 1;
+        if let Some(page) = self.pages.get_mut(key) {
+            self.recency.remove(&page.tick);
+            page.tick = tick;
+            self.recency.insert(tick, key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(page) = self.pages.remove(key) {
+            self.recency.remove(&page.tick);
+        }
+    }
+}
+
+/// Aggregated result pages by query, expiring after a TTL and evicting the
+/// least recently used page once full.
+///
+/// TTL and capacity are passed on each call so reloaded settings apply
+/// without rebuilding the cache.
+#[derive(Default)]
+pub struct ResultCache {
+    state: Mutex<CacheState>,
+}
+
+impl ResultCache {
+    /// The page stored for `key`, if it is younger than `ttl`.
+    pub fn get(&self, key: &CacheKey, ttl: Duration) -> Option<Vec<SearchResult>> {
+        let mut state = self.state.lock().ok()?;
+        let fresh = state.pages.get(key)?.stored.elapsed() < ttl;
+        if !fresh {
+            state.remove(key);
+            return None;
+        }
+        state.touch(key);
+        state.pages.get(key).map(|page| page.results.clone())
+    }
+
+    pub fn insert(&self, key: CacheKey, results: Vec<SearchResult>, max_entries: usize) {
+        if max_entries == 0 {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else {
+            return;
+        };
+        state.remove(&key);
+        while state.pages.len() >= max_entries {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            state.pages.remove(&oldest);
+        }
+        let tick = state.next_tick;
+        state.next_tick += 1;
+        state.recency.insert(tick, key.clone());
+        state.pages.insert(
+            key,
+            CachedPage {
+                results,
+                stored: Instant::now(),
+                tick,
+            },
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().map(|state| state.pages.len()).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    fn query(q: &str) -> SearchQuery {
+        SearchQuery {
+            q: q.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn page(url: &str) -> Vec<SearchResult> {
+        vec![SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["mock".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        }]
+    }
+
+    #[test]
+    fn test_key_normalizes_query_and_categories() {
+        let mut a = query("  Rust Lang ");
+        a.categories = "it,general".to_string();
+        let mut b = query("rust lang");
+        b.categories = "general, it".to_string();
+        assert_eq!(CacheKey::new(&a), CacheKey::new(&b));
+
+        b.page = 2;
+        assert_ne!(CacheKey::new(&a), CacheKey::new(&b));
+    }
+
+    #[test]
+    fn test_least_recently_used_page_is_evicted() {
+        let cache = ResultCache::default();
+        let ttl = Duration::from_secs(60);
+        let (a, b, c) = (CacheKey::new(&query("a")), CacheKey::new(&query("b")), CacheKey::new(&query("c")));
+
+        cache.insert(a.clone(), page("https://a.example/"), 2);
+        cache.insert(b.clone(), page("https://b.example/"), 2);
+        // Reading `a` makes `b` the least recently used.
+        assert!(cache.get(&a, ttl).is_some());
+        cache.insert(c.clone(), page("https://c.example/"), 2);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&a, ttl).is_some());
+        assert!(cache.get(&b, ttl).is_none());
+        assert!(cache.get(&c, ttl).is_some());
+    }
+
+    #[test]
+    fn test_expired_page_is_dropped() {
+        let cache = ResultCache::default();
+        let key = CacheKey::new(&query("a"));
+        cache.insert(key.clone(), page("https://a.example/"), 10);
+
+        assert!(cache.get(&key, Duration::ZERO).is_none());
+        assert!(cache.is_empty());
+    }
+}
@@ -1,5 +1,9 @@
 use std::time::{Duration, Instant};
 
+/// Longest suspension an upstream `Retry-After` can impose, unless the
+/// configured cooldown is longer.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(600);
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum CircuitBreakerState {
     Closed,
@@ -14,19 +18,38 @@ pub struct CircuitBreaker {
     last_failure: Option<Instant>,
     failure_threshold: u32,
     cooldown: Duration,
+    /// Consecutive successful probes required in HalfOpen before closing.
+    success_threshold: u32,
+    half_open_successes: u32,
+    probe_in_flight: bool,
+    /// Upstream-provided cooldown for the current open period, replacing `cooldown`.
+    cooldown_override: Option<Duration>,
 }
 
 impl CircuitBreaker {
-    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+    pub fn new(failure_threshold: u32, cooldown: Duration, success_threshold: u32) -> Self {
         Self {
             state: CircuitBreakerState::Closed,
             failures: 0,
             last_failure: None,
             failure_threshold,
             cooldown,
+            success_threshold,
+            half_open_successes: 0,
+            probe_in_flight: false,
+            cooldown_override: None,
         }
     }
 
+    pub fn state(&self) -> CircuitBreakerState {
+        self.state.clone()
+    }
+
+    /// Cooldown applying to the current open period.
+    pub fn cooldown(&self) -> Duration {
+        self.cooldown_override.unwrap_or(self.cooldown)
+    }
+
     /// Checks if a request is allowed.
     /// Manages state transitions based on cooldown.
     pub fn check(&mut self) -> bool {
@@ -34,9 +57,12 @@ impl CircuitBreaker {
             CircuitBreakerState::Closed => true,
             CircuitBreakerState::Open => {
                 if let Some(last) = self.last_failure {
-                    if last.elapsed() >= self.cooldown {
+                    if last.elapsed() >= self.cooldown() {
                         // Cooldown passed, try one request (Half-Open)
                         self.state = CircuitBreakerState::HalfOpen;
+                        self.cooldown_override = None;
+                        self.half_open_successes = 0;
+                        self.probe_in_flight = true;
                         return true;
                     }
                 }
@@ -45,18 +71,27 @@ impl CircuitBreaker {
             CircuitBreakerState::HalfOpen => {
                 // In Half-Open state, we only allow one request at a time.
                 // The first request transitioned from Open -> HalfOpen and got 'true'.
-                // Subsequent checks while still in HalfOpen (meaning the first request hasn't finished)
-                // should return false to prevent flooding.
-                false
+                // Subsequent checks while that probe is still running should return false
+                // to prevent flooding. Once it succeeded, the next probe may go out.
+                if self.probe_in_flight {
+                    return false;
+                }
+                self.probe_in_flight = true;
+                true
             }
         }
     }
 
     pub fn report_success(&mut self) {
         if self.state == CircuitBreakerState::HalfOpen {
-            self.state = CircuitBreakerState::Closed;
-            self.failures = 0;
-            self.last_failure = None;
+            self.probe_in_flight = false;
+            self.half_open_successes += 1;
+            if self.half_open_successes >= self.success_threshold {
+                self.state = CircuitBreakerState::Closed;
+                self.failures = 0;
+                self.last_failure = None;
+                self.half_open_successes = 0;
+            }
         } else if self.state == CircuitBreakerState::Closed {
             // Also reset failures on success in Closed state
             self.failures = 0;
@@ -79,6 +114,8 @@ impl CircuitBreaker {
                 // If it fails in HalfOpen, go back to Open immediately
                 self.state = CircuitBreakerState::Open;
                 self.last_failure = Some(Instant::now());
+                self.half_open_successes = 0;
+                self.probe_in_flight = false;
             }
             CircuitBreakerState::Open => {
                 // Update timestamp to extend cooldown
@@ -86,6 +123,16 @@ impl CircuitBreaker {
             }
         }
     }
+
+    /// Opens the breaker for the upstream's `Retry-After`, capped at the
+    /// larger of the configured cooldown and [`MAX_RETRY_AFTER`].
+    pub fn report_rate_limited(&mut self, retry_after: Duration) {
+        self.state = CircuitBreakerState::Open;
+        self.last_failure = Some(Instant::now());
+        self.cooldown_override = Some(retry_after.min(self.cooldown.max(MAX_RETRY_AFTER)));
+        self.half_open_successes = 0;
+        self.probe_in_flight = false;
+    }
 }
 
 #[cfg(test)]
@@ -94,7 +141,7 @@ mod tests {
 
     #[test]
     fn test_circuit_breaker_flow() {
-        let mut cb = CircuitBreaker::new(2, Duration::from_millis(100));
+        let mut cb = CircuitBreaker::new(2, Duration::from_millis(100), 1);
 
         // Closed initially
         assert!(cb.check());
@@ -138,4 +185,61 @@ mod tests {
         assert_eq!(cb.state, CircuitBreakerState::Open);
         assert!(!cb.check());
     }
+
+    #[test]
+    fn test_half_open_requires_success_threshold() {
+        let mut cb = CircuitBreaker::new(1, Duration::from_millis(50), 2);
+
+        cb.report_failure();
+        assert_eq!(cb.state, CircuitBreakerState::Open);
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(cb.check()); // First probe
+        assert!(!cb.check()); // Probe still in flight
+
+        // First success is not enough to close
+        cb.report_success();
+        assert_eq!(cb.state, CircuitBreakerState::HalfOpen);
+
+        // Second probe succeeds -> Closed
+        assert!(cb.check());
+        cb.report_success();
+        assert_eq!(cb.state, CircuitBreakerState::Closed);
+
+        // A failure between probes reverts to Open and resets the count
+        cb.report_failure();
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(cb.check());
+        cb.report_success();
+        assert!(cb.check());
+        cb.report_failure();
+        assert_eq!(cb.state, CircuitBreakerState::Open);
+        assert_eq!(cb.half_open_successes, 0);
+    }
+
+    #[test]
+    fn test_rate_limit_overrides_cooldown() {
+        let mut cb = CircuitBreaker::new(3, Duration::from_secs(60), 1);
+
+        cb.report_rate_limited(Duration::from_millis(50));
+        assert_eq!(cb.state, CircuitBreakerState::Open);
+        assert_eq!(cb.cooldown(), Duration::from_millis(50));
+        assert!(!cb.check());
+
+        std::thread::sleep(Duration::from_millis(80));
+        assert!(cb.check(), "hinted cooldown should replace the configured one");
+        assert_eq!(cb.cooldown(), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn test_retry_after_is_clamped() {
+        let mut cb = CircuitBreaker::new(2, Duration::from_secs(60), 1);
+        cb.report_rate_limited(Duration::from_secs(31_536_000));
+        assert_eq!(cb.cooldown(), MAX_RETRY_AFTER);
+
+        // A configured cooldown beyond the ceiling still bounds the hint.
+        let mut cb = CircuitBreaker::new(2, Duration::from_secs(3600), 1);
+        cb.report_rate_limited(Duration::from_secs(31_536_000));
+        assert_eq!(cb.cooldown(), Duration::from_secs(3600));
+    }
 }
@@ -0,0 +1,164 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{
+    apply_headers, error_for_status, http, EngineAbout, SearchEngine, DEFAULT_USER_AGENT,
+};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const API_URL: &str = "https://crates.io/api/v1/crates";
+
+const PER_PAGE: u32 = 10;
+
+/// Rust packages from the crates.io API.
+///
+/// crates.io rejects requests without a descriptive User-Agent, so one is
+/// always sent.
+pub struct CratesIo;
+
+impl CratesIo {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["crates"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let name = match item["name"].as_str() {
+                Some(name) => name,
+                None => continue,
+            };
+
+            let mut metadata = HashMap::new();
+            if let Some(downloads) = item["downloads"].as_u64() {
+                metadata.insert("downloads".to_string(), downloads.to_string());
+            }
+            if let Some(version) = item["max_stable_version"].as_str().or_else(|| item["max_version"].as_str()) {
+                metadata.insert("max_version".to_string(), version.to_string());
+            }
+            if let Some(updated) = item["updated_at"].as_str() {
+                metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), updated.to_string());
+            }
+            if let Some(repository) = item["repository"].as_str() {
+                metadata.insert("repository".to_string(), repository.to_string());
+            }
+
+            let description = item["description"].as_str().unwrap_or_default().trim().to_string();
+
+            results.push(SearchResult {
+                url: format!("https://crates.io/crates/{}", name),
+                title: name.to_string(),
+                content: ResultContent::Text(description),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for CratesIo {
+    fn id(&self) -> String {
+        "crates".to_string()
+    }
+
+    fn name(&self) -> String {
+        "crates.io".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://crates.io", true, "JSON")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["it".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://crates.io/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let params = [
+            ("q", query.q.clone()),
+            ("page", query.page.to_string()),
+            ("per_page", PER_PAGE.to_string()),
+        ];
+
+        let request = client
+            .get(API_URL)
+            .query(&params)
+            .header(reqwest::header::USER_AGENT, DEFAULT_USER_AGENT);
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "crates": [
+                {
+                    "id": "serde",
+                    "name": "serde",
+                    "description": "A generic serialization/deserialization framework",
+                    "downloads": 412_345_678u64,
+                    "max_version": "1.0.204",
+                    "max_stable_version": "1.0.204",
+                    "updated_at": "2024-07-08T12:00:00.000000+00:00",
+                    "repository": "https://github.com/serde-rs/serde"
+                },
+                {
+                    "id": "tokio-preview",
+                    "name": "tokio-preview",
+                    "description": null,
+                    "downloads": 12,
+                    "max_version": "0.1.0-alpha.1",
+                    "max_stable_version": null
+                },
+                {
+                    "description": "No name"
+                }
+            ],
+            "meta": {"total": 3}
+        });
+
+        let results = CratesIo.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        let serde = &results[0];
+        assert_eq!(serde.url, "https://crates.io/crates/serde");
+        assert_eq!(serde.title, "serde");
+        assert_eq!(serde.metadata["downloads"], "412345678");
+        assert_eq!(serde.metadata["max_version"], "1.0.204");
+        assert!(matches!(&serde.content, ResultContent::Text(text) if text.starts_with("A generic serialization")));
+
+        // Pre-release only crates fall back to `max_version`.
+        let preview = &results[1];
+        assert_eq!(preview.metadata["max_version"], "0.1.0-alpha.1");
+        assert!(matches!(&preview.content, ResultContent::Text(text) if text.is_empty()));
+    }
+}
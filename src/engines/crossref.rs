@@ -0,0 +1,205 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub struct Crossref;
+
+impl Crossref {
+    /// Extracts works from a Crossref `/works` response.
+    /// Every field is optional in practice, so missing ones are skipped rather than failing the work.
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["message"]["items"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let doi = item["DOI"].as_str().unwrap_or_default();
+            let url = match item["URL"].as_str() {
+                Some(url) => url.to_string(),
+                None if !doi.is_empty() => format!("https://doi.org/{}", doi),
+                None => continue,
+            };
+
+            let title = match item["title"][0].as_str() {
+                Some(title) => title.to_string(),
+                None => continue,
+            };
+
+            let mut metadata = HashMap::new();
+            if !doi.is_empty() {
+                metadata.insert("doi".to_string(), doi.to_string());
+            }
+
+            let authors: Vec<String> = item["author"]
+                .as_array()
+                .map(|authors| {
+                    authors
+                        .iter()
+                        .filter_map(|author| {
+                            let given = author["given"].as_str().unwrap_or_default();
+                            let family = author["family"].as_str().or_else(|| author["name"].as_str())?;
+                            Some(format!("{} {}", given, family).trim().to_string())
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            if !authors.is_empty() {
+                metadata.insert("authors".to_string(), authors.join(", "));
+            }
+
+            let date_parts = item["published"]["date-parts"][0]
+                .as_array()
+                .or_else(|| item["issued"]["date-parts"][0].as_array());
+            if let Some(parts) = date_parts {
+                let parts: Vec<u64> = parts.iter().filter_map(|p| p.as_u64()).collect();
+                if let Some(year) = parts.first() {
+                    metadata.insert("year".to_string(), year.to_string());
+                    let date = match parts.as_slice() {
+                        [year, month, day, ..] => format!("{:04}-{:02}-{:02}", year, month, day),
+                        [year, month] => format!("{:04}-{:02}", year, month),
+                        _ => format!("{:04}", year),
+                    };
+                    metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), date);
+                }
+            }
+
+            let journal = item["container-title"][0].as_str().unwrap_or_default();
+            if !journal.is_empty() {
+                metadata.insert("journal".to_string(), journal.to_string());
+            }
+
+            let content = item["abstract"].as_str().unwrap_or(journal).to_string();
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Text(content),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Crossref {
+    fn id(&self) -> String {
+        "crossref".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Crossref".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.crossref.org", true, "JSON")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["science".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://api.crossref.org/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let url = "https://api.crossref.org/works";
+
+        let rows = 20;
+        let offset = (query.page - 1) * rows;
+
+        let params = [
+            ("query", query.q.clone()),
+            ("rows", rows.to_string()),
+            ("offset", offset.to_string()),
+        ];
+
+        // Crossref routes clients that identify themselves with a mailto to its "polite" pool.
+        let user_agent = match config.extra.get("mailto") {
+            Some(mailto) => format!("SearXNG/1.0 (https://github.com/searxng/searxng; mailto:{})", mailto),
+            None => crate::engines::DEFAULT_USER_AGENT.to_string(),
+        };
+
+        let request = client.get(url)
+            .query(&params)
+            .header("User-Agent", user_agent);
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "status": "ok",
+            "message": {
+                "items": [
+                    {
+                        "DOI": "10.1000/xyz123",
+                        "URL": "https://doi.org/10.1000/xyz123",
+                        "title": ["A Study of Things"],
+                        "author": [
+                            {"given": "Ada", "family": "Lovelace"},
+                            {"family": "Babbage"}
+                        ],
+                        "published": {"date-parts": [[2021, 3, 9]]},
+                        "container-title": ["Journal of Things"],
+                        "abstract": "<jats:p>We study things.</jats:p>"
+                    },
+                    {
+                        "DOI": "10.1000/partial",
+                        "title": ["Partial Work"],
+                        "issued": {"date-parts": [[1999]]}
+                    },
+                    {
+                        "DOI": "10.1000/untitled"
+                    }
+                ]
+            }
+        });
+
+        let results = Crossref.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        let full = &results[0];
+        assert_eq!(full.url, "https://doi.org/10.1000/xyz123");
+        assert_eq!(full.title, "A Study of Things");
+        assert_eq!(full.metadata["doi"], "10.1000/xyz123");
+        assert_eq!(full.metadata["authors"], "Ada Lovelace, Babbage");
+        assert_eq!(full.metadata["year"], "2021");
+        assert_eq!(full.metadata["published_date"], "2021-03-09");
+
+        let partial = &results[1];
+        assert_eq!(partial.url, "https://doi.org/10.1000/partial");
+        assert_eq!(partial.metadata["year"], "1999");
+        assert!(!partial.metadata.contains_key("authors"));
+        assert!(matches!(&partial.content, ResultContent::Text(text) if text.is_empty()));
+    }
+}
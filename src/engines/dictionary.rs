@@ -0,0 +1,244 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const API_URL: &str = "https://api.dictionaryapi.dev/api/v2/entries/";
+
+/// Definitions per part of speech shown in the infobox.
+const DEFINITIONS_PER_MEANING: usize = 3;
+
+/// Word definitions from the Free Dictionary API, one infobox per entry with
+/// a row for each part of speech.
+pub struct Dictionary;
+
+impl Dictionary {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let entries = match body.as_array() {
+            Some(entries) => entries,
+            None => return results,
+        };
+
+        for entry in entries {
+            let word = match entry["word"].as_str() {
+                Some(word) => word,
+                None => continue,
+            };
+
+            let mut attributes = Vec::new();
+            let mut metadata = HashMap::new();
+
+            let phonetic = entry["phonetic"].as_str().or_else(|| {
+                entry["phonetics"].as_array()?.iter().find_map(|p| p["text"].as_str())
+            });
+            if let Some(phonetic) = phonetic.filter(|p| !p.is_empty()) {
+                attributes.push(("Pronunciation".to_string(), phonetic.to_string()));
+                metadata.insert("phonetic".to_string(), phonetic.to_string());
+            }
+
+            let mut description = String::new();
+            let mut parts_of_speech = Vec::new();
+            for meaning in entry["meanings"].as_array().into_iter().flatten() {
+                let part_of_speech = meaning["partOfSpeech"].as_str().unwrap_or_default();
+                let definitions: Vec<String> = meaning["definitions"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|d| {
+                        let definition = d["definition"].as_str()?;
+                        Some(match d["example"].as_str() {
+                            Some(example) => format!("{} (e.g. \"{}\")", definition, example),
+                            None => definition.to_string(),
+                        })
+                    })
+                    .take(DEFINITIONS_PER_MEANING)
+                    .collect();
+                if definitions.is_empty() {
+                    continue;
+                }
+
+                if description.is_empty() {
+                    let first = meaning["definitions"][0]["definition"].as_str().unwrap_or_default();
+                    description = first.to_string();
+                }
+                let numbered: Vec<String> = definitions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, definition)| format!("{}. {}", i + 1, definition))
+                    .collect();
+                attributes.push((part_of_speech.to_string(), numbered.join(" ")));
+                if !part_of_speech.is_empty() && !parts_of_speech.contains(&part_of_speech) {
+                    parts_of_speech.push(part_of_speech);
+                }
+            }
+            if !parts_of_speech.is_empty() {
+                metadata.insert("part_of_speech".to_string(), parts_of_speech.join(", "));
+            }
+
+            let url = entry["sourceUrls"][0]
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("https://en.wiktionary.org/wiki/{}", word.replace(' ', "_")));
+
+            results.push(SearchResult {
+                url,
+                title: word.to_string(),
+                content: ResultContent::Infobox {
+                    description,
+                    attributes,
+                    image: None,
+                },
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Dictionary {
+    fn id(&self) -> String {
+        "dictionary".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Free Dictionary".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://dictionaryapi.dev", true, "JSON")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["dictionary".to_string()]
+    }
+
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://api.dictionaryapi.dev/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let word = query.normalized_q();
+        if word.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let language = query.language_code().unwrap_or_else(|| "en".to_string());
+        let mut url = Url::parse(API_URL).expect("valid API URL");
+        url.path_segments_mut()
+            .expect("API URL has a path")
+            .pop_if_empty()
+            .push(&language)
+            .push(&word);
+
+        let resp = http::send(apply_headers(client.get(url), config)).await?;
+
+        // Unknown words are a 404 with a "No Definitions Found" body.
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response_with_several_parts_of_speech() {
+        let body = json!([
+            {
+                "word": "run",
+                "phonetics": [
+                    {"audio": "https://example.com/run.mp3"},
+                    {"text": "/ɹʌn/"}
+                ],
+                "meanings": [
+                    {
+                        "partOfSpeech": "verb",
+                        "definitions": [
+                            {"definition": "To move swiftly on foot.", "example": "Run to the shop."},
+                            {"definition": "To flee."},
+                            {"definition": "To operate."},
+                            {"definition": "To compete in a race."}
+                        ]
+                    },
+                    {
+                        "partOfSpeech": "noun",
+                        "definitions": [
+                            {"definition": "An act of running."}
+                        ]
+                    },
+                    {
+                        "partOfSpeech": "adjective",
+                        "definitions": []
+                    }
+                ],
+                "sourceUrls": ["https://en.wiktionary.org/wiki/run"]
+            },
+            {"title": "No word"}
+        ]);
+
+        let results = Dictionary.parse_response(&body);
+        assert_eq!(results.len(), 1);
+
+        let result = &results[0];
+        assert_eq!(result.title, "run");
+        assert_eq!(result.url, "https://en.wiktionary.org/wiki/run");
+        assert_eq!(result.metadata["phonetic"], "/ɹʌn/");
+        assert_eq!(result.metadata["part_of_speech"], "verb, noun");
+
+        match &result.content {
+            ResultContent::Infobox { description, attributes, .. } => {
+                assert_eq!(description, "To move swiftly on foot.");
+                assert_eq!(
+                    attributes,
+                    &vec![
+                        ("Pronunciation".to_string(), "/ɹʌn/".to_string()),
+                        (
+                            "verb".to_string(),
+                            "1. To move swiftly on foot. (e.g. \"Run to the shop.\") 2. To flee. 3. To operate."
+                                .to_string()
+                        ),
+                        ("noun".to_string(), "1. An act of running.".to_string()),
+                    ]
+                );
+            }
+            other => panic!("expected an infobox, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_not_found_body() {
+        let body = json!({
+            "title": "No Definitions Found",
+            "message": "Sorry pal, we couldn't find definitions for the word you were looking for.",
+            "resolution": "You can try the search again at later time or head to the web instead."
+        });
+        assert!(Dictionary.parse_response(&body).is_empty());
+    }
+}
@@ -1,14 +1,29 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
-use crate::models::{ResultContent, SearchQuery, SearchResult};
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, parse_opensearch_suggestions, response_text,
+    EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
-use scraper::{Html, Selector};
-use std::collections::HashMap;
+
+const SEARCH_URL: &str = "https://html.duckduckgo.com/html/";
+const AUTOCOMPLETE_URL: &str = "https://duckduckgo.com/ac/";
 
 pub struct DuckDuckGo;
 
+impl DuckDuckGo {
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new("div#links > div.web-result", "h2 > a", "h2 > a", "a.result__snippet")
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        parse_with_selectors(html, selectors, SEARCH_URL, &self.id())
+    }
+}
+
 #[async_trait]
 impl SearchEngine for DuckDuckGo {
     fn id(&self) -> String {
@@ -19,18 +34,29 @@ impl SearchEngine for DuckDuckGo {
         "DuckDuckGo".to_string()
     }
 
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://duckduckgo.com", false, "HTML")
+            .with_privacy_policy("https://duckduckgo.com/privacy")
+    }
+
     fn categories(&self) -> Vec<String> {
         vec!["general".to_string()]
     }
 
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://html.duckduckgo.com/".to_string())
+    }
+
     async fn search(
         &self,
         query: &SearchQuery,
         client: &Client,
-        _config: &EngineConfig,
+        config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError> {
-        let url = "https://html.duckduckgo.com/html/";
-
         let language = if query.language.is_empty() {
             "wt-wt"
         } else {
@@ -53,55 +79,27 @@ impl SearchEngine for DuckDuckGo {
             ("s", &s),
         ];
 
-        let resp = client.post(url).form(&params).send().await?;
-
-        if !resp.status().is_success() {
-            return Err(EngineError::Unexpected(anyhow::anyhow!(
-                "DuckDuckGo returned status: {}",
-                resp.status()
-            )));
-        }
-
-        let text = resp.text().await?;
-        let document = Html::parse_document(&text);
-
-        // Selectors
-        let result_selector = Selector::parse("div#links > div.web-result")
-            .map_err(|e| EngineError::Parsing(format!("Invalid result selector: {:?}", e)))?;
-        let title_selector = Selector::parse("h2 > a")
-             .map_err(|e| EngineError::Parsing(format!("Invalid title selector: {:?}", e)))?;
-        let snippet_selector = Selector::parse("a.result__snippet")
-             .map_err(|e| EngineError::Parsing(format!("Invalid snippet selector: {:?}", e)))?;
-
-        let mut results = Vec::new();
-
-        for element in document.select(&result_selector) {
-            let title_element = match element.select(&title_selector).next() {
-                Some(el) => el,
-                None => continue,
-            };
-
-            let title = title_element.text().collect::<Vec<_>>().join(" ");
-            let url = match title_element.value().attr("href") {
-                Some(href) => href.to_string(),
-                None => continue,
-            };
-
-            let content_text = match element.select(&snippet_selector).next() {
-                Some(el) => el.text().collect::<Vec<_>>().join(" "),
-                None => String::new(),
-            };
-
-            results.push(SearchResult {
-                url,
-                title,
-                content: ResultContent::Text(content_text),
-                engines: vec![self.id()],
-                score: 1.0,
-                metadata: HashMap::new(),
-            });
-        }
-
-        Ok(results)
+        let resp = http::send(apply_headers(client.post(SEARCH_URL).form(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+
+    async fn suggest(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<String>, EngineError> {
+        // `type=list` answers in the OpenSearch suggestions format.
+        let params = [("q", query.q.as_str()), ("type", "list")];
+        let resp = http::send(apply_headers(client.get(AUTOCOMPLETE_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: serde_json::Value = resp.json().await?;
+        Ok(parse_opensearch_suggestions(&body))
     }
 }
@@ -1,3 +1,4 @@
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,8 +9,11 @@ pub enum EngineError {
     Parsing(String),
     #[error("Timeout")]
     Timeout,
+    /// The upstream answered 429, optionally with a `Retry-After` hint.
     #[error("Rate limited")]
-    RateLimited,
+    RateLimited { retry_after: Option<Duration> },
+    #[error("{engine} returned HTTP {status}")]
+    Http { status: u16, engine: String },
     #[error("Unexpected error: {0}")]
     Unexpected(#[from] anyhow::Error),
 }
@@ -0,0 +1,185 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const SEARCH_URL: &str = "https://api.genius.com/search";
+
+const PER_PAGE: u32 = 10;
+
+/// Songs from the Genius API. The access token is the first entry of
+/// `tokens`; without one the engine returns nothing.
+pub struct Genius;
+
+impl Genius {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let hits = match body["response"]["hits"].as_array() {
+            Some(hits) => hits,
+            None => return results,
+        };
+
+        for hit in hits.iter().filter(|hit| hit["type"] == "song") {
+            let song = &hit["result"];
+            let (title, url) = match (song["title"].as_str(), song["url"].as_str()) {
+                (Some(title), Some(url)) => (title, url),
+                _ => continue,
+            };
+
+            let mut metadata = HashMap::new();
+            let artist = song["primary_artist"]["name"].as_str().or_else(|| song["artist_names"].as_str());
+            if let Some(artist) = artist {
+                metadata.insert("artist".to_string(), artist.to_string());
+            }
+            if let Some(album) = song["album"]["name"].as_str() {
+                metadata.insert("album".to_string(), album.to_string());
+            }
+            if let Some(thumbnail) = song["song_art_image_thumbnail_url"].as_str() {
+                metadata.insert(metadata_keys::THUMBNAIL.to_string(), thumbnail.to_string());
+            }
+
+            let content = song["full_title"].as_str().unwrap_or(title).to_string();
+
+            results.push(SearchResult {
+                url: url.to_string(),
+                title: title.to_string(),
+                content: ResultContent::Text(content),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Genius {
+    fn id(&self) -> String {
+        "genius".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Genius".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://genius.com", true, "JSON")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["music".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://api.genius.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let Some(token) = config.tokens.first() else {
+            return Ok(vec![]);
+        };
+
+        let params = [
+            ("q", query.q.clone()),
+            ("page", query.page.to_string()),
+            ("per_page", PER_PAGE.to_string()),
+        ];
+
+        let request = client.get(SEARCH_URL).query(&params).bearer_auth(token);
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Trimmed response of `GET /search?q=bohemian rhapsody`.
+    const SEARCH_RESPONSE: &str = r#"{
+        "meta": {"status": 200},
+        "response": {
+            "hits": [
+                {
+                    "highlights": [],
+                    "index": "song",
+                    "type": "song",
+                    "result": {
+                        "id": 1063,
+                        "title": "Bohemian Rhapsody",
+                        "full_title": "Bohemian Rhapsody by Queen",
+                        "artist_names": "Queen",
+                        "url": "https://genius.com/Queen-bohemian-rhapsody-lyrics",
+                        "song_art_image_thumbnail_url": "https://images.genius.com/thumb.300x300x1.jpg",
+                        "album": {"id": 4211, "name": "A Night at the Opera"},
+                        "primary_artist": {"id": 563, "name": "Queen"}
+                    }
+                },
+                {
+                    "index": "song",
+                    "type": "song",
+                    "result": {
+                        "id": 2,
+                        "title": "Bohemian Rhapsody (Live)",
+                        "url": "https://genius.com/Queen-bohemian-rhapsody-live-lyrics",
+                        "artist_names": "Queen"
+                    }
+                },
+                {
+                    "index": "artist",
+                    "type": "artist",
+                    "result": {"name": "Queen", "url": "https://genius.com/artists/Queen"}
+                }
+            ]
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_response() {
+        let body: Value = serde_json::from_str(SEARCH_RESPONSE).unwrap();
+        let results = Genius.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        let song = &results[0];
+        assert_eq!(song.title, "Bohemian Rhapsody");
+        assert_eq!(song.url, "https://genius.com/Queen-bohemian-rhapsody-lyrics");
+        assert_eq!(song.metadata["artist"], "Queen");
+        assert_eq!(song.metadata["album"], "A Night at the Opera");
+        assert!(matches!(&song.content, ResultContent::Text(text) if text == "Bohemian Rhapsody by Queen"));
+
+        let live = &results[1];
+        assert_eq!(live.metadata["artist"], "Queen");
+        assert!(!live.metadata.contains_key("album"));
+    }
+
+    #[tokio::test]
+    async fn test_missing_token_skips_request() {
+        let query = SearchQuery {
+            q: "bohemian rhapsody".to_string(),
+            ..Default::default()
+        };
+        // Without a token nothing is sent upstream.
+        let results = Genius
+            .search(&query, &Client::new(), &EngineConfig::default())
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
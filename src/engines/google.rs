@@ -1,81 +1,112 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
+use crate::engines::selectors::ScraperSelectors;
+use crate::engines::{
+    apply_headers, error_for_status, http, parse_opensearch_suggestions, resolve_url, response_text,
+    EngineAbout, SearchEngine,
+};
 use crate::models::{ResultContent, SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use std::collections::HashMap;
 
-pub struct Google;
+const SEARCH_URL: &str = "https://www.google.com/search";
+const AUTOCOMPLETE_URL: &str = "https://suggestqueries.google.com/complete/search";
 
-#[async_trait]
-impl SearchEngine for Google {
-    fn id(&self) -> String {
-        "google".to_string()
-    }
+pub struct Google;
 
-    fn name(&self) -> String {
-        "Google".to_string()
-    }
+/// Extracts the knowledge panel shown for entity queries into an infobox result.
+/// Returns `None` when the page has no panel or it lacks a title or description.
+fn parse_knowledge_panel(document: &Html) -> Option<SearchResult> {
+    let panel_selector = Selector::parse(".kp-wholepage").ok()?;
+    let title_selector = Selector::parse(r#"[data-attrid="title"]"#).ok()?;
+    let description_selector = Selector::parse(".kno-rdesc span").ok()?;
+    let source_selector = Selector::parse(".kno-rdesc a").ok()?;
+    let fact_selector = Selector::parse(r#"[data-attrid^="kc:/"], [data-attrid^="ss:/"]"#).ok()?;
+    let label_selector = Selector::parse(".w8qArf").ok()?;
+    let value_selector = Selector::parse(".LrzXr").ok()?;
+    let image_selector = Selector::parse("g-img img").ok()?;
 
-    fn categories(&self) -> Vec<String> {
-        vec!["general".to_string()]
-    }
+    let text = |el: scraper::ElementRef| el.text().collect::<String>().trim().to_string();
 
-    async fn search(
-        &self,
-        query: &SearchQuery,
-        client: &Client,
-        _config: &EngineConfig,
-    ) -> Result<Vec<SearchResult>, EngineError> {
-        let url = "https://www.google.com/search";
+    let panel = document.select(&panel_selector).next()?;
+    let title = panel.select(&title_selector).next().map(text).filter(|t| !t.is_empty())?;
+    let description = panel
+        .select(&description_selector)
+        .next()
+        .map(text)
+        .filter(|d| !d.is_empty())?;
 
-        let start = (query.page - 1) * 10;
+    let attributes: Vec<(String, String)> = panel
+        .select(&fact_selector)
+        .filter_map(|fact| {
+            let label = fact.select(&label_selector).next().map(text)?;
+            let value = fact.select(&value_selector).next().map(text)?;
+            let label = label.trim_end_matches(':').trim().to_string();
+            (!label.is_empty() && !value.is_empty()).then_some((label, value))
+        })
+        .collect();
 
-        let mut params = vec![
-            ("q", query.q.clone()),
-            ("start", start.to_string()),
-        ];
+    let image = panel
+        .select(&image_selector)
+        .filter_map(|img| img.value().attr("src"))
+        .find(|src| src.starts_with("http"))
+        .map(str::to_string);
 
-        if query.safesearch > 0 {
-            params.push(("safe", "active".to_string()));
-        } else {
-            params.push(("safe", "off".to_string()));
-        }
+    // Link to the panel's source (usually Wikipedia), otherwise to the entity search.
+    let url = panel
+        .select(&source_selector)
+        .filter_map(|a| a.value().attr("href"))
+        .find_map(|href| resolve_url("https://www.google.com/", href))
+        .or_else(|| {
+            url::Url::parse_with_params("https://www.google.com/search", [("q", title.as_str())])
+                .ok()
+                .map(|u| u.to_string())
+        })?;
 
-        let resp = client.get(url).query(&params).send().await?;
+    Some(SearchResult {
+        url,
+        title,
+        content: ResultContent::Infobox {
+            description,
+            attributes,
+            image,
+        },
+        engines: vec!["google".to_string()],
+        score: 1.0,
+        metadata: HashMap::new(),
+    })
+}
 
-        if !resp.status().is_success() {
-             return Err(EngineError::Unexpected(anyhow::anyhow!("Google returned {}", resp.status())));
-        }
+impl Google {
+    // Google HTML can be tricky and changes often.
+    // These selectors are for a basic non-JS version if possible, but
+    // Google often returns different HTML based on User-Agent.
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new("div.g", "h3", "a", "div.VwiC3b, div.s, .st")
+    }
 
-        let text = resp.text().await?;
-        let document = Html::parse_document(&text);
-
-        // Google HTML can be tricky and changes often.
-        // These selectors are for a basic non-JS version if possible, but
-        // Google often returns different HTML based on User-Agent.
-        let result_selector = Selector::parse("div.g")
-            .map_err(|e| EngineError::Parsing(format!("Invalid result selector: {:?}", e)))?;
-        let title_selector = Selector::parse("h3")
-            .map_err(|e| EngineError::Parsing(format!("Invalid title selector: {:?}", e)))?;
-        let url_selector = Selector::parse("a")
-            .map_err(|e| EngineError::Parsing(format!("Invalid url selector: {:?}", e)))?;
-        let snippet_selector = Selector::parse("div.VwiC3b, div.s, .st")
-            .map_err(|e| EngineError::Parsing(format!("Invalid snippet selector: {:?}", e)))?;
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        let document = Html::parse_document(html);
+        let selectors = selectors.compile()?;
 
         let mut results = Vec::new();
 
-        for element in document.select(&result_selector) {
-            let title_element = match element.select(&title_selector).next() {
+        // The infobox goes first so it gets the strongest position score.
+        if let Some(infobox) = parse_knowledge_panel(&document) {
+            results.push(infobox);
+        }
+
+        for element in document.select(&selectors.result) {
+            let title_element = match element.select(&selectors.title).next() {
                 Some(el) => el,
                 None => continue,
             };
 
             let title = title_element.text().collect::<Vec<_>>().join(" ");
 
-            let url = match element.select(&url_selector).next().and_then(|el| el.value().attr("href")) {
+            let href = match element.select(&selectors.url).next().and_then(|el| el.value().attr("href")) {
                 Some(href) => {
                     if href.starts_with("/url?q=") {
                         // Extract actual URL from Google redirect
@@ -92,7 +123,12 @@ impl SearchEngine for Google {
                 None => continue,
             };
 
-            let content_text = match element.select(&snippet_selector).next() {
+            let url = match resolve_url(SEARCH_URL, &href) {
+                Some(url) => url,
+                None => continue,
+            };
+
+            let content_text = match element.select(&selectors.snippet).next() {
                 Some(el) => el.text().collect::<Vec<_>>().join(" "),
                 None => String::new(),
             };
@@ -110,3 +146,134 @@ impl SearchEngine for Google {
         Ok(results)
     }
 }
+
+#[async_trait]
+impl SearchEngine for Google {
+    fn id(&self) -> String {
+        "google".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Google".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.google.com", false, "HTML")
+            .with_privacy_policy("https://policies.google.com/privacy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.google.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let start = (query.page - 1) * 10;
+
+        let mut params = vec![
+            ("q", query.q.clone()),
+            ("start", start.to_string()),
+        ];
+
+        if let Some(language) = query.language_code() {
+            params.push(("hl", language));
+        }
+        if let Some(country) = query.country_code() {
+            params.push(("gl", country));
+        }
+
+        if query.safesearch > 0 {
+            params.push(("safe", "active".to_string()));
+        } else {
+            params.push(("safe", "off".to_string()));
+        }
+
+        let resp = http::send(apply_headers(client.get(SEARCH_URL).query(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+
+    async fn suggest(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<String>, EngineError> {
+        // The `firefox` client answers in the OpenSearch suggestions format.
+        let mut params = vec![("client", "firefox".to_string()), ("q", query.q.clone())];
+        if let Some(language) = query.language_code() {
+            params.push(("hl", language));
+        }
+        let resp = http::send(apply_headers(client.get(AUTOCOMPLETE_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: serde_json::Value = resp.json().await?;
+        Ok(parse_opensearch_suggestions(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_knowledge_panel() {
+        let html = r#"
+            <html><body>
+            <div class="kp-wholepage">
+                <h2 data-attrid="title">Ada Lovelace</h2>
+                <g-img><img src="data:image/gif;base64,R0lGOD"><img src="https://encrypted-tbn0.gstatic.com/ada.jpg"></g-img>
+                <div class="kno-rdesc">
+                    <span>Augusta Ada King, Countess of Lovelace was an English mathematician.</span>
+                    <a href="https://en.wikipedia.org/wiki/Ada_Lovelace">Wikipedia</a>
+                </div>
+                <div data-attrid="kc:/people/person:born">
+                    <span class="w8qArf">Born: </span><span class="LrzXr">10 December 1815, London</span>
+                </div>
+                <div data-attrid="kc:/people/person:parents">
+                    <span class="w8qArf">Parents: </span><span class="LrzXr">Lord Byron, Anne Isabella Milbanke</span>
+                </div>
+                <div data-attrid="kc:/people/person:empty"><span class="w8qArf">Empty:</span></div>
+            </div>
+            <div class="g"><a href="https://example.com/"><h3>Example</h3></a></div>
+            </body></html>
+        "#;
+
+        let infobox = parse_knowledge_panel(&Html::parse_document(html)).expect("panel should parse");
+        assert_eq!(infobox.title, "Ada Lovelace");
+        assert_eq!(infobox.url, "https://en.wikipedia.org/wiki/Ada_Lovelace");
+        match infobox.content {
+            ResultContent::Infobox { description, attributes, image } => {
+                assert!(description.starts_with("Augusta Ada King"));
+                assert_eq!(
+                    attributes,
+                    vec![
+                        ("Born".to_string(), "10 December 1815, London".to_string()),
+                        ("Parents".to_string(), "Lord Byron, Anne Isabella Milbanke".to_string()),
+                    ]
+                );
+                assert_eq!(image.as_deref(), Some("https://encrypted-tbn0.gstatic.com/ada.jpg"));
+            }
+            other => panic!("Expected infobox, got {:?}", other),
+        }
+
+        let plain = Html::parse_document(r#"<div class="g"><a href="https://example.com/"><h3>Example</h3></a></div>"#);
+        assert!(parse_knowledge_panel(&plain).is_none());
+    }
+}
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+
+/// Caps simultaneous upstream requests per host, across every engine and
+/// search that targets the host.
+#[derive(Default)]
+pub struct HostLimiter {
+    /// Host to its configured limit and the semaphore enforcing it.
+    semaphores: Mutex<HashMap<String, (usize, Arc<Semaphore>)>>,
+}
+
+impl HostLimiter {
+    /// Semaphore allowing `limit` requests to `host` at once. When the limit
+    /// changes, requests started afterwards use a fresh semaphore.
+    pub fn semaphore(&self, host: &str, limit: usize) -> Arc<Semaphore> {
+        let mut semaphores = self.semaphores.lock().unwrap_or_else(|e| e.into_inner());
+        match semaphores.get(host) {
+            Some((current, semaphore)) if *current == limit => semaphore.clone(),
+            _ => {
+                let semaphore = Arc::new(Semaphore::new(limit));
+                semaphores.insert(host.to_string(), (limit, semaphore.clone()));
+                semaphore
+            }
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use crate::config::{EngineConfig, OutboundSettings};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, REFERER};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// The outbound settings and, for engine searches, the search deadline.
+#[derive(Clone, Default)]
+struct Scope {
+    policy: OutboundSettings,
+    deadline: Option<Instant>,
+}
+
+tokio::task_local! {
+    static SCOPE: Scope;
+}
+
+/// Runs `future` with `policy` applied to every request it sends through [`send`].
+/// The registry wraps engine and answerer calls in this, so the settings follow
+/// reloads without engines having to read them.
+pub async fn with_policy<F: Future>(policy: OutboundSettings, future: F) -> F::Output {
+    with_policy_until(policy, None, future).await
+}
+
+/// [`with_policy`] for work bounded by `deadline`: [`send`] does not retry
+/// once the retry delay would run past it, so outbound retries stay within
+/// the search's budget like the registry's own engine retries.
+pub async fn with_policy_until<F: Future>(policy: OutboundSettings, deadline: Option<Instant>, future: F) -> F::Output {
+    SCOPE.scope(Scope { policy, deadline }, future).await
+}
+
+/// `outbound` for requests made on behalf of an engine. Headers
+/// set in its configuration are deliberate, so they are exempt from
+/// normalization like those in `allowed_headers`.
+pub fn engine_policy(outbound: &OutboundSettings, config: &EngineConfig) -> OutboundSettings {
+    let mut policy = outbound.clone();
+    policy.allowed_headers.extend(config.headers.keys().cloned());
+    policy
+}
+
+/// Upstream statuses worth another attempt.
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// `Accept` sent upstream when normalizing headers.
+pub const NORMALIZED_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+/// `Accept-Language` sent upstream when normalizing headers. Engines pass the
+/// query language as a parameter instead.
+pub const NORMALIZED_ACCEPT_LANGUAGE: &str = "en-US,en;q=0.5";
+
+/// Drops `Referer` and replaces `Accept` and `Accept-Language` with the
+/// normalized values, except for headers in `allowed_headers`.
+fn normalize_headers(headers: &mut HeaderMap, policy: &OutboundSettings) {
+    let allowed = |name: &str| policy.allowed_headers.iter().any(|h| h.eq_ignore_ascii_case(name));
+
+    if !allowed(REFERER.as_str()) {
+        headers.remove(REFERER);
+    }
+    for (name, value) in [(ACCEPT, NORMALIZED_ACCEPT), (ACCEPT_LANGUAGE, NORMALIZED_ACCEPT_LANGUAGE)] {
+        if !allowed(name.as_str()) {
+            headers.insert(name, HeaderValue::from_static(value));
+        }
+    }
+}
+
+/// The request target as logged: without the query string, which holds the search terms.
+fn log_target(url: &Url) -> String {
+    format!("{}://{}{}", url.scheme(), url.host_str().unwrap_or_default(), url.path())
+}
+
+/// Sends the request under the current outbound policy: headers are
+/// normalized, the request is logged, and GET and HEAD requests that fail
+/// transiently are retried while the deadline allows. Outside
+/// [`with_policy`] the default policy applies.
+pub async fn send(builder: RequestBuilder) -> reqwest::Result<Response> {
+    let Scope { policy, deadline } = SCOPE.try_with(|scope| scope.clone()).unwrap_or_default();
+    let (client, request) = builder.build_split();
+    let mut request = request?;
+    if policy.normalize_headers {
+        normalize_headers(request.headers_mut(), &policy);
+    }
+    let idempotent = matches!(*request.method(), Method::GET | Method::HEAD);
+
+    let mut attempt = 0;
+    loop {
+        let retry = if idempotent && attempt < policy.retries { request.try_clone() } else { None };
+        let method = request.method().clone();
+        let target = log_target(request.url());
+
+        let started = Instant::now();
+        let result = client.execute(request).await;
+        if policy.log_requests {
+            match &result {
+                Ok(resp) => {
+                    let status = resp.status().as_u16();
+                    tracing::debug!("{} {} -> {} in {:?}", method, target, status, started.elapsed());
+                }
+                Err(e) => {
+                    tracing::debug!("{} {} failed after {:?}: {}", method, target, started.elapsed(), e);
+                }
+            }
+        }
+
+        let transient = match &result {
+            Ok(resp) => is_transient(resp.status()),
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+        let delay = Duration::from_millis(policy.retry_delay);
+        if transient && retry.is_some() && deadline.is_some_and(|deadline| Instant::now() + delay >= deadline) {
+            tracing::debug!("Not retrying {} {}: the search deadline is near", method, target);
+            return result;
+        }
+        match retry {
+            Some(next) if transient => {
+                attempt += 1;
+                tracing::debug!("Retrying {} {} (attempt {})", method, target, attempt + 1);
+                tokio::time::sleep(delay).await;
+                request = next;
+            }
+            _ => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode as AxumStatus, routing::any, Router};
+    use reqwest::Client;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// Collects formatted log lines for inspection.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Answers 503 to the first request and 200 afterwards.
+    async fn flaky_server(hits: Arc<AtomicU32>) -> String {
+        let app = Router::new().route(
+            "/flaky",
+            any(move || {
+                let hits = hits.clone();
+                async move {
+                    if hits.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (AxumStatus::SERVICE_UNAVAILABLE, "busy")
+                    } else {
+                        (AxumStatus::OK, "ok")
+                    }
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    /// Echoes the request headers back as JSON.
+    async fn echo_server() -> String {
+        let app = Router::new().route(
+            "/echo",
+            any(|headers: axum::http::HeaderMap| async move {
+                let headers: std::collections::HashMap<String, String> = headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or_default().to_string()))
+                    .collect();
+                axum::Json(headers)
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_send_normalizes_outbound_headers() {
+        let base = echo_server().await;
+        let client = Client::new();
+        let request = || {
+            client
+                .get(format!("{}/echo", base))
+                .header(REFERER, "https://searx.example/search?q=private")
+                .header(ACCEPT_LANGUAGE, "de-CH,de;q=0.9,fr;q=0.3")
+                .header(ACCEPT, "application/json")
+        };
+
+        let seen: serde_json::Value = send(request()).await.unwrap().json().await.unwrap();
+        assert!(seen.get("referer").is_none());
+        assert_eq!(seen["accept"], NORMALIZED_ACCEPT);
+        assert_eq!(seen["accept-language"], NORMALIZED_ACCEPT_LANGUAGE);
+
+        // Allowlisted headers pass through unchanged.
+        let policy = OutboundSettings {
+            allowed_headers: vec!["Accept".to_string()],
+            ..Default::default()
+        };
+        let seen: serde_json::Value = with_policy(policy, send(request())).await.unwrap().json().await.unwrap();
+        assert_eq!(seen["accept"], "application/json");
+        assert_eq!(seen["accept-language"], NORMALIZED_ACCEPT_LANGUAGE);
+        assert!(seen.get("referer").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_configured_engine_headers_are_not_normalized() {
+        let base = echo_server().await;
+        let mut config = EngineConfig::default();
+        config.headers.insert("Accept-Language".to_string(), "ja-JP".to_string());
+        let request = crate::engines::apply_headers(Client::new().get(format!("{}/echo", base)), &config)
+            .header(ACCEPT, "application/json");
+
+        let policy = engine_policy(&OutboundSettings::default(), &config);
+        let seen: serde_json::Value = with_policy(policy, send(request)).await.unwrap().json().await.unwrap();
+        assert_eq!(seen["accept-language"], "ja-JP");
+        assert_eq!(seen["accept"], NORMALIZED_ACCEPT);
+    }
+
+    #[tokio::test]
+    async fn test_send_logs_and_retries_unavailable_get() {
+        let logs = Captured::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_env_filter("searxng_rs=debug")
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let hits = Arc::new(AtomicU32::new(0));
+        let base = flaky_server(hits.clone()).await;
+        let client = Client::new();
+        let policy = OutboundSettings {
+            retries: 2,
+            retry_delay: 10,
+            ..Default::default()
+        };
+
+        let resp = with_policy(policy.clone(), send(client.get(format!("{}/flaky?q=secret", base))))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), 200);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+
+        let logged = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logged.contains("/flaky -> 503"), "{}", logged);
+        assert!(logged.contains("/flaky -> 200"), "{}", logged);
+        assert!(!logged.contains("secret"), "query strings must not be logged");
+
+        // POSTs are not idempotent, so the 503 is returned as is.
+        hits.store(0, Ordering::SeqCst);
+        let resp = with_policy(policy, send(client.post(format!("{}/flaky", base)))).await.unwrap();
+        assert_eq!(resp.status(), 503);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_does_not_retry_past_deadline() {
+        let hits = Arc::new(AtomicU32::new(0));
+        let base = flaky_server(hits.clone()).await;
+        let policy = OutboundSettings {
+            retries: 2,
+            retry_delay: 200,
+            ..Default::default()
+        };
+
+        // The retry delay would end after the deadline, so the 503 stands.
+        let deadline = Instant::now() + Duration::from_millis(100);
+        let request = send(Client::new().get(format!("{}/flaky", base)));
+        let resp = with_policy_until(policy, Some(deadline), request).await.unwrap();
+        assert_eq!(resp.status(), 503);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,23 +1,90 @@
 pub mod aggregator;
+pub mod arxiv;
+pub mod brave;
+pub mod cache;
 pub mod circuit_breaker;
+pub mod cratesio;
+pub mod crossref;
+pub mod dictionary;
 pub mod duckduckgo;
 pub mod dummy;
 pub mod error;
 pub mod bing;
+pub mod genius;
 pub mod google;
+pub mod host_limit;
+pub mod http;
+pub mod mojeek;
+pub mod opengraph;
+pub mod openstreetmap;
+pub mod page_dedup;
+pub mod podcasts;
+pub mod pypi;
 pub mod qwant;
 pub mod reddit;
 pub mod registry;
+pub mod searxng;
+pub mod selectors;
+pub mod shopping;
+pub mod stackexchange;
+pub mod startpage;
+pub mod tls_pinning;
+pub mod wikidata;
 pub mod wikipedia;
+pub mod wiktionary;
+pub mod yandex;
+pub mod youtube;
 
 use crate::config::EngineConfig;
 use crate::models::{SearchQuery, SearchResult};
 use async_trait::async_trait;
 use error::EngineError;
-use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, RequestBuilder};
+use serde::Serialize;
+use std::time::Duration;
 
 pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (compatible; SearXNG/1.0; +https://github.com/searxng/searxng)";
 
+/// What an engine talks to, for the preferences and about pages.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EngineAbout {
+    /// Home page of the upstream service.
+    pub website: Option<String>,
+    /// Whether the engine uses a published API rather than scraping pages.
+    pub use_official_api: bool,
+    /// Format the upstream answers in, e.g. `HTML` or `JSON`.
+    pub results: &'static str,
+    pub privacy_policy: Option<String>,
+}
+
+impl EngineAbout {
+    pub fn new(website: &str, use_official_api: bool, results: &'static str) -> Self {
+        Self {
+            website: Some(website.to_string()),
+            use_official_api,
+            results,
+            privacy_policy: None,
+        }
+    }
+
+    pub fn with_privacy_policy(mut self, url: &str) -> Self {
+        self.privacy_policy = Some(url.to_string());
+        self
+    }
+}
+
+impl Default for EngineAbout {
+    fn default() -> Self {
+        Self {
+            website: None,
+            use_official_api: false,
+            results: "HTML",
+            privacy_policy: None,
+        }
+    }
+}
+
 #[async_trait]
 pub trait SearchEngine: Send + Sync {
     /// Unique identifier for the engine (e.g. "google", "ddg").
@@ -36,6 +103,43 @@ pub trait SearchEngine: Send + Sync {
         1.0
     }
 
+    /// Upstream service details for the engine listing.
+    fn about(&self) -> EngineAbout {
+        EngineAbout::default()
+    }
+
+    /// Whether the engine passes the safe search level on to its upstream.
+    fn supports_safesearch(&self) -> bool {
+        false
+    }
+
+    /// Whether the engine needs an API token in `EngineConfig.tokens` to query its upstream.
+    fn requires_token(&self) -> bool {
+        false
+    }
+
+    /// Whether the engine can serve pages after the first. Engines that cannot
+    /// are skipped for `page > 1`.
+    fn paging(&self) -> bool {
+        true
+    }
+
+    /// Last page the upstream serves. Later pages are requested as this one.
+    fn max_page(&self) -> Option<u32> {
+        None
+    }
+
+    /// Primary language subtags the engine can serve, e.g. `de`. `None` means
+    /// any language; otherwise queries for other languages skip the engine.
+    fn supported_languages(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// URL requested at startup to open a pooled connection to the engine's host.
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        None
+    }
+
     /// Perform the search.
     async fn search(
         &self,
@@ -43,14 +147,223 @@ pub trait SearchEngine: Send + Sync {
         client: &Client,
         config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError>;
+
+    /// Query completions for the autocomplete endpoint, best first.
+    async fn suggest(
+        &self,
+        _query: &SearchQuery,
+        _client: &Client,
+        _config: &EngineConfig,
+    ) -> Result<Vec<String>, EngineError> {
+        Ok(vec![])
+    }
 }
 
-pub fn create_client(user_agent: &str, proxy: Option<&str>) -> reqwest::Result<Client> {
-    let mut builder = Client::builder().user_agent(user_agent);
+/// Parses a `Retry-After` value given in seconds. The HTTP-date form is not supported.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    value.trim().parse::<u64>().ok().map(Duration::from_secs)
+}
+
+/// Returns `EngineError::RateLimited` with the `Retry-After` hint for a 429, or
+/// `EngineError::Http` carrying the upstream status for other unsuccessful responses.
+pub fn error_for_status(engine: &str, resp: &reqwest::Response) -> Result<(), EngineError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = resp
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(parse_retry_after);
+        return Err(EngineError::RateLimited { retry_after });
+    }
+    Err(EngineError::Http {
+        status: status.as_u16(),
+        engine: engine.to_string(),
+    })
+}
+
+/// `EngineConfig.extra` key naming the charset an engine's responses use,
+/// e.g. `gbk` or `shift_jis`, when the upstream mislabels or omits it.
+pub const ENCODING_KEY: &str = "encoding";
+
+/// Decodes `bytes` with the encoding named by `label`. Invalid sequences are
+/// replaced rather than failing the whole response.
+pub fn decode_body(bytes: &[u8], label: &str) -> Result<String, EngineError> {
+    let encoding = encoding_rs::Encoding::for_label(label.trim().as_bytes())
+        .ok_or_else(|| EngineError::Parsing(format!("Unknown encoding: {}", label)))?;
+    let (text, _, _) = encoding.decode(bytes);
+    Ok(text.into_owned())
+}
+
+/// Reads the response body as text, using the engine's configured encoding if
+/// any and reqwest's charset detection otherwise.
+pub async fn response_text(resp: reqwest::Response, config: &EngineConfig) -> Result<String, EngineError> {
+    match config.extra.get(ENCODING_KEY) {
+        Some(label) => decode_body(&resp.bytes().await?, label),
+        None => Ok(resp.text().await?),
+    }
+}
+
+/// Suggestions from an OpenSearch suggestions response, `[query, [suggestion, ...], ...]`.
+pub fn parse_opensearch_suggestions(body: &serde_json::Value) -> Vec<String> {
+    body[1]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|suggestion| suggestion.as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Resolves a possibly relative or protocol-relative URL against the engine's base URL.
+pub fn resolve_url(base: &str, href: &str) -> Option<String> {
+    let base = url::Url::parse(base).ok()?;
+    base.join(href).ok().map(|url| url.to_string())
+}
+
+/// Applies the headers configured for the engine, replacing any set earlier on the builder.
+/// Headers are added in name order so requests are reproducible.
+pub fn apply_headers(builder: RequestBuilder, config: &EngineConfig) -> RequestBuilder {
+    let mut configured: Vec<(&String, &String)> = config.headers.iter().collect();
+    configured.sort();
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in configured {
+        match (HeaderName::from_bytes(name.as_bytes()), HeaderValue::from_str(value)) {
+            (Ok(name), Ok(value)) => {
+                headers.insert(name, value);
+            }
+            _ => tracing::warn!("Ignoring invalid configured header: {}", name),
+        }
+    }
+    builder.headers(headers)
+}
+
+fn client_builder(user_agent: &str, proxy: Option<&str>) -> reqwest::Result<reqwest::ClientBuilder> {
+    // Redirects must not tell the next host which URL, and so which query, led there.
+    let mut builder = Client::builder().user_agent(user_agent).referer(false);
 
     if let Some(proxy_url) = proxy {
         builder = builder.proxy(reqwest::Proxy::all(proxy_url)?);
     }
 
-    builder.build()
+    Ok(builder)
+}
+
+pub fn create_client(user_agent: &str, proxy: Option<&str>) -> reqwest::Result<Client> {
+    client_builder(user_agent, proxy)?.build()
+}
+
+/// Whether the engine needs a dedicated client instead of the shared one.
+pub fn needs_engine_client(config: &EngineConfig) -> bool {
+    config.proxy.is_some() || config.extra.contains_key(tls_pinning::TLS_PINS_KEY)
+}
+
+/// Builds a client honoring the engine's proxy and TLS pinning configuration.
+pub fn create_engine_client(config: &EngineConfig) -> anyhow::Result<Client> {
+    let mut builder = client_builder(DEFAULT_USER_AGENT, config.proxy.as_deref())?;
+
+    if let Some(pins) = config.extra.get(tls_pinning::TLS_PINS_KEY) {
+        let pins = tls_pinning::parse_pins(pins).map_err(anyhow::Error::msg)?;
+        builder = builder.use_preconfigured_tls(tls_pinning::pinned_tls_config(pins)?);
+    }
+
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_opensearch_suggestions() {
+        let body = serde_json::json!(["rust", ["rust async", 42, "rust book"], [], {}]);
+        assert_eq!(parse_opensearch_suggestions(&body), vec!["rust async", "rust book"]);
+        assert!(parse_opensearch_suggestions(&serde_json::json!({"error": "nope"})).is_empty());
+    }
+
+    #[test]
+    fn test_error_for_status_surfaces_upstream_status() {
+        let resp = reqwest::Response::from(
+            axum::http::Response::builder()
+                .status(503)
+                .body("Service Unavailable")
+                .unwrap(),
+        );
+
+        match error_for_status("mock", &resp) {
+            Err(EngineError::Http { status, engine }) => {
+                assert_eq!(status, 503);
+                assert_eq!(engine, "mock");
+            }
+            other => panic!("Expected Http error, got {:?}", other),
+        }
+
+        let ok = reqwest::Response::from(axum::http::Response::builder().status(200).body("").unwrap());
+        assert!(error_for_status("mock", &ok).is_ok());
+    }
+
+    #[test]
+    fn test_error_for_status_reads_retry_after() {
+        let resp = reqwest::Response::from(
+            axum::http::Response::builder()
+                .status(429)
+                .header("Retry-After", "30")
+                .body("")
+                .unwrap(),
+        );
+        match error_for_status("mock", &resp) {
+            Err(EngineError::RateLimited { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(30)));
+            }
+            other => panic!("Expected RateLimited error, got {:?}", other),
+        }
+
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn test_decode_body_with_configured_encoding() {
+        // "中文搜索" in GBK.
+        let gbk = [0xD6, 0xD0, 0xCE, 0xC4, 0xCB, 0xD1, 0xCB, 0xF7];
+        assert_eq!(decode_body(&gbk, "gbk").unwrap(), "中文搜索");
+        assert_eq!(decode_body(&gbk, " GBK ").unwrap(), "中文搜索");
+        assert!(matches!(decode_body(&gbk, "klingon"), Err(EngineError::Parsing(_))));
+    }
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(
+            resolve_url("https://en.wikipedia.org/w/api.php", "/wiki/Foo").as_deref(),
+            Some("https://en.wikipedia.org/wiki/Foo")
+        );
+        assert_eq!(
+            resolve_url("https://www.example.com/search", "//cdn.example.com/x").as_deref(),
+            Some("https://cdn.example.com/x")
+        );
+        assert_eq!(
+            resolve_url("https://www.example.com/search", "https://other.com/page").as_deref(),
+            Some("https://other.com/page")
+        );
+    }
+
+    #[test]
+    fn test_apply_headers_overrides_engine_headers() {
+        let mut config = EngineConfig::default();
+        config.headers.insert("Accept".to_string(), "application/json".to_string());
+        config.headers.insert("User-Agent".to_string(), "custom-agent".to_string());
+
+        let client = Client::new();
+        let builder = client
+            .get("https://example.com")
+            .header("User-Agent", DEFAULT_USER_AGENT);
+        let request = apply_headers(builder, &config).build().unwrap();
+
+        assert_eq!(request.headers()["accept"], "application/json");
+        let user_agents: Vec<_> = request.headers().get_all("user-agent").iter().collect();
+        assert_eq!(user_agents, vec!["custom-agent"]);
+    }
 }
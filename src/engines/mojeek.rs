@@ -0,0 +1,136 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+
+const SEARCH_URL: &str = "https://www.mojeek.com/search";
+
+/// Mojeek, an independent crawler-based search engine.
+pub struct Mojeek;
+
+impl Mojeek {
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new("ul.results-standard li", "h2 a", "h2 a", "p.s")
+    }
+
+    /// Mojeek's `s` is the offset of the first result.
+    fn params(query: &SearchQuery) -> Vec<(&'static str, String)> {
+        vec![
+            ("q", query.q.clone()),
+            ("s", (query.page.saturating_sub(1) * 10).to_string()),
+            ("safe", if query.safesearch > 0 { "1" } else { "0" }.to_string()),
+        ]
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        parse_with_selectors(html, selectors, SEARCH_URL, &self.id())
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Mojeek {
+    fn id(&self) -> String {
+        "mojeek".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Mojeek".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.mojeek.com", false, "HTML")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.mojeek.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let request = client.get(SEARCH_URL).query(&Self::params(query));
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    const FIXTURE: &str = r#"
+        <html><body>
+        <div class="results">
+            <ul class="results-standard">
+                <li class="r1">
+                    <a class="ob" href="https://www.rust-lang.org/">www.rust-lang.org</a>
+                    <h2><a class="title" href="https://www.rust-lang.org/">Rust Programming Language</a></h2>
+                    <p class="s">A language empowering everyone to build reliable and efficient software.</p>
+                </li>
+                <li class="r2">
+                    <h2>Heading without a link</h2>
+                    <p class="s">Skipped.</p>
+                </li>
+                <li class="r3">
+                    <h2><a class="title" href="https://doc.rust-lang.org/book/">The Rust Programming Language</a></h2>
+                </li>
+            </ul>
+        </div>
+        <ul class="related"><li><h2><a href="https://example.com/">Not a result</a></h2></li></ul>
+        </body></html>
+    "#;
+
+    #[test]
+    fn test_parse_results_fixture() {
+        let results = Mojeek.parse_results(FIXTURE, &Mojeek::selectors()).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text.starts_with("A language empowering")));
+
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+        assert!(matches!(&results[1].content, ResultContent::Text(text) if text.is_empty()));
+    }
+
+    #[test]
+    fn test_params_use_result_offset_and_safe() {
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            page: 3,
+            safesearch: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            Mojeek::params(&query),
+            vec![
+                ("q", "rust".to_string()),
+                ("s", "20".to_string()),
+                ("safe", "1".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,193 @@
+use crate::config::SearchSettings;
+use crate::engines::resolve_url;
+use crate::models::{ResultContent, SearchResult};
+use crate::web::fetch::fetch_untrusted;
+use scraper::{Html, Selector};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+
+/// Bytes of a page read while looking for its OpenGraph tags.
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+
+/// OpenGraph tags of a page, falling back to the plain description meta tag.
+#[derive(Debug, Default, PartialEq)]
+pub struct OpenGraph {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+}
+
+fn meta_content(document: &Html, css: &str) -> Option<String> {
+    let selector = Selector::parse(css).ok()?;
+    document
+        .select(&selector)
+        .filter_map(|meta| meta.value().attr("content"))
+        .map(str::trim)
+        .find(|content| !content.is_empty())
+        .map(|content| content.to_string())
+}
+
+/// Reads the OpenGraph tags of `html`, resolving a relative image against `page_url`.
+pub fn parse_opengraph(html: &str, page_url: &str) -> OpenGraph {
+    let document = Html::parse_document(html);
+    OpenGraph {
+        title: meta_content(&document, r#"meta[property="og:title"]"#),
+        description: meta_content(&document, r#"meta[property="og:description"]"#)
+            .or_else(|| meta_content(&document, r#"meta[name="description"]"#)),
+        image: meta_content(&document, r#"meta[property="og:image"]"#).and_then(|image| resolve_url(page_url, &image)),
+    }
+}
+
+fn lacks_snippet(result: &SearchResult) -> bool {
+    matches!(&result.content, ResultContent::Text(text) if text.trim().is_empty())
+}
+
+/// Fills in the snippet, and the title and thumbnail where missing, of the
+/// top `opengraph_max_results` results that have no snippet, from the
+/// OpenGraph tags of their pages. Pages that fail to load are left as they are.
+///
+/// Result URLs come from upstreams, so pages on private hosts are only fetched
+/// with `allow_private_hosts`.
+pub async fn enrich(results: &mut [SearchResult], settings: &SearchSettings, allow_private_hosts: bool) {
+    let limit = Arc::new(Semaphore::new(settings.opengraph_concurrency.max(1)));
+    let timeout = Duration::from_millis(settings.opengraph_timeout);
+
+    let mut join_set = JoinSet::new();
+    for (index, result) in results.iter().enumerate().take(settings.opengraph_max_results) {
+        if !lacks_snippet(result) {
+            continue;
+        }
+        let url = result.url.clone();
+        let limit = limit.clone();
+        join_set.spawn(async move {
+            let _permit = limit.acquire_owned().await.ok()?;
+            match fetch_untrusted(&url, MAX_PAGE_BYTES, timeout, allow_private_hosts).await {
+                Ok(page) => Some((index, parse_opengraph(&String::from_utf8_lossy(&page.body), &url))),
+                Err(e) => {
+                    tracing::debug!("OpenGraph fetch for {} failed: {}", url, e);
+                    None
+                }
+            }
+        });
+    }
+
+    while let Some(joined) = join_set.join_next().await {
+        let Ok(Some((index, og))) = joined else {
+            continue;
+        };
+        let result = &mut results[index];
+        // Snippets are rendered as HTML and this runs after aggregation
+        // sanitized them, so the page's text gets the same treatment.
+        if let Some(description) = og.description {
+            result.content = ResultContent::Text(ammonia::clean(&description));
+        }
+        if result.title.trim().is_empty() {
+            if let Some(title) = og.title {
+                result.title = title;
+            }
+        }
+        if result.thumbnail().is_none() {
+            if let Some(image) = og.image {
+                result.set_thumbnail(image);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Html as HtmlResponse, routing::get, Router};
+    use std::collections::HashMap;
+
+    const PAGE: &str = r#"<html><head>
+        <meta property="og:title" content="Rust Blog">
+        <meta property="og:description" content="Announcing Rust 1.80.0">
+        <meta property="og:image" content="/images/rust-social.png">
+        <meta name="description" content="Plain description">
+    </head><body></body></html>"#;
+
+    fn result(url: String, title: &str, text: &str) -> SearchResult {
+        SearchResult {
+            url,
+            title: title.to_string(),
+            content: ResultContent::Text(text.to_string()),
+            engines: vec!["mock".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enrich_fills_missing_snippets() {
+        let app = Router::new().route("/post", get(|| async { HtmlResponse(PAGE) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut results = vec![
+            result(format!("{}/post", base), "", ""),
+            result(format!("{}/post?kept", base), "Kept", "Existing snippet"),
+            result(format!("{}/missing", base), "Missing", ""),
+        ];
+        let settings = SearchSettings {
+            enrich_opengraph: true,
+            ..Default::default()
+        };
+        enrich(&mut results, &settings, true).await;
+
+        assert_eq!(results[0].title, "Rust Blog");
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text == "Announcing Rust 1.80.0"));
+        assert_eq!(results[0].thumbnail(), Some(format!("{}/images/rust-social.png", base).as_str()));
+
+        // Results with a snippet are not fetched, and failed fetches change nothing.
+        assert!(matches!(&results[1].content, ResultContent::Text(text) if text == "Existing snippet"));
+        assert!(results[1].thumbnail().is_none());
+        assert!(matches!(&results[2].content, ResultContent::Text(text) if text.is_empty()));
+
+        // The mock upstream is on loopback, like an internal service would be.
+        let mut results = vec![result(format!("{}/post", base), "", "")];
+        enrich(&mut results, &settings, false).await;
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text.is_empty()));
+    }
+
+    #[tokio::test]
+    async fn test_enrich_sanitizes_descriptions() {
+        const HOSTILE: &str = r#"<html><head>
+            <meta property="og:description" content="&lt;img src=x onerror=alert(1)&gt;Tea &amp; biscuits &lt;script&gt;alert(2)&lt;/script&gt;">
+        </head></html>"#;
+        let app = Router::new().route("/post", get(|| async { HtmlResponse(HOSTILE) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let mut results = vec![result(format!("{}/post", base), "Post", "")];
+        let settings = SearchSettings {
+            enrich_opengraph: true,
+            ..Default::default()
+        };
+        enrich(&mut results, &settings, true).await;
+
+        let ResultContent::Text(text) = &results[0].content else {
+            panic!("expected a text snippet");
+        };
+        assert!(!text.contains("onerror"), "{}", text);
+        assert!(!text.contains("<script"), "{}", text);
+        assert!(text.contains("Tea &amp; biscuits"), "{}", text);
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_meta_description() {
+        let og = parse_opengraph(r#"<meta name="description" content="Only this">"#, "https://example.com/");
+        assert_eq!(
+            og,
+            OpenGraph {
+                title: None,
+                description: Some("Only this".to_string()),
+                image: None,
+            }
+        );
+    }
+}
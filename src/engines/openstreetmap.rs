@@ -0,0 +1,221 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{
+    apply_headers, error_for_status, http, EngineAbout, SearchEngine, DEFAULT_USER_AGENT,
+};
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const NOMINATIM_URL: &str = "https://nominatim.openstreetmap.org/search";
+
+/// Results requested when `extra.limit` is not configured.
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Places from OpenStreetMap's Nominatim geocoder, as map results.
+///
+/// Nominatim's usage policy requires an identifying User-Agent, so one is
+/// always sent, and it has no paging, so only the first page returns results.
+pub struct OpenStreetMap;
+
+/// Reads a hit's coordinates, which Nominatim returns as strings.
+pub fn parse_coordinates(item: &Value) -> Option<(f64, f64)> {
+    let lat = item["lat"].as_str().and_then(|s| s.parse::<f64>().ok());
+    let lon = item["lon"].as_str().and_then(|s| s.parse::<f64>().ok());
+    lat.zip(lon)
+}
+
+/// Zoom level showing the whole place: from its type where that is telling,
+/// otherwise wider for more important places.
+fn zoom_for(item: &Value) -> u8 {
+    let kind = item["addresstype"].as_str().or_else(|| item["type"].as_str()).unwrap_or_default();
+    match kind {
+        "continent" => 3,
+        "country" => 5,
+        "state" | "region" | "province" => 7,
+        "county" | "administrative" => 9,
+        "city" => 11,
+        "town" | "municipality" => 13,
+        "village" | "suburb" | "quarter" | "neighbourhood" => 15,
+        "road" | "street" | "house" | "building" | "amenity" | "shop" | "tourism" => 18,
+        _ => {
+            let importance = item["importance"].as_f64().unwrap_or(0.0).clamp(0.0, 1.0);
+            (17.0 - importance * 12.0).round() as u8
+        }
+    }
+}
+
+impl OpenStreetMap {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body.as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let Some((latitude, longitude)) = parse_coordinates(item) else {
+                continue;
+            };
+            let display_name = item["display_name"].as_str().unwrap_or_default();
+            let title = item["name"]
+                .as_str()
+                .filter(|name| !name.is_empty())
+                .or_else(|| display_name.split(',').next())
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            if title.is_empty() {
+                continue;
+            }
+            let zoom = zoom_for(item);
+
+            let url = match (item["osm_type"].as_str(), item["osm_id"].as_u64()) {
+                (Some(osm_type), Some(osm_id)) => format!("https://www.openstreetmap.org/{}/{}", osm_type, osm_id),
+                _ => format!(
+                    "https://www.openstreetmap.org/?mlat={}&mlon={}#map={}/{}/{}",
+                    latitude, longitude, zoom, latitude, longitude
+                ),
+            };
+
+            let mut metadata = HashMap::new();
+            if !display_name.is_empty() {
+                metadata.insert("address".to_string(), display_name.to_string());
+            }
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Map {
+                    latitude,
+                    longitude,
+                    zoom: Some(zoom),
+                },
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for OpenStreetMap {
+    fn id(&self) -> String {
+        "openstreetmap".to_string()
+    }
+
+    fn name(&self) -> String {
+        "OpenStreetMap".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.openstreetmap.org", true, "JSON")
+            .with_privacy_policy("https://osmfoundation.org/wiki/Privacy_Policy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["map".to_string()]
+    }
+
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://nominatim.openstreetmap.org/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let limit = config
+            .extra
+            .get("limit")
+            .and_then(|limit| limit.parse::<u32>().ok())
+            .unwrap_or(DEFAULT_LIMIT);
+
+        let mut params = vec![
+            ("q", query.q.clone()),
+            ("format", "json".to_string()),
+            ("limit", limit.to_string()),
+        ];
+        if let Some(language) = query.language_code() {
+            params.push(("accept-language", language));
+        }
+
+        let request = client
+            .get(NOMINATIM_URL)
+            .query(&params)
+            .header(reqwest::header::USER_AGENT, DEFAULT_USER_AGENT);
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!([
+            {
+                "osm_type": "relation",
+                "osm_id": 7444,
+                "lat": "48.8588897",
+                "lon": "2.3200410",
+                "name": "Paris",
+                "display_name": "Paris, Île-de-France, France métropolitaine, France",
+                "addresstype": "city",
+                "importance": 0.88
+            },
+            {
+                "lat": "48.8583701",
+                "lon": "2.2944813",
+                "display_name": "Tour Eiffel, 5, Avenue Anatole France, Paris, France",
+                "type": "attraction",
+                "importance": 0.6
+            },
+            {
+                "display_name": "No coordinates"
+            }
+        ]);
+
+        let results = OpenStreetMap.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        let paris = &results[0];
+        assert_eq!(paris.title, "Paris");
+        assert_eq!(paris.url, "https://www.openstreetmap.org/relation/7444");
+        assert_eq!(paris.metadata["address"], "Paris, Île-de-France, France métropolitaine, France");
+        match paris.content {
+            ResultContent::Map { latitude, longitude, zoom } => {
+                assert!((latitude - 48.8588897).abs() < 1e-9);
+                assert!((longitude - 2.3200410).abs() < 1e-9);
+                assert_eq!(zoom, Some(11));
+            }
+            ref other => panic!("Expected map, got {:?}", other),
+        }
+
+        // Untyped places fall back to importance, and to a coordinate link.
+        let tower = &results[1];
+        assert_eq!(tower.title, "Tour Eiffel");
+        assert!(tower.url.starts_with("https://www.openstreetmap.org/?mlat=48.8583701&mlon=2.2944813"));
+        assert!(matches!(tower.content, ResultContent::Map { zoom: Some(10), .. }));
+    }
+}
@@ -0,0 +1,190 @@
+use crate::models::{SearchQuery, SearchResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long the URLs shown for a query are remembered.
+const PAGE_TTL: Duration = Duration::from_secs(600);
+/// How often expired queries are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+/// Queries remembered at most; the least recently paged one is dropped to
+/// make room.
+const MAX_QUERIES: usize = 4096;
+
+struct SeenPages {
+    /// Page number to the URLs shown on it.
+    pages: HashMap<u32, Vec<String>>,
+    updated: Instant,
+}
+
+struct SeenQueries {
+    by_query: HashMap<String, SeenPages>,
+    swept: Instant,
+}
+
+/// Remembers which URLs were shown on earlier pages of a query so later pages
+/// can drop results that engines repeat across their pagination offsets.
+///
+/// Entries are keyed by everything that identifies the result list except the
+/// page, so every client paging through the same query sees the same pages.
+pub struct PageDedup {
+    seen: Mutex<SeenQueries>,
+    ttl: Duration,
+}
+
+impl Default for PageDedup {
+    fn default() -> Self {
+        Self::new(PAGE_TTL)
+    }
+}
+
+impl PageDedup {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            seen: Mutex::new(SeenQueries {
+                by_query: HashMap::new(),
+                swept: Instant::now(),
+            }),
+            ttl,
+        }
+    }
+
+    fn key(query: &SearchQuery) -> String {
+        let engines = query.engines.as_deref().or(query.cookie_engines.as_deref()).unwrap_or_default();
+        format!(
+            "{}\u{1f}{}\u{1f}{}\u{1f}{}\u{1f}{}\u{1f}{}\u{1f}{}",
+            query.q, query.language, query.country, query.categories, query.time_range, query.safesearch, engines
+        )
+    }
+
+    /// Drops results already shown on an earlier page of the same query and
+    /// records the remaining ones as shown on `query.page`.
+    pub fn filter(&self, query: &SearchQuery, results: Vec<SearchResult>) -> Vec<SearchResult> {
+        self.filter_at(query, results, Instant::now())
+    }
+
+    fn filter_at(&self, query: &SearchQuery, results: Vec<SearchResult>, now: Instant) -> Vec<SearchResult> {
+        let Ok(mut seen) = self.seen.lock() else {
+            return results;
+        };
+
+        if now.saturating_duration_since(seen.swept) >= SWEEP_INTERVAL {
+            seen.by_query.retain(|_, entry| now.saturating_duration_since(entry.updated) < self.ttl);
+            seen.swept = now;
+        }
+
+        let key = Self::key(query);
+        if let Some(entry) = seen.by_query.get(&key) {
+            if now.saturating_duration_since(entry.updated) >= self.ttl {
+                seen.by_query.remove(&key);
+            }
+        }
+        if !seen.by_query.contains_key(&key) && seen.by_query.len() >= MAX_QUERIES {
+            let oldest = seen
+                .by_query
+                .iter()
+                .min_by_key(|(_, entry)| entry.updated)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                seen.by_query.remove(&oldest);
+            }
+        }
+
+        let entry = seen.by_query.entry(key).or_insert_with(|| SeenPages {
+            pages: HashMap::new(),
+            updated: now,
+        });
+        entry.updated = now;
+
+        let earlier: HashSet<&String> = entry
+            .pages
+            .iter()
+            .filter(|(page, _)| **page < query.page)
+            .flat_map(|(_, urls)| urls)
+            .collect();
+
+        let results: Vec<SearchResult> = results
+            .into_iter()
+            .filter(|res| !earlier.contains(&res.url))
+            .collect();
+
+        entry
+            .pages
+            .insert(query.page, results.iter().map(|res| res.url.clone()).collect());
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    fn result(url: &str) -> SearchResult {
+        SearchResult {
+            url: url.to_string(),
+            title: url.to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["test".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_repeated_url_filtered_from_later_page() {
+        let dedup = PageDedup::default();
+        let page = |page: u32| SearchQuery {
+            q: "rust".to_string(),
+            page,
+            ..Default::default()
+        };
+
+        let first = dedup.filter(&page(1), vec![result("https://a.example/"), result("https://b.example/")]);
+        assert_eq!(first.len(), 2);
+
+        let second = dedup.filter(&page(2), vec![result("https://b.example/"), result("https://c.example/")]);
+        let urls: Vec<&str> = second.iter().map(|r| r.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://c.example/"]);
+
+        // Reloading page 1 is unaffected by what page 2 showed.
+        let again = dedup.filter(&page(1), vec![result("https://a.example/"), result("https://b.example/")]);
+        assert_eq!(again.len(), 2);
+
+        // A different query keeps its own history.
+        let other = SearchQuery {
+            q: "go".to_string(),
+            page: 2,
+            ..Default::default()
+        };
+        assert_eq!(dedup.filter(&other, vec![result("https://b.example/")]).len(), 1);
+    }
+
+    #[test]
+    fn test_expired_queries_are_swept_and_count_is_bounded() {
+        let dedup = PageDedup::new(Duration::from_secs(30));
+        let start = Instant::now();
+        let query = |q: &str, page: u32| SearchQuery {
+            q: q.to_string(),
+            page,
+            ..Default::default()
+        };
+        let len = || dedup.seen.lock().unwrap().by_query.len();
+
+        dedup.filter_at(&query("old", 1), vec![result("https://a.example/")], start);
+        dedup.filter_at(&query("new", 1), vec![result("https://a.example/")], start + Duration::from_secs(40));
+        // Expired, but not swept until the interval has passed.
+        assert_eq!(len(), 2);
+        // An expired entry is never used, swept or not.
+        let later = start + Duration::from_secs(40);
+        assert_eq!(dedup.filter_at(&query("old", 2), vec![result("https://a.example/")], later).len(), 1);
+
+        dedup.filter_at(&query("new", 2), vec![], start + SWEEP_INTERVAL + Duration::from_secs(50));
+        assert_eq!(len(), 1);
+
+        for i in 0..MAX_QUERIES + 5 {
+            dedup.filter_at(&query(&i.to_string(), 1), vec![], start + SWEEP_INTERVAL + Duration::from_secs(50));
+        }
+        assert_eq!(len(), MAX_QUERIES);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://itunes.apple.com/search";
+
+/// Podcast episode search using the iTunes Search API.
+pub struct Podcasts;
+
+/// Formats milliseconds as `m:ss` or `h:mm:ss`.
+pub fn format_duration(millis: u64) -> String {
+    let total = millis / 1000;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+impl Podcasts {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["results"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let url = match item["trackViewUrl"].as_str().or_else(|| item["collectionViewUrl"].as_str()) {
+                Some(url) => url.to_string(),
+                None => continue,
+            };
+            let title = item["trackName"].as_str().unwrap_or_default().to_string();
+
+            let mut metadata = HashMap::new();
+            if let Some(show) = item["collectionName"].as_str() {
+                metadata.insert("show".to_string(), show.to_string());
+            }
+            if let Some(artwork) = item["artworkUrl160"].as_str() {
+                metadata.insert(metadata_keys::THUMBNAIL.to_string(), artwork.to_string());
+            }
+            if let Some(date) = item["releaseDate"].as_str() {
+                metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), date.to_string());
+            }
+            if let Some(description) = item["shortDescription"].as_str().or_else(|| item["description"].as_str()) {
+                metadata.insert("description".to_string(), description.to_string());
+            }
+
+            let content = match item["episodeUrl"].as_str() {
+                Some(src) => ResultContent::Audio {
+                    src: src.to_string(),
+                    duration: item["trackTimeMillis"].as_u64().map(format_duration),
+                },
+                None => ResultContent::Text(metadata.get("description").cloned().unwrap_or_default()),
+            };
+
+            results.push(SearchResult {
+                url,
+                title,
+                content,
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Podcasts {
+    fn id(&self) -> String {
+        "podcasts".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Podcasts".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://podcasts.apple.com", true, "JSON")
+            .with_privacy_policy("https://www.apple.com/legal/privacy/")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["podcasts".to_string()]
+    }
+
+    fn warmup_url(&self, config: &EngineConfig) -> Option<String> {
+        Some(config.extra.get("base_url").cloned().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()))
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let url = config
+            .extra
+            .get("base_url")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_BASE_URL);
+
+        let limit = 20;
+        let offset = (query.page - 1) * limit;
+
+        let params = [
+            ("term", query.q.clone()),
+            ("media", "podcast".to_string()),
+            ("entity", "podcastEpisode".to_string()),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ];
+
+        let resp = http::send(apply_headers(client.get(url).query(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "resultCount": 2,
+            "results": [
+                {
+                    "trackName": "Episode 1: Ownership",
+                    "collectionName": "Rustacean Station",
+                    "trackViewUrl": "https://podcasts.apple.com/podcast/id1?i=1",
+                    "episodeUrl": "https://cdn.example.com/ep1.mp3",
+                    "trackTimeMillis": 3723000,
+                    "artworkUrl160": "https://cdn.example.com/art.jpg",
+                    "releaseDate": "2024-02-01T08:00:00Z"
+                },
+                {
+                    "trackName": "Trailer",
+                    "collectionViewUrl": "https://podcasts.apple.com/podcast/id2",
+                    "description": "Coming soon"
+                },
+                {
+                    "trackName": "No URL"
+                }
+            ]
+        });
+
+        let results = Podcasts.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        match &results[0].content {
+            ResultContent::Audio { src, duration } => {
+                assert_eq!(src, "https://cdn.example.com/ep1.mp3");
+                assert_eq!(duration.as_deref(), Some("1:02:03"));
+            }
+            other => panic!("Unexpected content {:?}", other),
+        }
+        assert_eq!(results[0].metadata["show"], "Rustacean Station");
+        assert_eq!(results[0].metadata["published_date"], "2024-02-01T08:00:00Z");
+
+        assert!(matches!(&results[1].content, ResultContent::Text(text) if text == "Coming soon"));
+        assert_eq!(format_duration(95_000), "1:35");
+    }
+}
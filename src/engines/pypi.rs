@@ -0,0 +1,260 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode, Url};
+use scraper::{Html, Selector};
+use serde_json::Value;
+use std::collections::HashMap;
+
+const DEFAULT_BASE_URL: &str = "https://pypi.org";
+
+/// Python packages from PyPI. PyPI has no search API, so the query is looked
+/// up as a package name: first in the JSON API, then, normalized, in the
+/// simple index.
+pub struct PyPi;
+
+/// PEP 503 name normalization, also treating whitespace as a separator so
+/// that `requests oauthlib` finds `requests-oauthlib`.
+pub fn normalize_name(name: &str) -> String {
+    name.split(|c: char| c == '-' || c == '_' || c == '.' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// Version of a distribution file name, e.g. `2.0.1` for
+/// `requests_oauthlib-2.0.1-py2.py3-none-any.whl` or `requests-oauthlib-2.0.1.tar.gz`.
+fn file_version(filename: &str) -> Option<&str> {
+    if let Some(wheel) = filename.strip_suffix(".whl") {
+        return wheel.split('-').nth(1);
+    }
+    let sdist = filename
+        .strip_suffix(".tar.gz")
+        .or_else(|| filename.strip_suffix(".zip"))?;
+    sdist.rsplit_once('-').map(|(_, version)| version)
+}
+
+fn project_url(name: &str) -> String {
+    format!("https://pypi.org/project/{}/", name)
+}
+
+impl PyPi {
+    /// Builds the result from a `/pypi/{name}/json` response.
+    fn parse_project(&self, body: &Value) -> Option<SearchResult> {
+        let info = &body["info"];
+        let name = info["name"].as_str()?;
+
+        let mut metadata = HashMap::new();
+        for key in ["version", "author"] {
+            if let Some(value) = info[key].as_str().map(str::trim).filter(|v| !v.is_empty()) {
+                metadata.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        let summary = info["summary"].as_str().unwrap_or_default().trim().to_string();
+
+        Some(SearchResult {
+            url: project_url(name),
+            title: name.to_string(),
+            content: ResultContent::Text(summary),
+            engines: vec![self.id()],
+            score: 1.0,
+            metadata,
+        })
+    }
+
+    /// Builds the result from a simple index page, which only lists files, so
+    /// the version is taken from the last one.
+    fn parse_simple_index(&self, name: &str, html: &str) -> SearchResult {
+        let document = Html::parse_document(html);
+        let link = Selector::parse("a").unwrap();
+
+        let mut metadata = HashMap::new();
+        let version = document
+            .select(&link)
+            .filter_map(|a| file_version(a.text().collect::<String>().trim()).map(str::to_string))
+            .next_back();
+        if let Some(version) = version {
+            metadata.insert("version".to_string(), version);
+        }
+
+        SearchResult {
+            url: project_url(name),
+            title: name.to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec![self.id()],
+            score: 1.0,
+            metadata,
+        }
+    }
+}
+
+#[async_trait]
+impl SearchEngine for PyPi {
+    fn id(&self) -> String {
+        "pypi".to_string()
+    }
+
+    fn name(&self) -> String {
+        "PyPI".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://pypi.org", true, "JSON")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["it".to_string()]
+    }
+
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, config: &EngineConfig) -> Option<String> {
+        Some(config.extra.get("base_url").cloned().unwrap_or_else(|| DEFAULT_BASE_URL.to_string()))
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let name = query.normalized_q();
+        if name.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let base_url = config
+            .extra
+            .get("base_url")
+            .map(String::as_str)
+            .unwrap_or(DEFAULT_BASE_URL);
+        let endpoint = |segments: &[&str]| -> Result<Url, EngineError> {
+            let mut url = Url::parse(base_url)
+                .map_err(|e| EngineError::Parsing(format!("Invalid PyPI base URL: {}", e)))?;
+            url.path_segments_mut()
+                .map_err(|_| EngineError::Parsing("Invalid PyPI base URL".to_string()))?
+                .pop_if_empty()
+                .extend(segments);
+            Ok(url)
+        };
+
+        let project_json = endpoint(&["pypi", &name, "json"])?;
+        let resp = http::send(apply_headers(client.get(project_json), config)).await?;
+        if resp.status() != StatusCode::NOT_FOUND {
+            error_for_status(&self.id(), &resp)?;
+            let body: Value = resp.json().await?;
+            return Ok(self.parse_project(&body).into_iter().collect());
+        }
+
+        let normalized = normalize_name(&name);
+        let simple_index = endpoint(&["simple", &normalized, ""])?;
+        let resp = http::send(apply_headers(client.get(simple_index), config)).await?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        error_for_status(&self.id(), &resp)?;
+        let html = resp.text().await?;
+
+        Ok(vec![self.parse_simple_index(&normalized, &html)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Path;
+    use axum::http::StatusCode as AxumStatus;
+    use axum::response::{Html as AxumHtml, IntoResponse};
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+
+    /// Knows `requests` in the JSON API and `requests-oauthlib` only in the simple index.
+    async fn pypi_server() -> String {
+        let app = Router::new()
+            .route(
+                "/pypi/:name/json",
+                get(|Path(name): Path<String>| async move {
+                    if name != "requests" {
+                        return AxumStatus::NOT_FOUND.into_response();
+                    }
+                    Json(json!({
+                        "info": {
+                            "name": "requests",
+                            "summary": "Python HTTP for Humans.",
+                            "version": "2.32.3",
+                            "author": "Kenneth Reitz"
+                        }
+                    }))
+                    .into_response()
+                }),
+            )
+            .route(
+                "/simple/:name/",
+                get(|Path(name): Path<String>| async move {
+                    if name != "requests-oauthlib" {
+                        return AxumStatus::NOT_FOUND.into_response();
+                    }
+                    AxumHtml(
+                        "<html><body><h1>Links for requests-oauthlib</h1>\
+                         <a href=\"/f/1\">requests-oauthlib-1.3.1.tar.gz</a>\
+                         <a href=\"/f/2\">requests_oauthlib-2.0.0-py2.py3-none-any.whl</a>\
+                         </body></html>",
+                    )
+                    .into_response()
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    async fn search(base: &str, q: &str) -> Vec<SearchResult> {
+        let mut config = EngineConfig::default();
+        config.extra.insert("base_url".to_string(), base.to_string());
+        let query = SearchQuery {
+            q: q.to_string(),
+            ..Default::default()
+        };
+        PyPi.search(&query, &Client::new(), &config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exact_match_from_json_api() {
+        let base = pypi_server().await;
+        let results = search(&base, "requests").await;
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://pypi.org/project/requests/");
+        assert_eq!(results[0].metadata["version"], "2.32.3");
+        assert_eq!(results[0].metadata["author"], "Kenneth Reitz");
+        assert!(matches!(
+            &results[0].content,
+            ResultContent::Text(text) if text == "Python HTTP for Humans."
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_simple_index_then_not_found() {
+        let base = pypi_server().await;
+
+        let results = search(&base, "Requests OAuthlib").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].title, "requests-oauthlib");
+        assert_eq!(results[0].metadata["version"], "2.0.0");
+
+        assert!(search(&base, "no-such-package").await.is_empty());
+    }
+
+    #[test]
+    fn test_normalize_name() {
+        assert_eq!(normalize_name("Django_REST.framework"), "django-rest-framework");
+        assert_eq!(normalize_name("  requests   oauthlib "), "requests-oauthlib");
+    }
+}
@@ -1,6 +1,6 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
 use crate::models::{ResultContent, SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -8,6 +8,28 @@ use std::collections::HashMap;
 
 pub struct Qwant;
 
+impl Qwant {
+    /// Query parameters in the order they are sent.
+    fn params(query: &SearchQuery) -> Vec<(&'static str, String)> {
+        let count = 10;
+        let offset = (query.page - 1) * count;
+
+        let language = if query.language.is_empty() {
+            "en_US"
+        } else {
+            &query.language
+        };
+
+        vec![
+            ("q", query.q.clone()),
+            ("count", count.to_string()),
+            ("offset", offset.to_string()),
+            ("locale", language.to_string()),
+            ("safesearch", query.safesearch.to_string()),
+        ]
+    }
+}
+
 #[async_trait]
 impl SearchEngine for Qwant {
     fn id(&self) -> String {
@@ -18,43 +40,38 @@ impl SearchEngine for Qwant {
         "Qwant".to_string()
     }
 
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.qwant.com", false, "JSON")
+    }
+
     fn categories(&self) -> Vec<String> {
         vec!["general".to_string()]
     }
 
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://api.qwant.com/".to_string())
+    }
+
     async fn search(
         &self,
         query: &SearchQuery,
         client: &Client,
-        _config: &EngineConfig,
+        config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError> {
         let url = "https://api.qwant.com/v3/search/web";
 
-        let count = 10;
-        let offset = (query.page - 1) * count;
-
-        let language = if query.language.is_empty() {
-            "en_US"
-        } else {
-            &query.language
-        };
-
-        let params = [
-            ("q", query.q.as_str()),
-            ("count", &count.to_string()),
-            ("offset", &offset.to_string()),
-            ("locale", language),
-            ("safesearch", &query.safesearch.to_string()),
-        ];
+        let params = Self::params(query);
 
-        let resp = client.get(url)
+        let request = client.get(url)
             .query(&params)
-            .header("User-Agent", crate::engines::DEFAULT_USER_AGENT)
-            .send().await?;
+            .header("User-Agent", crate::engines::DEFAULT_USER_AGENT);
+        let resp = http::send(apply_headers(request, config)).await?;
 
-        if !resp.status().is_success() {
-             return Err(EngineError::Unexpected(anyhow::anyhow!("Qwant returned {}", resp.status())));
-        }
+        error_for_status(&self.id(), &resp)?;
 
         let body: serde_json::Value = resp.json().await?;
 
@@ -103,3 +120,28 @@ impl SearchEngine for Qwant {
         Ok(results)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_are_sent_in_a_stable_order() {
+        let query = SearchQuery {
+            q: "rust lang".to_string(),
+            page: 2,
+            ..Default::default()
+        };
+
+        let request = Client::new()
+            .get("https://api.qwant.com/v3/search/web")
+            .query(&Qwant::params(&query))
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            request.url().query(),
+            Some("q=rust+lang&count=10&offset=10&locale=en_US&safesearch=0")
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
 use crate::models::{ResultContent, SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -18,15 +18,33 @@ impl SearchEngine for Reddit {
         "Reddit".to_string()
     }
 
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.reddit.com", true, "JSON")
+            .with_privacy_policy("https://www.reddit.com/policies/privacy-policy")
+    }
+
     fn categories(&self) -> Vec<String> {
         vec!["general".to_string(), "social media".to_string()]
     }
 
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    /// Reddit pages with `after` cursors, so only the first page is fetched.
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.reddit.com/".to_string())
+    }
+
     async fn search(
         &self,
         query: &SearchQuery,
         client: &Client,
-        _config: &EngineConfig,
+        config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError> {
         let url = "https://www.reddit.com/search.json";
 
@@ -46,15 +64,13 @@ impl SearchEngine for Reddit {
         // Reddit doesn't use simple page numbers, but we'll do our best.
         // For now, let's just fetch the first page or use "after" if we had it.
 
-        let resp = client.get(url)
+        let request = client.get(url)
             .query(&params)
-            // Reddit requires a custom User-Agent to avoid 429
-            .header("User-Agent", "Mozilla/5.0 (compatible; SearXNG-rs/0.1.0; +https://github.com/searxng/searxng-rs)")
-            .send().await?;
+            // Reddit requires a custom User-Agent to avoid 429. Can be overridden via `headers`.
+            .header("User-Agent", "Mozilla/5.0 (compatible; SearXNG-rs/0.1.0; +https://github.com/searxng/searxng-rs)");
+        let resp = http::send(apply_headers(request, config)).await?;
 
-        if !resp.status().is_success() {
-            return Err(EngineError::Unexpected(anyhow::anyhow!("Reddit returned {}", resp.status())));
-        }
+        error_for_status(&self.id(), &resp)?;
 
         let body: serde_json::Value = resp.json().await?;
         let mut results = Vec::new();
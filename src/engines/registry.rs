@@ -1,16 +1,106 @@
 use arc_swap::ArcSwap;
-use crate::config::Settings;
-use crate::engines::aggregator::aggregate;
-use crate::engines::circuit_breaker::CircuitBreaker;
-use crate::engines::{create_client, DEFAULT_USER_AGENT};
-use crate::engines::SearchEngine;
-use crate::models::{SearchQuery, SearchResult};
+use crate::answerers::Answerer;
+use crate::bangs::parse_bangs;
+use crate::config::{EngineConfig, SafesearchMode, Settings};
+use crate::engines::aggregator::{aggregate, enforce_language, is_nsfw, merge_suggestions, normalize_scores};
+use crate::engines::cache::{CacheKey, ResultCache};
+use crate::engines::circuit_breaker::{CircuitBreaker, CircuitBreakerState};
+use crate::engines::error::EngineError;
+use crate::engines::host_limit::HostLimiter;
+use crate::engines::page_dedup::PageDedup;
+use crate::engines::{create_engine_client, http, needs_engine_client, opengraph};
+use crate::engines::{EngineAbout, SearchEngine};
+use crate::metrics::{self, EngineOutcome};
+use crate::models::{Answer, SearchQuery, SearchResult};
+use crate::query::infer_categories;
 use reqwest::Client;
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::task::JoinSet;
+use url::Url;
+
+/// What a single engine task produced.
+struct EngineRun {
+    id: String,
+    /// How long the upstream call took; `None` if the engine was not queried.
+    elapsed: Option<Duration>,
+    /// Whether the engine errored, timed out or was skipped by its circuit breaker.
+    failed: bool,
+    results: Vec<SearchResult>,
+}
+
+/// Reports a failure to an engine's circuit breaker if the engine task is
+/// dropped before reporting an outcome, as when the hard timeout aborts it.
+/// A half-open probe would otherwise stay in flight, and the breaker would
+/// never let another request through.
+struct BreakerGuard {
+    breaker: Arc<Mutex<CircuitBreaker>>,
+    armed: bool,
+}
+
+impl BreakerGuard {
+    fn new(breaker: Arc<Mutex<CircuitBreaker>>) -> Self {
+        Self { breaker, armed: true }
+    }
+
+    /// The task reports its outcome itself.
+    fn disarm(&mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for BreakerGuard {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        if let Ok(mut breaker) = self.breaker.try_lock() {
+            breaker.report_failure();
+            return;
+        }
+        let breaker = self.breaker.clone();
+        if let Ok(handle) = tokio::runtime::Handle::try_current() {
+            handle.spawn(async move { breaker.lock().await.report_failure() });
+        }
+    }
+}
+
+/// Aggregated results of a search along with per-engine diagnostics.
+#[derive(Debug, Default)]
+pub struct SearchOutcome {
+    pub results: Vec<SearchResult>,
+    /// Engine id to upstream latency in milliseconds, for engines that were queried.
+    pub timings: HashMap<String, u64>,
+    /// Notices about results that were filtered out.
+    pub warnings: Vec<String>,
+    /// Engines that errored, timed out or were suspended, sorted by id.
+    pub failed: Vec<String>,
+}
+
+/// Where an enabled engine's upstream can be reached, for warmup and health checks.
+pub struct UpstreamProbe {
+    pub id: String,
+    pub client: Client,
+    pub url: String,
+    pub timeout: Duration,
+}
+
+/// Public view of a registered engine, as listed by `/engines`.
+#[derive(Debug, Serialize)]
+pub struct EngineStatus {
+    pub id: String,
+    pub name: String,
+    pub categories: Vec<String>,
+    /// Ranking weight: the configured weight times the engine's own.
+    pub weight: f64,
+    pub enabled: bool,
+    /// Why the engine is not being queried, if it is not.
+    pub disabled_reason: Option<String>,
+    pub about: EngineAbout,
+}
 
 struct EngineEntry {
     engine: Arc<dyn SearchEngine>,
@@ -20,18 +110,70 @@ struct EngineEntry {
     circuit_breaker: Arc<Mutex<CircuitBreaker>>,
 }
 
+/// Whether an engine's results are safe-search filtered, upstream or here.
+fn applies_safesearch(entry: &EngineEntry, config: &EngineConfig) -> bool {
+    match config.safesearch_mode {
+        SafesearchMode::Passthrough => entry.engine.supports_safesearch(),
+        SafesearchMode::Clientside => true,
+        SafesearchMode::Off => false,
+    }
+}
+
+/// Resolves which engines run for a request.
+///
+/// The `engines` query parameter overrides the preferences cookie for the request;
+/// an empty selection from either source falls back to all eligible engines.
+/// Ids that are not eligible (disabled or outside the query categories) are ignored.
+pub fn resolve_engines(cookie: Option<&str>, param: Option<&str>, eligible: &[String]) -> Vec<String> {
+    let parse = |value: &str| -> Vec<String> {
+        value
+            .split(',')
+            .map(|id| id.trim())
+            .filter(|id| !id.is_empty())
+            .map(|id| id.to_string())
+            .collect()
+    };
+
+    let selection = match param.map(parse) {
+        Some(ids) if !ids.is_empty() => ids,
+        Some(_) => Vec::new(),
+        None => cookie.map(parse).unwrap_or_default(),
+    };
+
+    if selection.is_empty() {
+        return eligible.to_vec();
+    }
+
+    eligible
+        .iter()
+        .filter(|id| selection.contains(id))
+        .cloned()
+        .collect()
+}
+
+/// Suggestions returned by [`EngineRegistry::suggestions`].
+const MAX_SUGGESTIONS: usize = 10;
+
 pub struct EngineRegistry {
     engines: HashMap<String, EngineEntry>,
+    answerers: Vec<Arc<dyn Answerer>>,
     settings: Arc<ArcSwap<Settings>>,
     default_client: Client,
+    page_dedup: PageDedup,
+    result_cache: ResultCache,
+    host_limiter: HostLimiter,
 }
 
 impl EngineRegistry {
     pub fn new(settings: Arc<ArcSwap<Settings>>, default_client: Client) -> Self {
         Self {
             engines: HashMap::new(),
+            answerers: Vec::new(),
             settings,
             default_client,
+            page_dedup: PageDedup::default(),
+            result_cache: ResultCache::default(),
+            host_limiter: HostLimiter::default(),
         }
     }
 
@@ -46,8 +188,8 @@ impl EngineRegistry {
             .cloned()
             .unwrap_or_default();
 
-        let client = if let Some(proxy) = &config.proxy {
-            match create_client(DEFAULT_USER_AGENT, Some(proxy)) {
+        let client = if needs_engine_client(&config) {
+            match create_engine_client(&config) {
                 Ok(c) => c,
                 Err(e) => {
                     tracing::error!("Failed to create client for engine {}: {}", id, e);
@@ -61,6 +203,7 @@ impl EngineRegistry {
         let circuit_breaker = Arc::new(Mutex::new(CircuitBreaker::new(
             config.failure_threshold,
             Duration::from_secs(config.cooldown),
+            config.success_threshold,
         )));
 
         let entry = EngineEntry {
@@ -73,36 +216,401 @@ impl EngineRegistry {
         self.engines.insert(id, entry);
     }
 
-    pub async fn search(&self, query: &SearchQuery) -> Vec<SearchResult> {
+    pub fn engine_count(&self) -> usize {
+        self.engines.len()
+    }
+
+    pub fn register_answerer(&mut self, answerer: Box<dyn Answerer>) {
+        self.answerers.push(Arc::from(answerer));
+    }
+
+    /// Runs all enabled answerers concurrently. Answers are only produced for the first page.
+    pub async fn answers(&self, query: &SearchQuery) -> Vec<Answer> {
+        if query.page > 1 {
+            return vec![];
+        }
+
         let mut join_set = JoinSet::new();
-        let query_categories = query.get_categories();
         let current_settings = self.settings.load();
 
+        let mut query = query.clone();
+        query.q = query.normalized_q();
+        self.apply_bangs(&mut query);
+
+        for answerer in &self.answerers {
+            let config = current_settings
+                .answerers
+                .get(&answerer.id())
+                .cloned()
+                .unwrap_or_default();
+
+            if !config.enabled {
+                continue;
+            }
+
+            let answerer = answerer.clone();
+            let query = query.clone();
+            let client = self.default_client.clone();
+            let outbound = current_settings.outbound.clone();
+
+            join_set.spawn(async move {
+                let timeout_duration = Duration::from_secs(config.timeout);
+                let answer = http::with_policy(outbound, answerer.answer(&query, &client, &config));
+                match tokio::time::timeout(timeout_duration, answer).await {
+                    Ok(answer) => answer,
+                    Err(_) => {
+                        tracing::warn!("Answerer {} timed out", answerer.id());
+                        None
+                    }
+                }
+            });
+        }
+
+        let mut answers = Vec::new();
+        while let Some(res) = join_set.join_next().await {
+            match res {
+                Ok(Some(answer)) => answers.push(answer),
+                Ok(None) => {}
+                Err(e) => tracing::error!("Task join error: {}", e),
+            }
+        }
+        answers
+    }
+
+    /// Autocomplete suggestions from the configured provider, or from every
+    /// enabled engine when none is configured, merged in engine id order.
+    pub async fn suggestions(&self, query: &SearchQuery) -> Vec<String> {
+        let current_settings = self.settings.load();
+        let mut query = query.clone();
+        query.q = query.normalized_q();
+        if query.q.is_empty() {
+            return vec![];
+        }
+
+        let provider = current_settings.search.autocomplete.trim();
+        let mut providers: Vec<(&String, &EngineEntry, EngineConfig)> = Vec::new();
+        for (id, entry) in &self.engines {
+            if !provider.is_empty() && id != provider {
+                continue;
+            }
+            let config = current_settings.engines.get(id).cloned().unwrap_or_default();
+            if !config.enabled || (entry.engine.requires_token() && config.tokens.is_empty()) {
+                continue;
+            }
+            providers.push((id, entry, config));
+        }
+        providers.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut join_set = JoinSet::new();
+        for (index, (id, entry, config)) in providers.into_iter().enumerate() {
+            let id = id.clone();
+            let engine = entry.engine.clone();
+            let client = entry.client.clone();
+            let query = query.clone();
+            let outbound = http::engine_policy(&current_settings.outbound, &config);
+
+            join_set.spawn(async move {
+                let timeout_duration = Duration::from_secs(config.timeout);
+                let suggest = http::with_policy(outbound, engine.suggest(&query, &client, &config));
+                let suggestions = match tokio::time::timeout(timeout_duration, suggest).await {
+                    Ok(Ok(suggestions)) => suggestions,
+                    Ok(Err(e)) => {
+                        tracing::warn!("Engine {} suggestions failed: {}", id, e);
+                        vec![]
+                    }
+                    Err(_) => {
+                        tracing::warn!("Engine {} suggestions timed out", id);
+                        vec![]
+                    }
+                };
+                (index, suggestions)
+            });
+        }
+
+        let mut lists = Vec::new();
+        while let Some(res) = join_set.join_next().await {
+            match res {
+                Ok(list) => lists.push(list),
+                Err(e) => tracing::error!("Task join error: {}", e),
+            }
+        }
+        lists.sort_by_key(|(index, _)| *index);
+
+        merge_suggestions(lists.into_iter().map(|(_, list)| list).collect(), MAX_SUGGESTIONS)
+    }
+
+    /// Strips a leading `!bang` from the query, routing it to the named engine
+    /// or category.
+    fn apply_bangs(&self, query: &mut SearchQuery) {
+        let engine_ids: Vec<String> = self.engines.keys().cloned().collect();
+        let mut categories: Vec<String> =
+            self.engines.values().flat_map(|entry| entry.categories.clone()).collect();
+        categories.sort();
+        categories.dedup();
+        parse_bangs(query, &engine_ids, &categories);
+    }
+
+    /// Reason the engine is currently not queried, checked in order of how
+    /// actionable it is for an operator.
+    async fn disabled_reason(entry: &EngineEntry, config: &EngineConfig) -> Option<String> {
+        if !config.enabled {
+            return Some("disabled in configuration".to_string());
+        }
+        if entry.engine.requires_token() && config.tokens.is_empty() {
+            return Some("missing API token".to_string());
+        }
+        if entry.circuit_breaker.lock().await.state() == CircuitBreakerState::Open {
+            return Some("suspended after repeated failures".to_string());
+        }
+        None
+    }
+
+    /// Each enabled engine's warmup URL, with the engine's own client and timeout.
+    pub fn upstream_probes(&self) -> Vec<UpstreamProbe> {
+        let current_settings = self.settings.load();
+        let mut probes = Vec::new();
+
+        for (id, entry) in &self.engines {
+            let config = current_settings
+                .engines
+                .get(id)
+                .cloned()
+                .unwrap_or_default();
+            if !config.enabled {
+                continue;
+            }
+            let Some(url) = entry.engine.warmup_url(&config) else {
+                continue;
+            };
+            probes.push(UpstreamProbe {
+                id: id.clone(),
+                client: entry.client.clone(),
+                url,
+                timeout: Duration::from_secs(config.timeout),
+            });
+        }
+        probes
+    }
+
+    /// Sends a HEAD request to each enabled engine's warmup URL through the
+    /// engine's own client, so its first real query can reuse the pooled connection.
+    /// Failures are logged and otherwise ignored. Returns how many hosts responded.
+    pub async fn warmup(&self) -> usize {
+        let mut join_set = JoinSet::new();
+
+        for probe in self.upstream_probes() {
+            join_set.spawn(async move {
+                let request = probe.client.head(&probe.url).timeout(probe.timeout).send();
+                match request.await {
+                    Ok(_) => {
+                        tracing::debug!("Warmed up engine {} ({})", probe.id, probe.url);
+                        true
+                    }
+                    Err(e) => {
+                        tracing::warn!("Warmup for engine {} failed: {}", probe.id, e);
+                        false
+                    }
+                }
+            });
+        }
+
+        let mut warmed = 0;
+        while let Some(res) = join_set.join_next().await {
+            if let Ok(true) = res {
+                warmed += 1;
+            }
+        }
+        warmed
+    }
+
+    /// Status of every registered engine, sorted by id.
+    pub async fn engine_statuses(&self) -> Vec<EngineStatus> {
+        let current_settings = self.settings.load();
+        let mut statuses = Vec::new();
+
         for (id, entry) in &self.engines {
             let config = current_settings
                 .engines
                 .get(id)
                 .cloned()
                 .unwrap_or_default();
+            let disabled_reason = Self::disabled_reason(entry, &config).await;
+
+            statuses.push(EngineStatus {
+                id: id.clone(),
+                name: entry.engine.name(),
+                categories: entry.categories.clone(),
+                weight: config.weight * entry.engine.weight(),
+                enabled: disabled_reason.is_none(),
+                disabled_reason,
+                about: entry.engine.about(),
+            });
+        }
+
+        statuses.sort_by(|a, b| a.id.cmp(&b.id));
+        statuses
+    }
+
+    /// Enabled engines matching one of the query categories, with their current config.
+    fn selected_engines(&self, query: &SearchQuery, settings: &Settings) -> Vec<(&String, &EngineEntry, EngineConfig)> {
+        let query_categories = query.get_categories();
+        let mut selected = Vec::new();
+
+        for (id, entry) in &self.engines {
+            let config = settings
+                .engines
+                .get(id)
+                .cloned()
+                .unwrap_or_default();
 
             if !config.enabled {
                 continue;
             }
 
-            // Check if engine supports any of the query categories
+            if entry.engine.requires_token() && config.tokens.is_empty() {
+                continue;
+            }
+
+            // A bang picks its engines outright.
+            if let Some(bang_engines) = &query.bang_engines {
+                if bang_engines.contains(id) {
+                    selected.push((id, entry, config));
+                }
+                continue;
+            }
+
+            // Check if engine supports any of the query categories. Each engine is
+            // visited once, however many of its categories were requested.
             let category_match = query_categories.iter().any(|c| entry.categories.contains(c));
 
-            if !category_match {
+            if !category_match && !config.always_run {
+                continue;
+            }
+
+            if config.single_category_only && query_categories.len() > 1 {
                 continue;
             }
 
+            selected.push((id, entry, config));
+        }
+
+        if query.bang_engines.is_some() {
+            return selected;
+        }
+
+        let eligible: Vec<String> = selected.iter().map(|(id, _, _)| (*id).clone()).collect();
+        let chosen = resolve_engines(query.cookie_engines.as_deref(), query.engines.as_deref(), &eligible);
+        selected.retain(|(id, _, _)| chosen.contains(id));
+
+        selected
+    }
+
+    /// Engines selected for a strict safe search query that cannot apply safe search upstream.
+    pub fn safesearch_unsupported(&self, query: &SearchQuery) -> Vec<String> {
+        if query.safesearch < 2 {
+            return vec![];
+        }
+
+        let current_settings = self.settings.load();
+        let mut query = query.clone();
+        self.apply_bangs(&mut query);
+        let mut unsupported: Vec<String> = self
+            .selected_engines(&query, &current_settings)
+            .into_iter()
+            .filter(|(_, entry, config)| !applies_safesearch(entry, config))
+            .map(|(id, _, _)| id.clone())
+            .collect();
+        unsupported.sort();
+        unsupported
+    }
+
+    pub async fn search(&self, query: &SearchQuery) -> SearchOutcome {
+        let mut join_set = JoinSet::new();
+        let current_settings = self.settings.load();
+
+        let mut query = query.clone();
+        query.q = query.normalized_q();
+        self.apply_bangs(&mut query);
+        if current_settings.search.lowercase_query {
+            query.q = query.q.to_lowercase();
+        }
+        if current_settings.search.auto_category && query.categories.is_empty() {
+            // Only categories some engine serves, so a guess never empties the page.
+            let inferred: Vec<String> = infer_categories(&query.q)
+                .into_iter()
+                .filter(|category| self.engines.values().any(|entry| entry.categories.contains(category)))
+                .collect();
+            query.categories = inferred.join(",");
+        }
+        let query = &query;
+
+        // Selector overrides are a debugging aid, so their pages are never cached.
+        let cache_ttl = Duration::from_secs(current_settings.cache.ttl);
+        let cache_key = (!cache_ttl.is_zero() && query.selector_overrides.is_empty()).then(|| CacheKey::new(query));
+        if let Some(results) = cache_key.as_ref().and_then(|key| self.result_cache.get(key, cache_ttl)) {
+            tracing::debug!("Serving {:?} from the result cache", query.q);
+            return SearchOutcome {
+                results,
+                ..Default::default()
+            };
+        }
+
+        let strict_safesearch = query.safesearch >= 2 && current_settings.search.strict_safesearch_only;
+        let deadline = current_settings
+            .search
+            .request_timeout
+            .map(|ms| std::time::Instant::now() + Duration::from_millis(ms));
+
+        let query_language = query.language_code();
+
+        // Weight of each queried engine, for the soft timeout's coverage check.
+        let mut engine_weights = HashMap::new();
+        for (id, entry, config) in self.selected_engines(query, &current_settings) {
+            if strict_safesearch && !applies_safesearch(entry, &config) {
+                tracing::debug!("Skipping engine {}: safe search not supported", id);
+                continue;
+            }
+
+            if query.page > 1 && !entry.engine.paging() {
+                tracing::debug!("Skipping engine {}: no paging", id);
+                continue;
+            }
+            if let (Some(language), Some(supported)) = (&query_language, entry.engine.supported_languages()) {
+                if !supported.contains(language) {
+                    tracing::debug!("Skipping engine {}: language {} not supported", id, language);
+                    continue;
+                }
+            }
+
             let engine = entry.engine.clone();
-            let query = query.clone();
+            let mut query = query.clone();
+            if let Some(max_page) = engine.max_page() {
+                query.page = query.page.min(max_page.max(1));
+            }
+            // Engines filtered here, or not at all, search unfiltered upstream.
+            let nsfw_filter = (config.safesearch_mode == SafesearchMode::Clientside && query.safesearch > 0)
+                .then(|| current_settings.search.clone());
+            if config.safesearch_mode != SafesearchMode::Passthrough {
+                query.safesearch = 0;
+            }
             let client = entry.client.clone();
             let id = id.clone();
-            let config = config.clone();
             let last_request = entry.last_request.clone();
             let circuit_breaker = entry.circuit_breaker.clone();
+            // Engines share a limit when their warmup URLs point at the same host.
+            let host_semaphore = current_settings
+                .search
+                .max_requests_per_host
+                .filter(|limit| *limit > 0)
+                .and_then(|limit| {
+                    let url = Url::parse(&entry.engine.warmup_url(&config)?).ok()?;
+                    let host = url.host_str()?.to_lowercase();
+                    Some(self.host_limiter.semaphore(&host, limit))
+                });
+            let debug = current_settings.debug;
+            let outbound = http::engine_policy(&current_settings.outbound, &config);
+            let max_results = current_settings.search.max_results_per_engine;
+            engine_weights.insert(id.clone(), config.weight * engine.weight());
 
             join_set.spawn(async move {
                 // Circuit Breaker Check
@@ -110,9 +618,11 @@ impl EngineRegistry {
                     let mut cb = circuit_breaker.lock().await;
                     if !cb.check() {
                         tracing::warn!("Engine {} circuit breaker is open", id);
-                        return vec![];
+                        metrics::record_circuit_open(&id);
+                        return EngineRun { id, elapsed: None, failed: true, results: vec![] };
                     }
                 }
+                let mut breaker_guard = BreakerGuard::new(circuit_breaker.clone());
 
                 // Throttling Logic
                 if config.throttle > 0 {
@@ -142,43 +652,209 @@ impl EngineRegistry {
                     }
                 }
 
-                let timeout_duration = Duration::from_secs(config.timeout);
-                match tokio::time::timeout(timeout_duration, engine.search(&query, &client, &config)).await {
+                let started = std::time::Instant::now();
+                let mut attempt = 0;
+                let outcome = loop {
+                    // Each attempt gets the engine timeout, bounded by what is left of the deadline.
+                    let mut timeout_duration = Duration::from_secs(config.timeout);
+                    if let Some(deadline) = deadline {
+                        timeout_duration = timeout_duration.min(deadline.saturating_duration_since(std::time::Instant::now()));
+                    }
+                    let attempt_search = async {
+                        // Waiting for a host slot counts against the timeout.
+                        let _permit = match &host_semaphore {
+                            Some(semaphore) => semaphore.acquire().await.ok(),
+                            None => None,
+                        };
+                        let search = engine.search(&query, &client, &config);
+                        http::with_policy_until(outbound.clone(), deadline, search).await
+                    };
+                    let outcome = tokio::time::timeout(timeout_duration, attempt_search).await;
+
+                    let retryable = !matches!(outcome, Ok(Ok(_)) | Ok(Err(EngineError::RateLimited { .. })));
+                    if !retryable || attempt >= config.retries {
+                        break outcome;
+                    }
+
+                    let delay = Duration::from_millis(config.retry_delay);
+                    if let Some(deadline) = deadline {
+                        if std::time::Instant::now() + delay >= deadline {
+                            tracing::debug!("Engine {} retry budget exhausted after {} attempts", id, attempt + 1);
+                            break outcome;
+                        }
+                    }
+                    attempt += 1;
+                    tracing::debug!("Retrying engine {} (attempt {})", id, attempt + 1);
+                    tokio::time::sleep(delay).await;
+                };
+                let elapsed = started.elapsed();
+                let metered = match &outcome {
+                    Ok(Ok(_)) => EngineOutcome::Success,
+                    Ok(Err(_)) => EngineOutcome::Failure,
+                    Err(_) => EngineOutcome::Timeout,
+                };
+                metrics::record_engine_request(&id, metered, elapsed);
+
+                let mut breaker = circuit_breaker.lock().await;
+                breaker_guard.disarm();
+                let (failed, results) = match outcome {
                     Ok(result) => match result {
                         Ok(mut results) => {
-                            circuit_breaker.lock().await.report_success();
-                            // Apply weight and position decay
+                            breaker.report_success();
+                            if let Some(search_settings) = &nsfw_filter {
+                                results.retain(|res| !is_nsfw(res, search_settings));
+                            }
+                            if results.len() > max_results {
+                                tracing::warn!(
+                                    "Engine {} returned {} results, keeping the first {}",
+                                    id,
+                                    results.len(),
+                                    max_results
+                                );
+                                results.truncate(max_results);
+                            }
+                            // Apply weight and position decay. The configured weight scales the engine's own.
+                            let weight = config.weight * engine.weight();
                             for (index, res) in results.iter_mut().enumerate() {
                                 // Simple position decay: higher rank (lower index) gets more score
                                 // Formula: weight / (index + 1)
-                                res.score = config.weight / (index as f64 + 1.0);
+                                res.score = weight / (index as f64 + 1.0);
+
+                                // Expose the inputs so operators can tune weights.
+                                if debug {
+                                    res.metadata.insert(format!("{}_pos", id), (index + 1).to_string());
+                                    res.metadata.insert(format!("{}_score", id), format!("{:.4}", res.score));
+                                }
                             }
-                            results
+                            (false, results)
+                        }
+                        Err(EngineError::RateLimited { retry_after: Some(retry_after) }) => {
+                            breaker.report_rate_limited(retry_after);
+                            tracing::warn!("Engine {} rate limited, suspending for {:?}", id, breaker.cooldown());
+                            (true, vec![])
                         }
                         Err(e) => {
-                            circuit_breaker.lock().await.report_failure();
+                            breaker.report_failure();
                             tracing::error!("Engine {} failed: {}", id, e);
-                            vec![]
+                            (true, vec![])
                         }
                     },
                     Err(_) => {
-                        circuit_breaker.lock().await.report_failure();
+                        breaker.report_failure();
                         tracing::warn!("Engine {} timed out", id);
-                        vec![]
+                        (true, vec![])
                     }
-                }
+                };
+                drop(breaker);
+
+                EngineRun { id, elapsed: Some(elapsed), failed, results }
             });
         }
 
         let mut raw_results = Vec::new();
-        while let Some(res) = join_set.join_next().await {
+        let mut timings = HashMap::new();
+        let mut failed = Vec::new();
+        let mut finished = Vec::new();
+
+        // Two-phase wait: at the soft deadline, return if enough of the engine
+        // weight has answered, otherwise keep waiting until the hard deadline.
+        let total_weight: f64 = engine_weights.values().sum();
+        let coverage = current_settings.search.soft_timeout_coverage;
+        let mut answered_weight = 0.0;
+        let collect_started = tokio::time::Instant::now();
+        let mut soft_deadline = current_settings
+            .search
+            .soft_timeout
+            .map(|ms| collect_started + Duration::from_millis(ms));
+        let hard_deadline = current_settings
+            .search
+            .hard_timeout
+            .map(|ms| collect_started + Duration::from_millis(ms));
+
+        loop {
+            let joined = match soft_deadline.or(hard_deadline) {
+                Some(deadline) => match tokio::time::timeout_at(deadline, join_set.join_next()).await {
+                    Ok(joined) => joined,
+                    Err(_) => {
+                        let soft_phase = soft_deadline.take().is_some();
+                        if soft_phase && answered_weight < total_weight * coverage {
+                            continue;
+                        }
+                        break;
+                    }
+                },
+                None => join_set.join_next().await,
+            };
+            let Some(res) = joined else {
+                break;
+            };
+
             match res {
-                Ok(results) => raw_results.extend(results),
+                Ok(run) => {
+                    finished.push(run.id.clone());
+                    if run.failed {
+                        failed.push(run.id.clone());
+                    } else {
+                        answered_weight += engine_weights.get(&run.id).copied().unwrap_or_default();
+                    }
+                    if let Some(elapsed) = run.elapsed {
+                        timings.insert(run.id, elapsed.as_millis() as u64);
+                    }
+                    raw_results.extend(run.results);
+                }
                 Err(e) => tracing::error!("Task join error: {}", e),
             }
         }
 
-        aggregate(raw_results, &current_settings.blocklist)
+        let mut warnings = Vec::new();
+        if !join_set.is_empty() {
+            join_set.abort_all();
+            let mut slow: Vec<&String> = engine_weights.keys().filter(|id| !finished.contains(id)).collect();
+            slow.sort();
+            tracing::debug!("Stopped waiting for engines: {:?}", slow);
+            warnings.push(format!(
+                "Results may be incomplete; these engines were too slow: {}",
+                slow.iter().map(|id| id.as_str()).collect::<Vec<_>>().join(", ")
+            ));
+        }
+
+        let mut results = aggregate(raw_results, &current_settings);
+        if current_settings.search.enforce_language {
+            let (kept, dropped) = enforce_language(results, &query.language);
+            // Only worth surfacing when the filter removed a sizeable share of the page.
+            if dropped > 0 && dropped >= kept.len() {
+                warnings.push(format!(
+                    "{} results were removed because they are not in the requested language ({})",
+                    dropped, query.language
+                ));
+            }
+            results = kept;
+        }
+        if current_settings.search.dedup_across_pages {
+            results = self.page_dedup.filter(query, results);
+        }
+        if current_settings.search.enrich_opengraph {
+            opengraph::enrich(&mut results, &current_settings.search, current_settings.proxy.allow_private_hosts).await;
+        }
+        if current_settings.search.normalize_scores {
+            normalize_scores(&mut results);
+        }
+
+        failed.sort();
+
+        // Pages missing engines would keep serving the gap until they expire.
+        if let Some(key) = cache_key {
+            if failed.is_empty() && warnings.is_empty() {
+                self.result_cache.insert(key, results.clone(), current_settings.cache.max_entries);
+            }
+        }
+
+        SearchOutcome {
+            results,
+            timings,
+            warnings,
+            failed,
+        }
     }
 }
 
@@ -232,46 +908,408 @@ mod tests {
         }
     }
 
-    #[tokio::test]
-    async fn test_search_category_filtering() {
-        // Construct a dummy settings object manually
-        let settings = Arc::new(ArcSwap::from(Arc::new(Settings {
-             general: crate::config::GeneralSettings {
-                 instance_name: "SearXNG".into(),
-             },
-             server: crate::config::ServerSettings {
-                 bind_address: "127.0.0.1".into(),
-                 port: 8080,
-                 base_url: "http://localhost:8080".into(),
-                 secret_key: "secret".into(),
-             },
-             debug: false,
-             engines: HashMap::new(),
-             blocklist: Vec::new(),
-        })));
-
-        let client = Client::new();
-        let mut registry = EngineRegistry::new(settings, client.clone());
-
-        registry.register_engine(Box::new(MockEngine {
-            id: "general_engine".to_string(),
-            categories: vec!["general".to_string()],
-            fail: false,
-            call_count: Arc::new(Mutex::new(0)),
-        }));
-        registry.register_engine(Box::new(MockEngine {
-            id: "image_engine".to_string(),
-            categories: vec!["images".to_string()],
-            fail: false,
-            call_count: Arc::new(Mutex::new(0)),
-        }));
+    /// Records the page of every query it receives.
+    struct PagingEngine {
+        id: String,
+        paging: bool,
+        max_page: Option<u32>,
+        pages: Arc<Mutex<Vec<u32>>>,
+    }
 
-        // 1. Test "general" category (default)
+    #[async_trait]
+    impl SearchEngine for PagingEngine {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+        fn name(&self) -> String {
+            self.id.clone()
+        }
+        fn paging(&self) -> bool {
+            self.paging
+        }
+        fn max_page(&self) -> Option<u32> {
+            self.max_page
+        }
+        async fn search(
+            &self,
+            query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            self.pages.lock().await.push(query.page);
+            Ok(vec![])
+        }
+    }
+
+    /// Serves German and French only.
+    struct RegionalEngine {
+        call_count: Arc<Mutex<u32>>,
+    }
+
+    #[async_trait]
+    impl SearchEngine for RegionalEngine {
+        fn id(&self) -> String {
+            "regional".to_string()
+        }
+        fn name(&self) -> String {
+            "Regional".to_string()
+        }
+        fn supported_languages(&self) -> Option<Vec<String>> {
+            Some(vec!["de".to_string(), "fr".to_string()])
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            *self.call_count.lock().await += 1;
+            Ok(vec![])
+        }
+    }
+
+    struct TokenEngine;
+
+    #[async_trait]
+    impl SearchEngine for TokenEngine {
+        fn id(&self) -> String {
+            "token_engine".to_string()
+        }
+        fn name(&self) -> String {
+            "Token Engine".to_string()
+        }
+        fn requires_token(&self) -> bool {
+            true
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            Ok(vec![])
+        }
+    }
+
+    /// Engine whose upstream always answers 429 with `Retry-After: 30`.
+    struct RateLimitedEngine;
+
+    #[async_trait]
+    impl SearchEngine for RateLimitedEngine {
+        fn id(&self) -> String {
+            "limited".to_string()
+        }
+        fn name(&self) -> String {
+            "Limited".to_string()
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            let resp = reqwest::Response::from(
+                axum::http::Response::builder()
+                    .status(429)
+                    .header("Retry-After", "30")
+                    .body("")
+                    .unwrap(),
+            );
+            crate::engines::error_for_status(&self.id(), &resp)?;
+            Ok(vec![])
+        }
+    }
+
+    struct FloodEngine;
+
+    #[async_trait]
+    impl SearchEngine for FloodEngine {
+        fn id(&self) -> String {
+            "flood".to_string()
+        }
+        fn name(&self) -> String {
+            "Flood".to_string()
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            Ok((0..1000)
+                .map(|i| SearchResult {
+                    url: format!("https://example.com/{}", i),
+                    title: i.to_string(),
+                    content: ResultContent::Text(String::new()),
+                    engines: vec![self.id()],
+                    score: 1.0,
+                    metadata: HashMap::new(),
+                })
+                .collect())
+        }
+    }
+
+    struct WarmupEngine {
+        id: String,
+        url: String,
+    }
+
+    #[async_trait]
+    impl SearchEngine for WarmupEngine {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+        fn name(&self) -> String {
+            self.id.clone()
+        }
+        fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+            Some(self.url.clone())
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            Ok(vec![])
+        }
+    }
+
+    /// Tracks how many of its kind are searching at once.
+    struct SharedHostEngine {
+        id: String,
+        in_flight: Arc<std::sync::atomic::AtomicUsize>,
+        max_in_flight: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl SearchEngine for SharedHostEngine {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+        fn name(&self) -> String {
+            self.id.clone()
+        }
+        fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+            Some("https://shared.example/".to_string())
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            use std::sync::atomic::Ordering;
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(vec![])
+        }
+    }
+
+    /// Answers with a single result after `delay`.
+    struct SlowEngine {
+        id: String,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl SearchEngine for SlowEngine {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+        fn name(&self) -> String {
+            self.id.clone()
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            tokio::time::sleep(self.delay).await;
+            Ok(vec![SearchResult {
+                url: format!("https://{}.example/", self.id),
+                title: self.id.clone(),
+                content: ResultContent::Text(String::new()),
+                engines: vec![self.id.clone()],
+                score: 1.0,
+                metadata: HashMap::new(),
+            }])
+        }
+    }
+
+    fn test_settings(engines: HashMap<String, EngineConfig>) -> Arc<ArcSwap<Settings>> {
+        Arc::new(ArcSwap::from(Arc::new(Settings::for_tests(engines))))
+    }
+
+    #[tokio::test]
+    async fn test_retries_stop_at_deadline() {
+        let mut engines = HashMap::new();
+        engines.insert(
+            "flaky".to_string(),
+            EngineConfig {
+                retries: 10,
+                retry_delay: 100,
+                ..Default::default()
+            },
+        );
+        let mut settings = Settings::for_tests(engines);
+        settings.search.request_timeout = Some(250);
+
+        let call_count = Arc::new(Mutex::new(0));
+        let mut registry = EngineRegistry::new(Arc::new(ArcSwap::from(Arc::new(settings))), Client::new());
+        registry.register_engine(Box::new(MockEngine {
+            id: "flaky".to_string(),
+            categories: vec!["general".to_string()],
+            fail: true,
+            call_count: call_count.clone(),
+        }));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+        let started = std::time::Instant::now();
+        let outcome = registry.search(&query).await;
+
+        // Attempts at ~0ms, ~100ms and ~200ms; a fourth would start after the deadline.
+        let calls = *call_count.lock().await;
+        assert!((2..=3).contains(&calls), "Expected the deadline to cut retries short, got {} calls", calls);
+        assert!(started.elapsed() < Duration::from_millis(400));
+        assert_eq!(outcome.failed, vec!["flaky".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_results_per_engine_are_capped() {
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        registry.register_engine(Box::new(FloodEngine));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+        let results = registry.search(&query).await.results;
+        assert_eq!(results.len(), 100);
+        // The engine's top results are the ones kept.
+        assert_eq!(results[0].url, "https://example.com/0");
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_sets_breaker_cooldown() {
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        registry.register_engine(Box::new(RateLimitedEngine));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+        let outcome = registry.search(&query).await;
+        assert_eq!(outcome.failed, vec!["limited".to_string()]);
+
+        let breaker = registry.engines["limited"].circuit_breaker.lock().await;
+        assert_eq!(breaker.state(), CircuitBreakerState::Open);
+        assert_eq!(breaker.cooldown(), Duration::from_secs(30));
+    }
+
+    #[tokio::test]
+    async fn test_warmup_contacts_engine_hosts() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let hits = Arc::new(AtomicU32::new(0));
+        let counter = hits.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { "ok" }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        // Grab a free port and close it again so the second engine's host refuses connections.
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        registry.register_engine(Box::new(WarmupEngine {
+            id: "reachable".to_string(),
+            url: format!("http://{}/", addr),
+        }));
+        registry.register_engine(Box::new(WarmupEngine {
+            id: "unreachable".to_string(),
+            url: format!("http://{}/", closed),
+        }));
+
+        assert_eq!(registry.warmup().await, 1);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_engine_statuses_report_disabled_reason() {
+        let mut engines = HashMap::new();
+        engines.insert(
+            "off".to_string(),
+            EngineConfig {
+                enabled: false,
+                ..Default::default()
+            },
+        );
+        let mut registry = EngineRegistry::new(test_settings(engines), Client::new());
+        registry.register_engine(Box::new(TokenEngine));
+        for id in ["off", "on"] {
+            registry.register_engine(Box::new(MockEngine {
+                id: id.to_string(),
+                categories: vec!["general".to_string()],
+                fail: false,
+                call_count: Arc::new(Mutex::new(0)),
+            }));
+        }
+
+        let statuses = registry.engine_statuses().await;
+        let reasons: Vec<(&str, Option<&str>)> = statuses
+            .iter()
+            .map(|s| (s.id.as_str(), s.disabled_reason.as_deref()))
+            .collect();
+        assert_eq!(
+            reasons,
+            vec![
+                ("off", Some("disabled in configuration")),
+                ("on", None),
+                ("token_engine", Some("missing API token")),
+            ]
+        );
+        assert!(!statuses[2].enabled);
+    }
+
+    #[tokio::test]
+    async fn test_search_category_filtering() {
+        let settings = test_settings(HashMap::new());
+
+        let client = Client::new();
+        let mut registry = EngineRegistry::new(settings, client.clone());
+
+        registry.register_engine(Box::new(MockEngine {
+            id: "general_engine".to_string(),
+            categories: vec!["general".to_string()],
+            fail: false,
+            call_count: Arc::new(Mutex::new(0)),
+        }));
+        registry.register_engine(Box::new(MockEngine {
+            id: "image_engine".to_string(),
+            categories: vec!["images".to_string()],
+            fail: false,
+            call_count: Arc::new(Mutex::new(0)),
+        }));
+
+        // 1. Test "general" category (default)
         let query_general = SearchQuery {
             q: "test".to_string(),
             ..Default::default()
         };
-        let results = registry.search(&query_general).await;
+        let results = registry.search(&query_general).await.results;
         assert!(results.iter().any(|r| r.engines.contains(&"general_engine".to_string())), "general_engine should match default category");
         assert!(!results.iter().any(|r| r.engines.contains(&"image_engine".to_string())), "image_engine should NOT match default category");
 
@@ -281,11 +1319,457 @@ mod tests {
             categories: "images".to_string(),
             ..Default::default()
         };
-        let results = registry.search(&query_images).await;
+        let results = registry.search(&query_images).await.results;
         assert!(!results.iter().any(|r| r.engines.contains(&"general_engine".to_string())), "general_engine should NOT match images category");
         assert!(results.iter().any(|r| r.engines.contains(&"image_engine".to_string())), "image_engine should match images category");
     }
 
+    #[tokio::test]
+    async fn test_overlapping_categories_query_engine_once() {
+        let settings = test_settings(HashMap::new());
+        let mut registry = EngineRegistry::new(settings, Client::new());
+        let call_count = Arc::new(Mutex::new(0));
+
+        registry.register_engine(Box::new(MockEngine {
+            id: "multi".to_string(),
+            categories: vec!["general".to_string(), "images".to_string()],
+            fail: false,
+            call_count: call_count.clone(),
+        }));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            categories: "general, images,general,,images".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(query.get_categories(), vec!["general", "images"]);
+
+        let outcome = registry.search(&query).await;
+        assert_eq!(*call_count.lock().await, 1);
+        assert_eq!(outcome.results.len(), 1);
+        assert_eq!(outcome.results[0].engines, vec!["multi"]);
+    }
+
+    #[tokio::test]
+    async fn test_non_paging_engine_skipped_after_first_page() {
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        let single_page = Arc::new(Mutex::new(Vec::new()));
+        let capped = Arc::new(Mutex::new(Vec::new()));
+        registry.register_engine(Box::new(PagingEngine {
+            id: "single_page".to_string(),
+            paging: false,
+            max_page: None,
+            pages: single_page.clone(),
+        }));
+        registry.register_engine(Box::new(PagingEngine {
+            id: "capped".to_string(),
+            paging: true,
+            max_page: Some(3),
+            pages: capped.clone(),
+        }));
+
+        for page in [1, 2, 5] {
+            let query = SearchQuery {
+                q: "test".to_string(),
+                page,
+                ..Default::default()
+            };
+            registry.search(&query).await;
+        }
+
+        assert_eq!(*single_page.lock().await, vec![1]);
+        assert_eq!(*capped.lock().await, vec![1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_engine_skipped_for_unsupported_language() {
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        let call_count = Arc::new(Mutex::new(0));
+        registry.register_engine(Box::new(RegionalEngine {
+            call_count: call_count.clone(),
+        }));
+
+        let search = |language: &str| SearchQuery {
+            q: "test".to_string(),
+            language: language.to_string(),
+            ..Default::default()
+        };
+
+        registry.search(&search("ja-JP")).await;
+        assert_eq!(*call_count.lock().await, 0);
+
+        // Matched on the primary subtag; no language at all means any engine.
+        for language in ["de-CH", "FR", "", "all"] {
+            registry.search(&search(language)).await;
+        }
+        assert_eq!(*call_count.lock().await, 4);
+    }
+
+    #[tokio::test]
+    async fn test_bang_runs_engine_outside_query_categories() {
+        let mut registry = EngineRegistry::new(test_settings(HashMap::new()), Client::new());
+        let general_calls = Arc::new(Mutex::new(0));
+        let image_calls = Arc::new(Mutex::new(0));
+        registry.register_engine(Box::new(MockEngine {
+            id: "general_engine".to_string(),
+            categories: vec!["general".to_string()],
+            fail: false,
+            call_count: general_calls.clone(),
+        }));
+        registry.register_engine(Box::new(MockEngine {
+            id: "image_engine".to_string(),
+            categories: vec!["images".to_string()],
+            fail: false,
+            call_count: image_calls.clone(),
+        }));
+
+        let query = SearchQuery {
+            q: "!image_engine cats".to_string(),
+            ..Default::default()
+        };
+        let outcome = registry.search(&query).await;
+        assert_eq!(outcome.results.len(), 1);
+        assert_eq!(outcome.results[0].engines, vec!["image_engine"]);
+        assert_eq!((*general_calls.lock().await, *image_calls.lock().await), (0, 1));
+
+        // A category bang searches that category instead of the requested one.
+        let query = SearchQuery {
+            q: "!images cats".to_string(),
+            categories: "general".to_string(),
+            ..Default::default()
+        };
+        registry.search(&query).await;
+        assert_eq!((*general_calls.lock().await, *image_calls.lock().await), (0, 2));
+    }
+
+    #[tokio::test]
+    async fn test_single_category_engine_skips_multi_category_queries() {
+        let mut engines_config = HashMap::new();
+        engines_config.insert(
+            "heavy_images".to_string(),
+            EngineConfig {
+                single_category_only: true,
+                ..Default::default()
+            },
+        );
+        let mut registry = EngineRegistry::new(test_settings(engines_config), Client::new());
+        let call_count = Arc::new(Mutex::new(0));
+        registry.register_engine(Box::new(MockEngine {
+            id: "heavy_images".to_string(),
+            categories: vec!["images".to_string()],
+            fail: false,
+            call_count: call_count.clone(),
+        }));
+
+        let multi = SearchQuery {
+            q: "test".to_string(),
+            categories: "general,images".to_string(),
+            ..Default::default()
+        };
+        assert!(registry.search(&multi).await.results.is_empty());
+        assert_eq!(*call_count.lock().await, 0);
+
+        let single = SearchQuery {
+            q: "test".to_string(),
+            categories: "images".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(registry.search(&single).await.results.len(), 1);
+        assert_eq!(*call_count.lock().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_aborted_probe_counts_as_failure() {
+        let breaker = Arc::new(Mutex::new(CircuitBreaker::new(1, Duration::ZERO, 1)));
+        breaker.lock().await.report_failure();
+        // Cooldown over: this is the half-open probe.
+        assert!(breaker.lock().await.check());
+
+        let mut join_set = JoinSet::new();
+        let guard = BreakerGuard::new(breaker.clone());
+        join_set.spawn(async move {
+            let _guard = guard;
+            std::future::pending::<()>().await
+        });
+        tokio::task::yield_now().await;
+        join_set.abort_all();
+        while join_set.join_next().await.is_some() {}
+
+        // The aborted probe failed, so the breaker reopened and, its cooldown
+        // over, lets the next probe through instead of waiting forever.
+        assert_eq!(breaker.lock().await.state(), CircuitBreakerState::Open);
+        assert!(breaker.lock().await.check());
+    }
+
+    #[tokio::test]
+    async fn test_soft_timeout_returns_once_heavy_engines_answer() {
+        let engine_config = |weight: f64| EngineConfig {
+            weight,
+            throttle: 0,
+            ..Default::default()
+        };
+        let mut engines = HashMap::new();
+        engines.insert("fast".to_string(), engine_config(3.0));
+        engines.insert("slow".to_string(), engine_config(1.0));
+        let mut settings = Settings::for_tests(engines);
+        settings.search.soft_timeout = Some(50);
+        settings.search.hard_timeout = Some(2000);
+        settings.search.soft_timeout_coverage = 0.5;
+        let settings = Arc::new(ArcSwap::from(Arc::new(settings)));
+
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        registry.register_engine(Box::new(SlowEngine {
+            id: "fast".to_string(),
+            delay: Duration::from_millis(0),
+        }));
+        registry.register_engine(Box::new(SlowEngine {
+            id: "slow".to_string(),
+            delay: Duration::from_millis(400),
+        }));
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+
+        // "fast" holds 75% of the weight, so the soft deadline suffices.
+        let start = std::time::Instant::now();
+        let outcome = registry.search(&query).await;
+        assert!(start.elapsed() < Duration::from_millis(300), "took {:?}", start.elapsed());
+        assert_eq!(outcome.results.len(), 1);
+        assert_eq!(outcome.results[0].engines, vec!["fast"]);
+        assert!(outcome.warnings.iter().any(|w| w.contains("slow")));
+        assert!(outcome.failed.is_empty());
+
+        // Below the coverage threshold the search waits for the slow engine.
+        let mut stricter = Settings::clone(&settings.load());
+        stricter.search.soft_timeout_coverage = 0.9;
+        settings.store(Arc::new(stricter));
+        let outcome = registry.search(&query).await;
+        assert_eq!(outcome.results.len(), 2);
+        assert!(outcome.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_strict_safesearch_excludes_unsupported_engines() {
+        let settings = test_settings(HashMap::new());
+        let mut strict = Settings::for_tests(HashMap::new());
+        strict.search.strict_safesearch_only = true;
+
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        registry.register_engine(Box::new(MockEngine {
+            id: "unsafe_engine".to_string(),
+            categories: vec!["general".to_string()],
+            fail: false,
+            call_count: Arc::new(Mutex::new(0)),
+        }));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            safesearch: 2,
+            ..Default::default()
+        };
+
+        assert_eq!(registry.safesearch_unsupported(&query), vec!["unsafe_engine".to_string()]);
+
+        // Without strict mode the engine still runs, with a warning
+        let results = registry.search(&query).await.results;
+        assert_eq!(results.len(), 1);
+
+        // In strict mode it is excluded
+        settings.store(Arc::new(strict));
+        let results = registry.search(&query).await.results;
+        assert!(results.is_empty(), "Non-supporting engine should be excluded under strict mode");
+    }
+
+    #[tokio::test]
+    async fn test_host_concurrency_cap_serializes_requests() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.max_requests_per_host = Some(1);
+        let settings = Arc::new(ArcSwap::from(Arc::new(settings)));
+
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        for id in ["first", "second"] {
+            registry.register_engine(Box::new(SharedHostEngine {
+                id: id.to_string(),
+                in_flight: in_flight.clone(),
+                max_in_flight: max_in_flight.clone(),
+            }));
+        }
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+        let outcome = registry.search(&query).await;
+        assert!(outcome.failed.is_empty());
+        assert_eq!(outcome.timings.len(), 2);
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 1, "requests to one host should not overlap");
+
+        // Without a cap both engines hit the host at once.
+        settings.store(Arc::new(Settings::for_tests(HashMap::new())));
+        max_in_flight.store(0, Ordering::SeqCst);
+        registry.search(&query).await;
+        assert_eq!(max_in_flight.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_auto_category_routes_image_queries() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.auto_category = true;
+        let mut registry = EngineRegistry::new(Arc::new(ArcSwap::from(Arc::new(settings))), Client::new());
+        for (id, category) in [("web", "general"), ("pictures", "images")] {
+            registry.register_engine(Box::new(MockEngine {
+                id: id.to_string(),
+                categories: vec![category.to_string()],
+                fail: false,
+                call_count: Arc::new(Mutex::new(0)),
+            }));
+        }
+
+        let engines_for = |q: &str| SearchQuery {
+            q: q.to_string(),
+            ..Default::default()
+        };
+        let results = registry.search(&engines_for("mountain wallpaper")).await.results;
+        assert_eq!(results.iter().map(|r| r.engines[0].as_str()).collect::<Vec<_>>(), vec!["pictures"]);
+
+        // No engine serves `it`, so code queries fall back to general.
+        let results = registry.search(&engines_for("Vec::new()")).await.results;
+        assert_eq!(results.iter().map(|r| r.engines[0].as_str()).collect::<Vec<_>>(), vec!["web"]);
+    }
+
+    #[tokio::test]
+    async fn test_clientside_safesearch_filters_flagged_results() {
+        let mut engines = HashMap::new();
+        for (id, mode) in [("clientside", SafesearchMode::Clientside), ("passthrough", SafesearchMode::Passthrough)] {
+            engines.insert(
+                id.to_string(),
+                EngineConfig {
+                    safesearch_mode: mode,
+                    ..Default::default()
+                },
+            );
+        }
+        let mut settings = Settings::for_tests(engines);
+        // Both mock engines return a result on a host flagged as NSFW.
+        settings.search.nsfw_hosts = vec!["clientside".to_string(), "passthrough".to_string()];
+
+        let mut registry = EngineRegistry::new(Arc::new(ArcSwap::from(Arc::new(settings))), Client::new());
+        for id in ["clientside", "passthrough"] {
+            registry.register_engine(Box::new(MockEngine {
+                id: id.to_string(),
+                categories: vec!["general".to_string()],
+                fail: false,
+                call_count: Arc::new(Mutex::new(0)),
+            }));
+        }
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            safesearch: 1,
+            ..Default::default()
+        };
+        let engines: Vec<String> = registry
+            .search(&query)
+            .await
+            .results
+            .iter()
+            .flat_map(|r| r.engines.clone())
+            .collect();
+        assert_eq!(engines, vec!["passthrough".to_string()]);
+
+        // Nothing is filtered when safe search is off for the request.
+        let query = SearchQuery { safesearch: 0, ..query };
+        assert_eq!(registry.search(&query).await.results.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_always_run_engine_ignores_category() {
+        let mut engines = HashMap::new();
+        engines.insert(
+            "instant".to_string(),
+            EngineConfig {
+                always_run: true,
+                ..Default::default()
+            },
+        );
+        let mut registry = EngineRegistry::new(test_settings(engines), Client::new());
+        for id in ["instant", "web"] {
+            registry.register_engine(Box::new(MockEngine {
+                id: id.to_string(),
+                categories: vec!["general".to_string()],
+                fail: false,
+                call_count: Arc::new(Mutex::new(0)),
+            }));
+        }
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            categories: "images".to_string(),
+            ..Default::default()
+        };
+        let engines: Vec<String> = registry
+            .search(&query)
+            .await
+            .results
+            .into_iter()
+            .flat_map(|r| r.engines)
+            .collect();
+        assert_eq!(engines, vec!["instant".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_debug_mode_stamps_engine_position() {
+        let mut debug = Settings::for_tests(HashMap::new());
+        debug.debug = true;
+        let settings = Arc::new(ArcSwap::from(Arc::new(debug)));
+
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        registry.register_engine(Box::new(MockEngine {
+            id: "mock".to_string(),
+            categories: vec!["general".to_string()],
+            fail: false,
+            call_count: Arc::new(Mutex::new(0)),
+        }));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            ..Default::default()
+        };
+
+        let results = registry.search(&query).await.results;
+        assert_eq!(results[0].metadata.get("mock_pos").map(String::as_str), Some("1"));
+        assert_eq!(results[0].metadata.get("mock_score").map(String::as_str), Some("1.0000"));
+
+        settings.store(Arc::new(Settings::for_tests(HashMap::new())));
+        let results = registry.search(&query).await.results;
+        assert!(!results[0].metadata.contains_key("mock_pos"));
+    }
+
+    #[test]
+    fn test_resolve_engines() {
+        let eligible = vec!["bing".to_string(), "ddg".to_string(), "google".to_string()];
+
+        // Neither: all eligible engines
+        assert_eq!(resolve_engines(None, None, &eligible), eligible);
+
+        // Cookie only
+        assert_eq!(resolve_engines(Some("ddg,google"), None, &eligible), vec!["ddg", "google"]);
+
+        // Param only, ineligible ids ignored
+        assert_eq!(resolve_engines(None, Some("bing, images_only"), &eligible), vec!["bing"]);
+
+        // Param overrides cookie
+        assert_eq!(resolve_engines(Some("ddg"), Some("google"), &eligible), vec!["google"]);
+
+        // Explicit empty param falls back to defaults rather than the cookie
+        assert_eq!(resolve_engines(Some("ddg"), Some(""), &eligible), eligible);
+    }
+
     #[tokio::test]
     async fn test_search_throttling() {
         let mut engines_config = HashMap::new();
@@ -297,20 +1781,7 @@ mod tests {
             },
         );
 
-        let settings = Arc::new(ArcSwap::from(Arc::new(Settings {
-             general: crate::config::GeneralSettings {
-                 instance_name: "SearXNG".into(),
-             },
-             server: crate::config::ServerSettings {
-                 bind_address: "127.0.0.1".into(),
-                 port: 8080,
-                 base_url: "http://localhost:8080".into(),
-                 secret_key: "secret".into(),
-             },
-             debug: false,
-             engines: engines_config,
-             blocklist: Vec::new(),
-        })));
+        let settings = test_settings(engines_config);
 
         let client = Client::new();
         let mut registry = EngineRegistry::new(settings, client.clone());
@@ -352,20 +1823,7 @@ mod tests {
             },
         );
 
-        let settings = Arc::new(ArcSwap::from(Arc::new(Settings {
-             general: crate::config::GeneralSettings {
-                 instance_name: "SearXNG".into(),
-             },
-             server: crate::config::ServerSettings {
-                 bind_address: "127.0.0.1".into(),
-                 port: 8080,
-                 base_url: "http://localhost:8080".into(),
-                 secret_key: "secret".into(),
-             },
-             debug: false,
-             engines: engines_config,
-             blocklist: Vec::new(),
-        })));
+        let settings = test_settings(engines_config);
 
         let client = Client::new();
         let mut registry = EngineRegistry::new(settings, client.clone());
@@ -399,4 +1857,58 @@ mod tests {
         registry.search(&query).await;
         assert_eq!(*call_count.lock().await, 3, "Should call engine after cooldown");
     }
+
+    #[tokio::test]
+    async fn test_result_cache_serves_repeated_queries() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.cache.ttl = 1;
+        let mut registry = EngineRegistry::new(Arc::new(ArcSwap::from(Arc::new(settings))), Client::new());
+        let call_count = Arc::new(Mutex::new(0));
+        registry.register_engine(Box::new(MockEngine {
+            id: "cached".to_string(),
+            categories: vec!["general".to_string()],
+            fail: false,
+            call_count: call_count.clone(),
+        }));
+
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            ..Default::default()
+        };
+        let first = registry.search(&query).await;
+        assert_eq!(first.results.len(), 1);
+
+        // The same query, spelled differently, within the TTL.
+        let repeated = SearchQuery {
+            q: "  Rust ".to_string(),
+            ..Default::default()
+        };
+        let second = registry.search(&repeated).await;
+        assert_eq!(*call_count.lock().await, 1, "Cached page should not query the engine");
+        assert_eq!(second.results.len(), 1);
+        assert_eq!(second.results[0].url, first.results[0].url);
+
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        registry.search(&query).await;
+        assert_eq!(*call_count.lock().await, 2, "Expired page should query the engine again");
+    }
+
+    #[tokio::test]
+    async fn test_result_cache_skips_pages_with_failures() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.cache.ttl = 60;
+        let mut registry = EngineRegistry::new(Arc::new(ArcSwap::from(Arc::new(settings))), Client::new());
+        let call_count = Arc::new(Mutex::new(0));
+        registry.register_engine(Box::new(MockEngine {
+            id: "broken".to_string(),
+            categories: vec!["general".to_string()],
+            fail: true,
+            call_count: call_count.clone(),
+        }));
+
+        let query = SearchQuery::default();
+        registry.search(&query).await;
+        registry.search(&query).await;
+        assert_eq!(*call_count.lock().await, 2);
+    }
 }
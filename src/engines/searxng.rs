@@ -0,0 +1,224 @@
+use crate::config::{EngineConfig, Settings};
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use arc_swap::ArcSwap;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use url::Url;
+
+/// Header counting how many SearXNG instances a query has passed through.
+pub const HOPS_HEADER: &str = "x-searxng-hops";
+
+/// Compares instance base URLs by host, port and path, ignoring scheme,
+/// case and trailing slashes.
+fn instance_key(base_url: &str) -> Option<String> {
+    let url = Url::parse(base_url.trim()).ok()?;
+    let host = url.host_str()?.to_lowercase();
+    let port = url.port_or_known_default().unwrap_or_default();
+    Some(format!("{}:{}{}", host, port, url.path().trim_end_matches('/')))
+}
+
+/// Whether `base_url` is this instance or on the federation blocklist.
+pub fn is_federation_blocked(base_url: &str, settings: &Settings) -> bool {
+    let Some(target) = instance_key(base_url) else {
+        return true;
+    };
+    std::iter::once(&settings.server.base_url)
+        .chain(&settings.search.federation_blocklist)
+        .filter_map(|blocked| instance_key(blocked))
+        .any(|blocked| blocked == target)
+}
+
+/// Hop count to send upstream, or `None` if forwarding would exceed `max_hops`.
+pub fn next_hop(hops: u8, max_hops: u8) -> Option<u8> {
+    let next = hops.checked_add(1)?;
+    (next <= max_hops).then_some(next)
+}
+
+/// Queries another SearXNG instance through its JSON API (`extra.base_url`).
+pub struct Searxng {
+    settings: Arc<ArcSwap<Settings>>,
+}
+
+impl Searxng {
+    pub fn new(settings: Arc<ArcSwap<Settings>>) -> Self {
+        Self { settings }
+    }
+
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["results"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let (url, title) = match (item["url"].as_str(), item["title"].as_str()) {
+                (Some(url), Some(title)) => (url, title.to_string()),
+                _ => continue,
+            };
+            // Peers are not trusted: a `javascript:` or `data:` link would run
+            // in the user's browser once rendered.
+            let url = match Url::parse(url) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => url.to_string(),
+                _ => continue,
+            };
+            let content = item["content"].as_str().unwrap_or_default().to_string();
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Text(content),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata: HashMap::new(),
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Searxng {
+    fn id(&self) -> String {
+        "searxng".to_string()
+    }
+
+    fn name(&self) -> String {
+        "SearXNG".to_string()
+    }
+
+    /// The instance is configured per deployment, so there is no fixed website.
+    fn about(&self) -> EngineAbout {
+        EngineAbout {
+            use_official_api: true,
+            results: "JSON",
+            ..Default::default()
+        }
+    }
+
+    fn warmup_url(&self, config: &EngineConfig) -> Option<String> {
+        let base_url = config.extra.get("base_url")?;
+        (!is_federation_blocked(base_url, &self.settings.load())).then(|| base_url.clone())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        // Registered for every deployment but only federates once an instance is configured.
+        let Some(base_url) = config.extra.get("base_url") else {
+            return Ok(Vec::new());
+        };
+
+        // Neither case is the upstream's fault, and the hop count comes from the
+        // client, so they skip the engine rather than count against its breaker.
+        let settings = self.settings.load();
+        if is_federation_blocked(base_url, &settings) {
+            tracing::debug!("Not federating to blocked instance {}", base_url);
+            return Ok(Vec::new());
+        }
+        let Some(hops) = next_hop(query.hops, settings.search.max_federation_hops) else {
+            tracing::debug!("Not federating: depth of {} reached", query.hops);
+            return Ok(Vec::new());
+        };
+
+        let url = format!("{}/search", base_url.trim_end_matches('/'));
+        let params = [
+            ("q", query.q.clone()),
+            ("format", "json".to_string()),
+            ("pageno", query.page.to_string()),
+            ("language", query.language.clone()),
+            ("safesearch", query.safesearch.to_string()),
+        ];
+
+        let request = client.get(&url).query(&params).header(HOPS_HEADER, hops.to_string());
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_and_blocklisted_instances_are_rejected() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.server.base_url = "https://search.example.org".to_string();
+        settings.search.federation_blocklist = vec!["https://other.example.net/searx/".to_string()];
+
+        assert!(is_federation_blocked("https://search.example.org/", &settings));
+        assert!(is_federation_blocked("https://SEARCH.example.org", &settings));
+        assert!(is_federation_blocked("https://other.example.net/searx", &settings));
+        assert!(is_federation_blocked("not a url", &settings));
+        assert!(!is_federation_blocked("https://friendly.example.com", &settings));
+        assert!(!is_federation_blocked("https://other.example.net/", &settings));
+    }
+
+    #[test]
+    fn test_hop_count_is_limited() {
+        assert_eq!(next_hop(0, 2), Some(1));
+        assert_eq!(next_hop(1, 2), Some(2));
+        assert_eq!(next_hop(2, 2), None);
+        assert_eq!(next_hop(u8::MAX, u8::MAX), None);
+    }
+
+    #[tokio::test]
+    async fn test_hop_limit_and_blocked_instances_skip_without_error() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.federation_blocklist = vec!["https://blocked.example.net".to_string()];
+        let max_hops = settings.search.max_federation_hops;
+        let engine = Searxng::new(Arc::new(ArcSwap::from(Arc::new(settings))));
+
+        let mut config = EngineConfig::default();
+        config.extra.insert("base_url".to_string(), "https://friendly.example.com".to_string());
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            hops: max_hops,
+            ..Default::default()
+        };
+        let results = engine.search(&query, &Client::new(), &config).await.unwrap();
+        assert!(results.is_empty());
+
+        config.extra.insert("base_url".to_string(), "https://blocked.example.net".to_string());
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            ..Default::default()
+        };
+        let results = engine.search(&query, &Client::new(), &config).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let body = serde_json::json!({
+            "results": [
+                {"url": "https://example.com/", "title": "Example", "content": "An example"},
+                {"title": "No URL"},
+                {"url": "javascript:alert(document.cookie)", "title": "Hostile"},
+                {"url": "JavaScript:alert(1)", "title": "Hostile"},
+                {"url": "data:text/html,<script>alert(1)</script>", "title": "Hostile"},
+                {"url": "/relative", "title": "Relative"}
+            ]
+        });
+        let engine = Searxng::new(Arc::new(ArcSwap::from(Arc::new(Settings::for_tests(HashMap::new())))));
+        let results = engine.parse_response(&body);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://example.com/");
+        assert_eq!(results[0].engines, vec!["searxng"]);
+    }
+}
@@ -0,0 +1,187 @@
+use crate::engines::error::EngineError;
+use crate::engines::resolve_url;
+use crate::models::{ResultContent, SearchResult};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine as _;
+use scraper::{Html, Selector};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Replacement selectors for one engine; unset fields keep the engine's own.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SelectorOverride {
+    pub result: Option<String>,
+    pub title: Option<String>,
+    pub url: Option<String>,
+    pub snippet: Option<String>,
+}
+
+/// Per-request selector overrides keyed by engine id.
+pub type SelectorOverrides = HashMap<String, SelectorOverride>;
+
+/// Decodes a `selector_override` parameter: base64 (standard or URL-safe) JSON
+/// such as `{"bing": {"result": "li.b_algo_new"}}`.
+pub fn decode_overrides(value: &str) -> Result<SelectorOverrides, String> {
+    let bytes = STANDARD
+        .decode(value)
+        .or_else(|_| URL_SAFE_NO_PAD.decode(value.trim_end_matches('=')))
+        .map_err(|e| format!("selector_override is not valid base64: {}", e))?;
+    serde_json::from_slice(&bytes).map_err(|e| format!("selector_override is not valid JSON: {}", e))
+}
+
+/// CSS selectors a scraper engine extracts results with. `title`, `url` and
+/// `snippet` are matched within each `result` element.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScraperSelectors {
+    pub result: String,
+    pub title: String,
+    /// Element whose `href` is the result URL.
+    pub url: String,
+    pub snippet: String,
+}
+
+pub struct CompiledSelectors {
+    pub result: Selector,
+    pub title: Selector,
+    pub url: Selector,
+    pub snippet: Selector,
+}
+
+impl ScraperSelectors {
+    pub fn new(result: &str, title: &str, url: &str, snippet: &str) -> Self {
+        Self {
+            result: result.to_string(),
+            title: title.to_string(),
+            url: url.to_string(),
+            snippet: snippet.to_string(),
+        }
+    }
+
+    /// Replaces the selectors set in `overrides`, if any.
+    pub fn with_override(mut self, overrides: Option<&SelectorOverride>) -> Self {
+        let Some(overrides) = overrides else {
+            return self;
+        };
+        for (field, replacement) in [
+            (&mut self.result, &overrides.result),
+            (&mut self.title, &overrides.title),
+            (&mut self.url, &overrides.url),
+            (&mut self.snippet, &overrides.snippet),
+        ] {
+            if let Some(replacement) = replacement {
+                *field = replacement.clone();
+            }
+        }
+        self
+    }
+
+    pub fn compile(&self) -> Result<CompiledSelectors, EngineError> {
+        let parse = |name: &str, selector: &str| {
+            Selector::parse(selector).map_err(|e| EngineError::Parsing(format!("Invalid {} selector: {:?}", name, e)))
+        };
+        Ok(CompiledSelectors {
+            result: parse("result", &self.result)?,
+            title: parse("title", &self.title)?,
+            url: parse("url", &self.url)?,
+            snippet: parse("snippet", &self.snippet)?,
+        })
+    }
+}
+
+/// Text results from `html` using `selectors`, for engines whose result
+/// markup needs no special handling. Result URLs are resolved against
+/// `base_url`; results without a title or URL are skipped.
+pub fn parse_with_selectors(
+    html: &str,
+    selectors: &ScraperSelectors,
+    base_url: &str,
+    engine_id: &str,
+) -> Result<Vec<SearchResult>, EngineError> {
+    let document = Html::parse_document(html);
+    let selectors = selectors.compile()?;
+
+    let mut results = Vec::new();
+
+    for element in document.select(&selectors.result) {
+        let title = match element.select(&selectors.title).next() {
+            Some(el) => el.text().collect::<String>().trim().to_string(),
+            None => continue,
+        };
+
+        let url = match element
+            .select(&selectors.url)
+            .next()
+            .and_then(|el| el.value().attr("href"))
+            .and_then(|href| resolve_url(base_url, href))
+        {
+            Some(href) => href,
+            None => continue,
+        };
+
+        let content_text = match element.select(&selectors.snippet).next() {
+            Some(el) => el.text().collect::<Vec<_>>().join(" ").trim().to_string(),
+            None => String::new(),
+        };
+
+        results.push(SearchResult {
+            url,
+            title,
+            content: ResultContent::Text(content_text),
+            engines: vec![engine_id.to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        });
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_overrides() {
+        let json = r#"{"bing": {"result": "div.new"}}"#;
+        let expected = SelectorOverride {
+            result: Some("div.new".to_string()),
+            ..Default::default()
+        };
+
+        let standard = decode_overrides(&STANDARD.encode(json)).unwrap();
+        assert_eq!(standard["bing"], expected);
+        let url_safe = decode_overrides(&URL_SAFE_NO_PAD.encode(json)).unwrap();
+        assert_eq!(url_safe["bing"], expected);
+
+        assert!(decode_overrides("%%%").is_err());
+        assert!(decode_overrides(&STANDARD.encode(r#"{"bing": {"resutl": "div"}}"#)).is_err());
+
+        let selectors = ScraperSelectors::new("li", "h2", "a", "p").with_override(standard.get("bing"));
+        assert_eq!(selectors, ScraperSelectors::new("div.new", "h2", "a", "p"));
+    }
+
+    #[test]
+    fn test_parse_with_selectors() {
+        let html = r#"
+            <ul>
+                <li><h2><a href="/docs">  Docs </a></h2><p>The <b>manual</b></p></li>
+                <li><h2><a href="https://other.example.org/">Other</a></h2></li>
+                <li><p>No title or link</p></li>
+            </ul>
+        "#;
+        let selectors = ScraperSelectors::new("li", "h2", "h2 a", "p");
+
+        let results = parse_with_selectors(html, &selectors, "https://example.com/search", "test").unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].title, "Docs");
+        assert_eq!(results[0].url, "https://example.com/docs");
+        assert_eq!(results[0].engines, vec!["test".to_string()]);
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text == "The  manual"));
+        assert_eq!(results[1].url, "https://other.example.org/");
+        assert!(matches!(&results[1].content, ResultContent::Text(text) if text.is_empty()));
+
+        let broken = ScraperSelectors::new("li[", "h2", "a", "p");
+        assert!(parse_with_selectors(html, &broken, "https://example.com/", "test").is_err());
+    }
+}
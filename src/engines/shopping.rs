@@ -0,0 +1,198 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Product search against a price-comparison API.
+///
+/// There is no public default endpoint, so the engine does nothing until
+/// `extra.base_url` is configured. The API is expected to answer
+/// `GET <base_url>?q=&page=` with `{"products": [{"title", "url", "price",
+/// "currency", "merchant", "image", "description"}]}`; an `api_key` in `extra`
+/// is sent as a bearer token.
+pub struct Shopping;
+
+/// Formats a price with the currency symbol where one is well known,
+/// otherwise with the ISO code as a suffix.
+fn format_price(amount: f64, currency: &str) -> String {
+    let currency = currency.to_uppercase();
+    match currency.as_str() {
+        "USD" => format!("${:.2}", amount),
+        "EUR" => format!("€{:.2}", amount),
+        "GBP" => format!("£{:.2}", amount),
+        // No minor unit in everyday use.
+        "JPY" => format!("¥{:.0}", amount),
+        "" => format!("{:.2}", amount),
+        code => format!("{:.2} {}", amount, code),
+    }
+}
+
+impl Shopping {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["products"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let (url, title) = match (item["url"].as_str(), item["title"].as_str()) {
+                (Some(url), Some(title)) => (url.to_string(), title.to_string()),
+                _ => continue,
+            };
+
+            let mut metadata = HashMap::new();
+            // Some APIs send prices as strings to avoid float rounding.
+            let amount = item["price"]
+                .as_f64()
+                .or_else(|| item["price"].as_str().and_then(|p| p.trim().parse().ok()));
+            if let Some(amount) = amount {
+                let currency = item["currency"].as_str().unwrap_or_default();
+                metadata.insert("price".to_string(), format_price(amount, currency));
+            }
+            if let Some(merchant) = item["merchant"].as_str() {
+                metadata.insert("merchant".to_string(), merchant.to_string());
+            }
+            if let Some(image) = item["image"].as_str().filter(|i| !i.is_empty()) {
+                metadata.insert(metadata_keys::THUMBNAIL.to_string(), image.to_string());
+            }
+
+            let description = item["description"].as_str().unwrap_or_default().to_string();
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Text(description),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Shopping {
+    fn id(&self) -> String {
+        "shopping".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Shopping".to_string()
+    }
+
+    /// The upstream is configured per deployment, so there is no fixed website.
+    fn about(&self) -> EngineAbout {
+        EngineAbout {
+            use_official_api: true,
+            results: "JSON",
+            ..Default::default()
+        }
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["shopping".to_string()]
+    }
+
+    fn warmup_url(&self, config: &EngineConfig) -> Option<String> {
+        config.extra.get("base_url").cloned()
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let Some(url) = config.extra.get("base_url") else {
+            return Ok(vec![]);
+        };
+
+        let params = [
+            ("q", query.q.clone()),
+            ("page", query.page.to_string()),
+        ];
+
+        let mut request = client.get(url).query(&params);
+        if let Some(key) = config.extra.get("api_key") {
+            request = request.bearer_auth(key);
+        }
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: Value = resp.json().await?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "products": [
+                {
+                    "title": "Mechanical Keyboard",
+                    "url": "https://shop.example.com/keyboard",
+                    "price": 89.5,
+                    "currency": "usd",
+                    "merchant": "Example Shop",
+                    "image": "https://shop.example.com/keyboard.jpg",
+                    "description": "Tenkeyless, brown switches"
+                },
+                {
+                    "title": "USB Cable",
+                    "url": "https://other.example.com/cable",
+                    "price": "4.00",
+                    "currency": "CHF",
+                    "merchant": "Other Store"
+                },
+                {
+                    "title": "No URL",
+                    "price": 1.0
+                }
+            ]
+        });
+
+        let results = Shopping.parse_response(&body);
+        assert_eq!(results.len(), 2);
+
+        let keyboard = &results[0];
+        assert_eq!(keyboard.metadata["price"], "$89.50");
+        assert_eq!(keyboard.metadata["merchant"], "Example Shop");
+        assert_eq!(keyboard.metadata["thumbnail"], "https://shop.example.com/keyboard.jpg");
+        assert!(matches!(&keyboard.content, ResultContent::Text(text) if text == "Tenkeyless, brown switches"));
+
+        let cable = &results[1];
+        assert_eq!(cable.metadata["price"], "4.00 CHF");
+        assert!(!cable.metadata.contains_key("thumbnail"));
+
+        assert_eq!(format_price(1234.0, "JPY"), "¥1234");
+        assert_eq!(format_price(9.99, "EUR"), "€9.99");
+    }
+
+    #[tokio::test]
+    async fn test_search_without_base_url_returns_nothing() {
+        let query = SearchQuery {
+            q: "keyboard".to_string(),
+            ..Default::default()
+        };
+        let results = Shopping
+            .search(&query, &Client::new(), &EngineConfig::default())
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
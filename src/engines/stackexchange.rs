@@ -0,0 +1,197 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use chrono::DateTime;
+use flate2::read::GzDecoder;
+use reqwest::Client;
+use scraper::Html;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Read;
+
+const SEARCH_URL: &str = "https://api.stackexchange.com/2.3/search/advanced";
+
+const PAGE_SIZE: u32 = 20;
+
+/// Questions from the Stack Exchange API, on Stack Overflow unless the `site`
+/// extra names another Stack Exchange site.
+pub struct StackExchange;
+
+/// Decodes an API body. The API compresses every response, whatever the
+/// request accepted, so gzip bodies are recognized by their magic bytes.
+fn decode_json(body: &[u8]) -> Result<Value, EngineError> {
+    let mut decoded = Vec::new();
+    let json = if body.starts_with(&[0x1f, 0x8b]) {
+        GzDecoder::new(body)
+            .read_to_end(&mut decoded)
+            .map_err(|e| EngineError::Parsing(format!("Invalid gzip body: {}", e)))?;
+        &decoded[..]
+    } else {
+        body
+    };
+    serde_json::from_slice(json)
+        .map_err(|e| EngineError::Parsing(format!("Invalid Stack Exchange response: {}", e)))
+}
+
+/// Titles come HTML-escaped, e.g. `don&#39;t`.
+fn unescape(text: &str) -> String {
+    Html::parse_fragment(text).root_element().text().collect()
+}
+
+impl StackExchange {
+    fn parse_response(&self, body: &Value) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["items"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let (title, url) = match (item["title"].as_str(), item["link"].as_str()) {
+                (Some(title), Some(url)) => (unescape(title), url.to_string()),
+                _ => continue,
+            };
+
+            let mut metadata = HashMap::new();
+            for key in ["score", "answer_count", "is_answered"] {
+                if let Some(value) = item.get(key).filter(|v| v.is_number() || v.is_boolean()) {
+                    metadata.insert(key.to_string(), value.to_string());
+                }
+            }
+            let created = item["creation_date"].as_i64().and_then(|ts| DateTime::from_timestamp(ts, 0));
+            if let Some(created) = created {
+                metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), created.to_rfc3339());
+            }
+
+            let tags: Vec<&str> = item["tags"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .collect();
+
+            results.push(SearchResult {
+                url,
+                title,
+                content: ResultContent::Text(tags.join(", ")),
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for StackExchange {
+    fn id(&self) -> String {
+        "stackoverflow".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Stack Overflow".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://stackoverflow.com", true, "JSON")
+            .with_privacy_policy("https://stackoverflow.com/legal/privacy-policy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["it".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://api.stackexchange.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let site = config.extra.get("site").map(String::as_str).unwrap_or("stackoverflow");
+
+        let params = [
+            ("q", query.q.clone()),
+            ("site", site.to_string()),
+            ("order", "desc".to_string()),
+            ("sort", "relevance".to_string()),
+            ("page", query.page.to_string()),
+            ("pagesize", PAGE_SIZE.to_string()),
+        ];
+
+        let request = client.get(SEARCH_URL).query(&params).header("Accept-Encoding", "gzip");
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body = decode_json(&resp.bytes().await?)?;
+
+        Ok(self.parse_response(&body))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    const FIXTURE: &str = r#"{
+        "items": [
+            {
+                "tags": ["rust", "borrow-checker"],
+                "owner": {"display_name": "ferris"},
+                "is_answered": true,
+                "view_count": 1520,
+                "answer_count": 3,
+                "score": 42,
+                "creation_date": 1700000000,
+                "question_id": 123,
+                "link": "https://stackoverflow.com/questions/123/why-can-t-i-borrow",
+                "title": "Why can&#39;t I borrow `self` as mutable?"
+            },
+            {
+                "tags": [],
+                "is_answered": false,
+                "answer_count": 0,
+                "score": -1,
+                "question_id": 124,
+                "link": "https://stackoverflow.com/questions/124/untitled"
+            }
+        ],
+        "has_more": true,
+        "quota_max": 300,
+        "quota_remaining": 299
+    }"#;
+
+    #[test]
+    fn test_parse_gzipped_fixture() {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(FIXTURE.as_bytes()).unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let body = decode_json(&gzipped).unwrap();
+        assert_eq!(body, decode_json(FIXTURE.as_bytes()).unwrap());
+
+        let results = StackExchange.parse_response(&body);
+        assert_eq!(results.len(), 1);
+
+        let question = &results[0];
+        assert_eq!(question.title, "Why can't I borrow `self` as mutable?");
+        assert_eq!(question.url, "https://stackoverflow.com/questions/123/why-can-t-i-borrow");
+        assert_eq!(question.metadata["score"], "42");
+        assert_eq!(question.metadata["answer_count"], "3");
+        assert_eq!(question.metadata["is_answered"], "true");
+        assert_eq!(question.metadata["published_date"], "2023-11-14T22:13:20+00:00");
+        assert!(matches!(&question.content, ResultContent::Text(text) if text == "rust, borrow-checker"));
+    }
+}
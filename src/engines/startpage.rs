@@ -0,0 +1,186 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use scraper::{Html, Selector};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const HOME_URL: &str = "https://www.startpage.com/";
+const SEARCH_URL: &str = "https://www.startpage.com/sp/search";
+
+/// How long an `sc` token from the homepage is reused.
+const TOKEN_TTL: Duration = Duration::from_secs(300);
+
+/// Startpage, which serves Google results. Searches need the `sc` token from
+/// the homepage form, so one is fetched first and cached for a few minutes.
+pub struct Startpage {
+    token: Mutex<Option<(String, Instant)>>,
+}
+
+impl Default for Startpage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Startpage {
+    pub fn new() -> Self {
+        Self { token: Mutex::new(None) }
+    }
+
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new(".w-gl__result", "h3", "a.w-gl__result-title", "p.w-gl__description")
+    }
+
+    /// Reads the `sc` token from the homepage search form.
+    fn extract_token(html: &str) -> Result<String, EngineError> {
+        let document = Html::parse_document(html);
+        let selector = Selector::parse(r#"form input[name="sc"]"#)
+            .map_err(|e| EngineError::Parsing(format!("Invalid token selector: {:?}", e)))?;
+
+        document
+            .select(&selector)
+            .filter_map(|input| input.value().attr("value"))
+            .find(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .ok_or_else(|| EngineError::Parsing("Startpage sc token not found on the homepage".to_string()))
+    }
+
+    fn cached_token(&self) -> Option<String> {
+        let token = self.token.lock().ok()?;
+        token
+            .as_ref()
+            .filter(|(_, fetched)| fetched.elapsed() < TOKEN_TTL)
+            .map(|(value, _)| value.clone())
+    }
+
+    async fn token(&self, client: &Client, config: &EngineConfig) -> Result<String, EngineError> {
+        if let Some(token) = self.cached_token() {
+            return Ok(token);
+        }
+
+        let resp = http::send(apply_headers(client.get(HOME_URL), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let token = Self::extract_token(&response_text(resp, config).await?)?;
+
+        if let Ok(mut cached) = self.token.lock() {
+            *cached = Some((token.clone(), Instant::now()));
+        }
+        Ok(token)
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        parse_with_selectors(html, selectors, SEARCH_URL, &self.id())
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Startpage {
+    fn id(&self) -> String {
+        "startpage".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Startpage".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.startpage.com", false, "HTML")
+            .with_privacy_policy("https://www.startpage.com/en/privacy-policy/")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some(HOME_URL.to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let token = self.token(client, config).await?;
+
+        let params = [
+            ("query", query.q.clone()),
+            ("page", query.page.to_string()),
+            ("cat", "web".to_string()),
+            ("sc", token),
+        ];
+
+        let resp = http::send(apply_headers(client.post(SEARCH_URL).form(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    #[test]
+    fn test_extract_token() {
+        let home = r#"
+            <form action="/sp/search" method="post">
+                <input type="text" name="query">
+                <input type="hidden" name="sc" value="Xk3pQ2vN9r20">
+            </form>
+        "#;
+        assert_eq!(Startpage::extract_token(home).unwrap(), "Xk3pQ2vN9r20");
+
+        let missing = Startpage::extract_token("<form><input name=\"query\"></form>");
+        assert!(matches!(missing, Err(EngineError::Parsing(msg)) if msg.contains("sc token")));
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let html = r#"
+            <div class="w-gl">
+                <div class="w-gl__result">
+                    <a class="w-gl__result-title" href="https://www.rust-lang.org/"><h3>Rust Programming Language</h3></a>
+                    <p class="w-gl__description">Fast, reliable, productive.</p>
+                </div>
+                <div class="w-gl__result">
+                    <h3>Ad without a link</h3>
+                </div>
+            </div>
+        "#;
+
+        let results = Startpage::new().parse_results(html, &Startpage::selectors()).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://www.rust-lang.org/");
+        assert_eq!(results[0].title, "Rust Programming Language");
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text == "Fast, reliable, productive."));
+    }
+
+    #[test]
+    fn test_cached_token_expires() {
+        let engine = Startpage::new();
+        assert_eq!(engine.cached_token(), None);
+
+        *engine.token.lock().unwrap() = Some(("fresh".to_string(), Instant::now()));
+        assert_eq!(engine.cached_token().as_deref(), Some("fresh"));
+
+        let stale = Instant::now().checked_sub(TOKEN_TTL + Duration::from_secs(1));
+        if let Some(stale) = stale {
+            *engine.token.lock().unwrap() = Some(("stale".to_string(), stale));
+            assert_eq!(engine.cached_token(), None);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use base64::Engine as _;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// `EngineConfig.extra` key holding the pin configuration: either `tofu` or a
+/// comma-separated list of `sha256/<base64>` SPKI hashes.
+pub const TLS_PINS_KEY: &str = "tls_pins";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PinConfig {
+    /// Only accept certificates whose SPKI hash is in the list.
+    Fixed(Vec<[u8; 32]>),
+    /// Remember the SPKI hash seen on the first connection to each host and reject changes.
+    TrustOnFirstUse,
+}
+
+/// Parses the `tls_pins` value.
+pub fn parse_pins(value: &str) -> Result<PinConfig, String> {
+    if value.trim().eq_ignore_ascii_case("tofu") {
+        return Ok(PinConfig::TrustOnFirstUse);
+    }
+
+    let pins = value
+        .split(',')
+        .map(str::trim)
+        .filter(|pin| !pin.is_empty())
+        .map(|pin| {
+            let encoded = pin.strip_prefix("sha256/").unwrap_or(pin);
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Invalid pin {}: {}", pin, e))?;
+            <[u8; 32]>::try_from(bytes.as_slice())
+                .map_err(|_| format!("Invalid pin {}: expected a SHA-256 hash", pin))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if pins.is_empty() {
+        return Err("No pins configured".to_string());
+    }
+    Ok(PinConfig::Fixed(pins))
+}
+
+/// Reads one DER TLV, returning `(tag, value, rest)`.
+fn read_tlv(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = input.split_first()?;
+    let (&first_len, rest) = rest.split_first()?;
+
+    let (len, rest) = if first_len & 0x80 == 0 {
+        (first_len as usize, rest)
+    } else {
+        let num_bytes = (first_len & 0x7f) as usize;
+        if num_bytes == 0 || num_bytes > 4 || rest.len() < num_bytes {
+            return None;
+        }
+        let len = rest[..num_bytes]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[num_bytes..])
+    };
+
+    if rest.len() < len {
+        return None;
+    }
+    Some((tag, &rest[..len], &rest[len..]))
+}
+
+/// Returns the DER-encoded SubjectPublicKeyInfo of an X.509 certificate.
+pub fn subject_public_key_info(cert_der: &[u8]) -> Option<&[u8]> {
+    const SEQUENCE: u8 = 0x30;
+    const EXPLICIT_VERSION: u8 = 0xa0;
+
+    let (tag, cert, _) = read_tlv(cert_der)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let (tag, mut tbs, _) = read_tlv(cert)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+
+    if tbs.first() == Some(&EXPLICIT_VERSION) {
+        tbs = read_tlv(tbs)?.2;
+    }
+    // serialNumber, signature, issuer, validity, subject
+    for _ in 0..5 {
+        tbs = read_tlv(tbs)?.2;
+    }
+
+    let (tag, value, rest) = read_tlv(tbs)?;
+    if tag != SEQUENCE {
+        return None;
+    }
+    let header_len = tbs.len() - value.len() - rest.len();
+    Some(&tbs[..header_len + value.len()])
+}
+
+pub fn spki_sha256(cert_der: &[u8]) -> Option<[u8; 32]> {
+    subject_public_key_info(cert_der).map(|spki| Sha256::digest(spki).into())
+}
+
+/// Runs the normal WebPKI verification, then additionally checks the leaf's SPKI hash.
+#[derive(Debug)]
+struct PinningVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+    pins: PinConfig,
+    first_seen: Mutex<HashMap<String, [u8; 32]>>,
+}
+
+impl ServerCertVerifier for PinningVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.inner
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)?;
+
+        let hash = spki_sha256(end_entity.as_ref())
+            .ok_or_else(|| rustls::Error::General("Unable to read certificate public key".to_string()))?;
+
+        let pinned = match &self.pins {
+            PinConfig::Fixed(pins) => pins.contains(&hash),
+            PinConfig::TrustOnFirstUse => {
+                let mut first_seen = self
+                    .first_seen
+                    .lock()
+                    .map_err(|_| rustls::Error::General("Pin store poisoned".to_string()))?;
+                *first_seen.entry(server_name.to_str().into_owned()).or_insert(hash) == hash
+            }
+        };
+
+        if pinned {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            tracing::error!("TLS pin mismatch for {}", server_name.to_str());
+            Err(rustls::Error::General(format!(
+                "Certificate for {} does not match the pinned key",
+                server_name.to_str()
+            )))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Builds a rustls config trusting the bundled web PKI roots and enforcing the pins.
+pub fn pinned_tls_config(pins: PinConfig) -> anyhow::Result<ClientConfig> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let inner = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone()).build()?;
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinningVerifier {
+            inner,
+            pins,
+            first_seen: Mutex::new(HashMap::new()),
+        }))
+        .with_no_client_auth();
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed P-256 certificate for example.com and its SPKI pin (from `openssl pkey -pubin`).
+    const CERT_DER_B64: &str = "MIIBgDCCASegAwIBAgIUVzZGgmPvK1hkXs54zt8+es7378kwCgYIKoZIzj0EAwIwFjEUMBIGA1UEAwwLZXhhbXBsZS5jb20wHhcNMjYxMDE2MDAyNTI3WhcNMzYxMDEzMDAyNTI3WjAWMRQwEgYDVQQDDAtleGFtcGxlLmNvbTBZMBMGByqGSM49AgEGCCqGSM49AwEHA0IABDz6iYkxNUKzqFqQsLHB/3xf9SfxXZ9Q2AtkGrB67itKS1MUS19fFMABSOcKjnHG/rcfn8R5pkafCLcrwkYwhyCjUzBRMB0GA1UdDgQWBBQIYph+0jsfhCtrsYIlFze50lEDnzAfBgNVHSMEGDAWgBQIYph+0jsfhCtrsYIlFze50lEDnzAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0cAMEQCIHkYug27SPlNEHrukFebe5ASREGQ0+DFonZb81T0Bd8jAiApkZ8wPJrPCmnJX4H2ygo0SCEVC1pZQfAH5zt5P9xeew==";
+    const CERT_PIN: &str = "sha256/b3Wk6Slo7EhqqVwqADIc+NThVkmqFq895riKWZUAGPo=";
+
+    #[test]
+    fn test_parse_pins() {
+        assert_eq!(parse_pins("tofu"), Ok(PinConfig::TrustOnFirstUse));
+
+        match parse_pins(&format!("{}, {}", CERT_PIN, CERT_PIN)) {
+            Ok(PinConfig::Fixed(pins)) => assert_eq!(pins.len(), 2),
+            other => panic!("Expected fixed pins, got {:?}", other),
+        }
+
+        assert!(parse_pins("sha256/not-base64!").is_err());
+        assert!(parse_pins("sha256/AAAA").is_err(), "too short for SHA-256");
+        assert!(parse_pins(" , ").is_err());
+    }
+
+    #[test]
+    fn test_spki_hash_matches_pin() {
+        let der = base64::engine::general_purpose::STANDARD
+            .decode(CERT_DER_B64)
+            .unwrap();
+        let PinConfig::Fixed(pins) = parse_pins(CERT_PIN).unwrap() else {
+            panic!("Expected fixed pins");
+        };
+        assert_eq!(spki_sha256(&der), Some(pins[0]));
+        assert_eq!(spki_sha256(&der[..20]), None);
+    }
+
+    #[test]
+    fn test_pinned_client_construction() {
+        let tls = pinned_tls_config(parse_pins(CERT_PIN).unwrap()).unwrap();
+        let client = reqwest::Client::builder().use_preconfigured_tls(tls).build();
+        assert!(client.is_ok());
+    }
+}
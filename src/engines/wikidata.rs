@@ -0,0 +1,259 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::language::primary_subtag;
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const API_URL: &str = "https://www.wikidata.org/w/api.php";
+
+/// Wikidata property ids read into the result metadata.
+const INSTANCE_OF: &str = "P31";
+const OFFICIAL_WEBSITE: &str = "P856";
+
+/// The Wikidata entity best matching the query, with its description, type
+/// and official website in the metadata. Weighted above plain web results so
+/// the entity summary floats to the top.
+pub struct Wikidata;
+
+/// Languages to read labels in: the query's language, then English.
+fn label_languages(query: &SearchQuery) -> Vec<String> {
+    let mut languages = Vec::new();
+    let requested = primary_subtag(&query.language);
+    if !requested.is_empty() && requested != "all" {
+        languages.push(requested);
+    }
+    if !languages.iter().any(|lang| lang == "en") {
+        languages.push("en".to_string());
+    }
+    languages
+}
+
+/// Picks the value of a `labels` or `descriptions` map in the first language
+/// available, falling back to any language.
+fn localized<'a>(values: &'a Value, languages: &[String]) -> Option<&'a str> {
+    languages
+        .iter()
+        .find_map(|lang| values[lang.as_str()]["value"].as_str())
+        .or_else(|| values.as_object()?.values().find_map(|v| v["value"].as_str()))
+}
+
+/// Main values of an entity's statements for `property`.
+fn claim_values<'a>(entity: &'a Value, property: &str) -> impl Iterator<Item = &'a Value> {
+    entity["claims"][property]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|claim| &claim["mainsnak"]["datavalue"]["value"])
+}
+
+impl Wikidata {
+    /// Builds the result for a `wbgetentities` entity, returning it with the
+    /// ids of the classes it is an instance of, whose labels are fetched next.
+    fn parse_entity(&self, entity: &Value, languages: &[String]) -> Option<(SearchResult, Vec<String>)> {
+        let id = entity["id"].as_str()?;
+        let title = localized(&entity["labels"], languages)?.to_string();
+        let description = localized(&entity["descriptions"], languages).unwrap_or_default().to_string();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("wikidata_id".to_string(), id.to_string());
+        if !description.is_empty() {
+            metadata.insert("description".to_string(), description.clone());
+        }
+        if let Some(website) = claim_values(entity, OFFICIAL_WEBSITE).find_map(|v| v.as_str()) {
+            metadata.insert("official_website".to_string(), website.to_string());
+        }
+
+        let instance_ids: Vec<String> = claim_values(entity, INSTANCE_OF)
+            .filter_map(|v| v["id"].as_str())
+            .map(|id| id.to_string())
+            .collect();
+
+        let result = SearchResult {
+            url: format!("https://www.wikidata.org/wiki/{}", id),
+            title,
+            content: ResultContent::Text(description),
+            engines: vec![self.id()],
+            score: 1.0,
+            metadata,
+        };
+        Some((result, instance_ids))
+    }
+
+    /// Records the classes as `instance_of`, by label where one was fetched.
+    fn set_instance_of(result: &mut SearchResult, instance_ids: &[String], labels: &Value, languages: &[String]) {
+        if instance_ids.is_empty() {
+            return;
+        }
+        let names: Vec<&str> = instance_ids
+            .iter()
+            .map(|id| localized(&labels[id.as_str()]["labels"], languages).unwrap_or(id))
+            .collect();
+        result.metadata.insert("instance_of".to_string(), names.join(", "));
+    }
+
+    async fn get_entities(
+        &self,
+        client: &Client,
+        config: &EngineConfig,
+        ids: &str,
+        props: &str,
+        languages: &[String],
+    ) -> Result<Value, EngineError> {
+        let params = [
+            ("action", "wbgetentities"),
+            ("format", "json"),
+            ("ids", ids),
+            ("props", props),
+            ("languages", &languages.join("|")),
+            ("languagefallback", "1"),
+        ];
+        let resp = http::send(apply_headers(client.get(API_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: Value = resp.json().await?;
+        Ok(body["entities"].clone())
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Wikidata {
+    fn id(&self) -> String {
+        "wikidata".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Wikidata".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.wikidata.org", true, "JSON")
+            .with_privacy_policy("https://foundation.wikimedia.org/wiki/Privacy_policy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn weight(&self) -> f64 {
+        2.0
+    }
+
+    /// A single entity summary only makes sense on the first page.
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.wikidata.org/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let languages = label_languages(query);
+
+        let params = [
+            ("action", "wbsearchentities"),
+            ("format", "json"),
+            ("search", query.q.as_str()),
+            ("language", languages[0].as_str()),
+            ("uselang", languages[0].as_str()),
+            ("type", "item"),
+            ("limit", "1"),
+        ];
+        let resp = http::send(apply_headers(client.get(API_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: Value = resp.json().await?;
+
+        let Some(id) = body["search"][0]["id"].as_str() else {
+            return Ok(vec![]);
+        };
+
+        let entities = self
+            .get_entities(client, config, id, "labels|descriptions|claims", &languages)
+            .await?;
+        let Some((mut result, instance_ids)) = self.parse_entity(&entities[id], &languages) else {
+            return Ok(vec![]);
+        };
+
+        if !instance_ids.is_empty() {
+            let labels = self
+                .get_entities(client, config, &instance_ids.join("|"), "labels", &languages)
+                .await?;
+            Self::set_instance_of(&mut result, &instance_ids, &labels, &languages);
+        }
+
+        Ok(vec![result])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn douglas_adams() -> Value {
+        json!({
+            "id": "Q42",
+            "labels": {
+                "en": {"language": "en", "value": "Douglas Adams"}
+            },
+            "descriptions": {
+                "en": {"language": "en", "value": "English writer and humorist (1952–2001)"}
+            },
+            "claims": {
+                "P31": [
+                    {"mainsnak": {"datavalue": {"value": {"entity-type": "item", "id": "Q5"}, "type": "wikibase-entityid"}}}
+                ],
+                "P856": [
+                    {"mainsnak": {"datavalue": {"value": "https://douglasadams.com/", "type": "string"}}}
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn test_parse_entity_fills_metadata() {
+        let query = SearchQuery {
+            q: "douglas adams".to_string(),
+            language: "de-DE".to_string(),
+            ..Default::default()
+        };
+        let languages = label_languages(&query);
+        assert_eq!(languages, vec!["de", "en"]);
+
+        // No German label, so the English one is used.
+        let (mut result, instance_ids) = Wikidata.parse_entity(&douglas_adams(), &languages).unwrap();
+        assert_eq!(result.title, "Douglas Adams");
+        assert_eq!(result.url, "https://www.wikidata.org/wiki/Q42");
+        assert_eq!(instance_ids, vec!["Q5"]);
+
+        let labels = json!({
+            "Q5": {"labels": {"de": {"language": "de", "value": "Mensch"}, "en": {"language": "en", "value": "human"}}}
+        });
+        Wikidata::set_instance_of(&mut result, &instance_ids, &labels, &languages);
+
+        assert_eq!(result.metadata["wikidata_id"], "Q42");
+        assert_eq!(result.metadata["description"], "English writer and humorist (1952–2001)");
+        assert_eq!(result.metadata["instance_of"], "Mensch");
+        assert_eq!(result.metadata["official_website"], "https://douglasadams.com/");
+    }
+
+    #[test]
+    fn test_label_falls_back_to_any_language() {
+        let entity = json!({
+            "id": "Q1",
+            "labels": {"fr": {"language": "fr", "value": "Univers"}}
+        });
+        let (result, instance_ids) = Wikidata.parse_entity(&entity, &["en".to_string()]).unwrap();
+        assert_eq!(result.title, "Univers");
+        assert!(instance_ids.is_empty());
+        assert!(!result.metadata.contains_key("description"));
+    }
+}
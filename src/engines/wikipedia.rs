@@ -1,6 +1,6 @@
 use crate::config::EngineConfig;
 use crate::engines::error::EngineError;
-use crate::engines::SearchEngine;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
 use crate::models::{ResultContent, SearchQuery, SearchResult};
 use async_trait::async_trait;
 use reqwest::Client;
@@ -8,6 +8,24 @@ use std::collections::HashMap;
 
 pub struct Wikipedia;
 
+/// Wikipedia editions served, by subdomain.
+const WIKI_LANGUAGES: &[&str] = &[
+    "af", "ar", "az", "be", "bg", "bn", "bs", "ca", "cs", "cy", "da", "de", "el", "en", "eo",
+    "es", "et", "eu", "fa", "fi", "fr", "ga", "gl", "he", "hi", "hr", "hu", "hy", "id", "is",
+    "it", "ja", "ka", "kk", "ko", "la", "lt", "lv", "mk", "ml", "mr", "ms", "nl", "nn", "no",
+    "pl", "pt", "ro", "ru", "sh", "sk", "sl", "sq", "sr", "sv", "sw", "ta", "te", "th", "tl",
+    "tr", "uk", "ur", "uz", "vi", "zh",
+];
+
+/// Wiki language subdomain for the query, e.g. `de` for `de-CH`. Defaults to `en`.
+pub fn wiki_language(query: &SearchQuery) -> &str {
+    if query.language.is_empty() {
+        "en"
+    } else {
+        query.language.split('-').next().unwrap_or("en")
+    }
+}
+
 #[async_trait]
 impl SearchEngine for Wikipedia {
     fn id(&self) -> String {
@@ -18,21 +36,35 @@ impl SearchEngine for Wikipedia {
         "Wikipedia".to_string()
     }
 
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.wikipedia.org", true, "JSON")
+            .with_privacy_policy("https://foundation.wikimedia.org/wiki/Privacy_policy")
+    }
+
     fn categories(&self) -> Vec<String> {
         vec!["general".to_string()]
     }
 
+    fn supported_languages(&self) -> Option<Vec<String>> {
+        Some(WIKI_LANGUAGES.iter().map(|language| language.to_string()).collect())
+    }
+
+    /// Search offsets stop at 10,000, which is page 500 at 20 results per page.
+    fn max_page(&self) -> Option<u32> {
+        Some(500)
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://en.wikipedia.org/".to_string())
+    }
+
     async fn search(
         &self,
         query: &SearchQuery,
         client: &Client,
-        _config: &EngineConfig,
+        config: &EngineConfig,
     ) -> Result<Vec<SearchResult>, EngineError> {
-        let language = if query.language.is_empty() {
-            "en"
-        } else {
-            query.language.split('-').next().unwrap_or("en")
-        };
+        let language = wiki_language(query);
 
         let url = format!("https://{}.wikipedia.org/w/api.php", language);
 
@@ -54,11 +86,9 @@ impl SearchEngine for Wikipedia {
             ("exsentences", "2"),
         ];
 
-        let resp = client.get(&url).query(&params).send().await?;
+        let resp = http::send(apply_headers(client.get(&url).query(&params), config)).await?;
 
-        if !resp.status().is_success() {
-             return Err(EngineError::Unexpected(anyhow::anyhow!("Wikipedia returned {}", resp.status())));
-        }
+        error_for_status(&self.id(), &resp)?;
 
         let body: serde_json::Value = resp.json().await?;
 
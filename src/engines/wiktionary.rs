@@ -0,0 +1,213 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::wikipedia::wiki_language;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use std::collections::HashMap;
+
+/// Section headings naming a part of speech on English Wiktionary.
+const PARTS_OF_SPEECH: &[&str] = &[
+    "Noun",
+    "Proper noun",
+    "Verb",
+    "Adjective",
+    "Adverb",
+    "Pronoun",
+    "Preposition",
+    "Conjunction",
+    "Interjection",
+    "Determiner",
+    "Article",
+    "Numeral",
+    "Particle",
+    "Phrase",
+    "Prefix",
+    "Suffix",
+];
+
+/// Dictionary entries from Wiktionary. Full page extracts are only served
+/// one page at a time, so the best matching entry is returned.
+pub struct Wiktionary;
+
+/// The heading text of a `== Heading ==` line and its level.
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '=').count();
+    if level < 2 || !line.ends_with('=') {
+        return None;
+    }
+    Some((level, line.trim_matches('=').trim()))
+}
+
+/// Reads the first language section of a plain text extract with wiki-style
+/// headings: its parts of speech, and the first definition, which follows
+/// the headword line under the first part-of-speech heading.
+pub fn parse_extract(extract: &str) -> (Option<String>, Vec<String>) {
+    let mut parts_of_speech: Vec<String> = Vec::new();
+    let mut definition = None;
+    let mut seen_language = false;
+    // Lines read under the current part-of-speech heading, if in one.
+    let mut under_pos: Option<usize> = None;
+
+    for line in extract.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some((level, name)) = heading(line) {
+            if level == 2 {
+                if seen_language {
+                    break;
+                }
+                seen_language = true;
+            }
+            under_pos = None;
+            if PARTS_OF_SPEECH.contains(&name) {
+                if !parts_of_speech.iter().any(|pos| pos == name) {
+                    parts_of_speech.push(name.to_string());
+                }
+                under_pos = Some(0);
+            }
+            continue;
+        }
+
+        if let Some(lines) = under_pos.as_mut() {
+            *lines += 1;
+            if *lines == 2 && definition.is_none() {
+                definition = Some(line.to_string());
+            }
+        }
+    }
+
+    (definition, parts_of_speech)
+}
+
+impl Wiktionary {
+    fn parse_response(&self, body: &serde_json::Value, language: &str) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        if let Some(pages) = body["query"]["pages"].as_object() {
+            for page in pages.values() {
+                let title = page["title"].as_str().unwrap_or_default().to_string();
+                if title.is_empty() {
+                    continue;
+                }
+                let extract = page["extract"].as_str().unwrap_or_default();
+                let (definition, parts_of_speech) = parse_extract(extract);
+
+                let mut metadata = HashMap::new();
+                if !parts_of_speech.is_empty() {
+                    metadata.insert("part_of_speech".to_string(), parts_of_speech.join(", "));
+                }
+
+                results.push(SearchResult {
+                    url: format!("https://{}.wiktionary.org/wiki/{}", language, title.replace(' ', "_")),
+                    title,
+                    content: ResultContent::Text(definition.unwrap_or_default()),
+                    engines: vec![self.id()],
+                    score: 1.0,
+                    metadata,
+                });
+            }
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Wiktionary {
+    fn id(&self) -> String {
+        "wiktionary".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Wiktionary".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.wiktionary.org", true, "JSON")
+            .with_privacy_policy("https://foundation.wikimedia.org/wiki/Privacy_policy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string(), "dictionary".to_string()]
+    }
+
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://en.wiktionary.org/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let language = wiki_language(query);
+        let url = format!("https://{}.wiktionary.org/w/api.php", language);
+
+        let params = [
+            ("action", "query"),
+            ("format", "json"),
+            ("generator", "search"),
+            ("gsrsearch", &query.q),
+            ("gsrlimit", "1"),
+            ("prop", "extracts"),
+            ("explaintext", "1"),
+            ("exsectionformat", "wiki"),
+        ];
+
+        let resp = http::send(apply_headers(client.get(&url).query(&params), config)).await?;
+
+        error_for_status(&self.id(), &resp)?;
+
+        let body: serde_json::Value = resp.json().await?;
+
+        Ok(self.parse_response(&body, language))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const EXTRACT: &str = "\n\n== English ==\n\n\n=== Etymology ===\nFrom Medieval Latin dictionarium.\n\n\n=== Noun ===\ndictionary (plural dictionaries)\n\nA reference work listing words and giving their meanings.\nAny work that has a list of material organized alphabetically.\n\n\n=== Verb ===\ndictionary (third-person singular simple present dictionaries)\n\n(rare) To look up in a dictionary.\n\n\n== French ==\n\n\n=== Adjective ===\nnot this one\n\nNor this.";
+
+    #[test]
+    fn test_parse_extract() {
+        let (definition, parts_of_speech) = parse_extract(EXTRACT);
+        assert_eq!(
+            definition.as_deref(),
+            Some("A reference work listing words and giving their meanings.")
+        );
+        // Only the first language section counts.
+        assert_eq!(parts_of_speech, vec!["Noun", "Verb"]);
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let body = json!({
+            "query": {
+                "pages": {
+                    "12345": {
+                        "pageid": 12345,
+                        "title": "dictionary",
+                        "extract": EXTRACT
+                    }
+                }
+            }
+        });
+
+        let results = Wiktionary.parse_response(&body, "en");
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].url, "https://en.wiktionary.org/wiki/dictionary");
+        assert_eq!(results[0].metadata["part_of_speech"], "Noun, Verb");
+        assert!(matches!(
+            &results[0].content,
+            ResultContent::Text(text) if text.starts_with("A reference work")
+        ));
+    }
+}
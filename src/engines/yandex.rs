@@ -0,0 +1,198 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::selectors::{parse_with_selectors, ScraperSelectors};
+use crate::engines::{
+    apply_headers, error_for_status, http, resolve_url, response_text, EngineAbout, SearchEngine,
+};
+use crate::models::{SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use url::Url;
+
+const SEARCH_URL: &str = "https://yandex.com/search/";
+
+/// Markers of the page Yandex serves instead of results when it wants a captcha solved.
+const CAPTCHA_MARKERS: &[&str] = &["/showcaptcha", "/checkcaptcha", "CheckboxCaptcha"];
+
+pub struct Yandex;
+
+/// Resolves a result link and unwraps Yandex click-tracking redirects such as
+/// `https://yandex.com/clck/jsredir?...&url=https%3A%2F%2Fexample.com%2F` to
+/// their destination.
+pub fn unwrap_redirect(href: &str) -> Option<String> {
+    let url = Url::parse(&resolve_url(SEARCH_URL, href)?).ok()?;
+
+    let is_yandex = url
+        .host_str()
+        .is_some_and(|host| host.split('.').any(|label| label == "yandex"));
+    if !is_yandex || !url.path().starts_with("/clck/") {
+        return Some(url.to_string());
+    }
+
+    url.query_pairs()
+        .find(|(key, _)| key == "url")
+        .and_then(|(_, target)| resolve_url(SEARCH_URL, &target))
+}
+
+/// Whether the page is a captcha challenge rather than results.
+fn is_captcha(html: &str) -> bool {
+    CAPTCHA_MARKERS.iter().any(|marker| html.contains(marker))
+}
+
+impl Yandex {
+    fn selectors() -> ScraperSelectors {
+        ScraperSelectors::new(
+            "li.serp-item",
+            "h2",
+            "a.OrganicTitle-Link, h2 a",
+            ".OrganicText, .organic__content-wrapper, .text-container",
+        )
+    }
+
+    /// Yandex numbers pages from 0 in `p`.
+    fn params(query: &SearchQuery) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("text", query.q.clone()),
+            ("p", query.page.saturating_sub(1).to_string()),
+        ];
+        if query.safesearch > 0 {
+            params.push(("fyandex", "1".to_string()));
+        }
+        params
+    }
+
+    fn parse_results(&self, html: &str, selectors: &ScraperSelectors) -> Result<Vec<SearchResult>, EngineError> {
+        if is_captcha(html) {
+            return Err(EngineError::RateLimited { retry_after: None });
+        }
+
+        let results = parse_with_selectors(html, selectors, SEARCH_URL, &self.id())?;
+        Ok(results
+            .into_iter()
+            .filter_map(|mut result| {
+                result.url = unwrap_redirect(&result.url)?;
+                Some(result)
+            })
+            .collect())
+    }
+}
+
+#[async_trait]
+impl SearchEngine for Yandex {
+    fn id(&self) -> String {
+        "yandex".to_string()
+    }
+
+    fn name(&self) -> String {
+        "Yandex".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://yandex.com", false, "HTML")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["general".to_string()]
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://yandex.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let request = client.get(SEARCH_URL).query(&Self::params(query));
+        let resp = http::send(apply_headers(request, config)).await?;
+
+        // The captcha is usually served after a redirect to `/showcaptcha`.
+        if resp.url().path().starts_with("/showcaptcha") {
+            return Err(EngineError::RateLimited { retry_after: None });
+        }
+        error_for_status(&self.id(), &resp)?;
+
+        let text = response_text(resp, config).await?;
+        let selectors = Self::selectors().with_override(query.selector_overrides.get(&self.id()));
+
+        self.parse_results(&text, &selectors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ResultContent;
+
+    const FIXTURE: &str = r#"
+        <html><body>
+        <ul id="search-result">
+            <li class="serp-item" data-cid="0">
+                <a class="OrganicTitle-Link" href="https://yandex.com/clck/jsredir?from=yandex.com&amp;url=https%3A%2F%2Fwww.rust-lang.org%2Flearn&amp;sign=abc">
+                    <h2>Learn Rust</h2>
+                </a>
+                <div class="OrganicText">Get started with Rust.</div>
+            </li>
+            <li class="serp-item" data-cid="1">
+                <h2><a href="https://doc.rust-lang.org/book/">The Book</a></h2>
+            </li>
+            <li class="serp-item" data-cid="2">
+                <div class="AdvLabel">Advertisement without a title</div>
+            </li>
+        </ul>
+        </body></html>
+    "#;
+
+    #[test]
+    fn test_parse_results_unwraps_redirects() {
+        let results = Yandex.parse_results(FIXTURE, &Yandex::selectors()).unwrap();
+        assert_eq!(results.len(), 2);
+
+        assert_eq!(results[0].url, "https://www.rust-lang.org/learn");
+        assert_eq!(results[0].title, "Learn Rust");
+        assert!(matches!(&results[0].content, ResultContent::Text(text) if text == "Get started with Rust."));
+
+        assert_eq!(results[1].url, "https://doc.rust-lang.org/book/");
+    }
+
+    #[test]
+    fn test_unwrap_redirect() {
+        assert_eq!(
+            unwrap_redirect("//yandex.ru/clck/jsredir?url=https%3A%2F%2Fexample.com%2Fa%3Fb%3D1").as_deref(),
+            Some("https://example.com/a?b=1")
+        );
+        assert_eq!(unwrap_redirect("https://example.com/").as_deref(), Some("https://example.com/"));
+        assert_eq!(unwrap_redirect("/clck/jsredir?from=yandex.com"), None);
+    }
+
+    #[test]
+    fn test_captcha_page_is_rate_limited() {
+        let html = r#"<form action="/checkcaptcha?key=abc"><div class="CheckboxCaptcha"></div></form>"#;
+        let err = Yandex.parse_results(html, &Yandex::selectors()).unwrap_err();
+        assert!(matches!(err, EngineError::RateLimited { retry_after: None }));
+    }
+
+    #[test]
+    fn test_params() {
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            page: 2,
+            safesearch: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            Yandex::params(&query),
+            vec![
+                ("text", "rust".to_string()),
+                ("p", "1".to_string()),
+                ("fyandex", "1".to_string()),
+            ]
+        );
+    }
+}
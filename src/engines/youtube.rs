@@ -0,0 +1,296 @@
+use crate::config::EngineConfig;
+use crate::engines::error::EngineError;
+use crate::engines::podcasts::format_duration;
+use crate::engines::{apply_headers, error_for_status, http, EngineAbout, SearchEngine};
+use crate::models::{metadata_keys, ResultContent, SearchQuery, SearchResult};
+use async_trait::async_trait;
+use reqwest::Client;
+use scraper::Html;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const SEARCH_URL: &str = "https://www.googleapis.com/youtube/v3/search";
+const VIDEOS_URL: &str = "https://www.googleapis.com/youtube/v3/videos";
+
+const MAX_RESULTS: u32 = 10;
+
+/// YouTube videos via the Data API v3. The API key is the first entry of
+/// `tokens`; without one the engine returns nothing. The API pages with
+/// opaque tokens, so only the first page is served.
+pub struct YouTube;
+
+/// Parses an ISO 8601 duration such as `PT1H2M3S` into seconds.
+pub fn parse_iso8601_duration(value: &str) -> Option<u64> {
+    let rest = value.strip_prefix('P')?;
+    let (days, time) = match rest.split_once('T') {
+        Some((days, time)) => (days, time),
+        None => (rest, ""),
+    };
+
+    const DATE_UNITS: &[(char, u64)] = &[('W', 604_800), ('D', 86_400)];
+    const TIME_UNITS: &[(char, u64)] = &[('H', 3600), ('M', 60), ('S', 1)];
+
+    let mut seconds = 0;
+    for (part, units) in [(days, DATE_UNITS), (time, TIME_UNITS)] {
+        let mut number = String::new();
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let (_, unit) = units.iter().find(|(designator, _)| *designator == c)?;
+            seconds += number.parse::<u64>().ok()? * unit;
+            number.clear();
+        }
+        if !number.is_empty() {
+            return None;
+        }
+    }
+    Some(seconds)
+}
+
+/// The API escapes HTML entities in titles and descriptions.
+fn unescape(text: &str) -> String {
+    Html::parse_fragment(text).root_element().text().collect()
+}
+
+impl YouTube {
+    fn safesearch(query: &SearchQuery) -> &'static str {
+        match query.safesearch {
+            0 => "none",
+            1 => "moderate",
+            _ => "strict",
+        }
+    }
+
+    /// Durations from a `videos?part=contentDetails` response, by video id.
+    fn parse_durations(body: &Value) -> HashMap<String, String> {
+        body["items"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let id = item["id"].as_str()?;
+                let seconds = parse_iso8601_duration(item["contentDetails"]["duration"].as_str()?)?;
+                Some((id.to_string(), format_duration(seconds * 1000)))
+            })
+            .collect()
+    }
+
+    fn parse_response(&self, body: &Value, durations: &HashMap<String, String>) -> Vec<SearchResult> {
+        let mut results = Vec::new();
+
+        let items = match body["items"].as_array() {
+            Some(items) => items,
+            None => return results,
+        };
+
+        for item in items {
+            let Some(video_id) = item["id"]["videoId"].as_str() else {
+                continue;
+            };
+            let snippet = &item["snippet"];
+            let title = unescape(snippet["title"].as_str().unwrap_or_default());
+
+            let thumbnails = &snippet["thumbnails"];
+            let thumbnail = ["high", "medium", "default"]
+                .iter()
+                .find_map(|size| thumbnails[*size]["url"].as_str())
+                .map(|url| url.to_string());
+
+            let mut metadata = HashMap::new();
+            if let Some(channel) = snippet["channelTitle"].as_str() {
+                metadata.insert("channel".to_string(), channel.to_string());
+            }
+            if let Some(date) = snippet["publishedAt"].as_str() {
+                metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), date.to_string());
+            }
+            if let Some(description) = snippet["description"].as_str().filter(|d| !d.is_empty()) {
+                metadata.insert("description".to_string(), unescape(description));
+            }
+            if let Some(thumbnail) = &thumbnail {
+                metadata.insert(metadata_keys::THUMBNAIL.to_string(), thumbnail.clone());
+            }
+
+            results.push(SearchResult {
+                url: format!("https://www.youtube.com/watch?v={}", video_id),
+                title,
+                content: ResultContent::Video {
+                    src: format!("https://www.youtube-nocookie.com/embed/{}", video_id),
+                    thumbnail,
+                    duration: durations.get(video_id).cloned(),
+                },
+                engines: vec![self.id()],
+                score: 1.0,
+                metadata,
+            });
+        }
+
+        results
+    }
+}
+
+#[async_trait]
+impl SearchEngine for YouTube {
+    fn id(&self) -> String {
+        "youtube".to_string()
+    }
+
+    fn name(&self) -> String {
+        "YouTube".to_string()
+    }
+
+    fn about(&self) -> EngineAbout {
+        EngineAbout::new("https://www.youtube.com", true, "JSON")
+            .with_privacy_policy("https://policies.google.com/privacy")
+    }
+
+    fn categories(&self) -> Vec<String> {
+        vec!["videos".to_string()]
+    }
+
+    fn supports_safesearch(&self) -> bool {
+        true
+    }
+
+    fn paging(&self) -> bool {
+        false
+    }
+
+    fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+        Some("https://www.googleapis.com/".to_string())
+    }
+
+    async fn search(
+        &self,
+        query: &SearchQuery,
+        client: &Client,
+        config: &EngineConfig,
+    ) -> Result<Vec<SearchResult>, EngineError> {
+        let Some(key) = config.tokens.first() else {
+            return Ok(vec![]);
+        };
+        let mut params = vec![
+            ("part", "snippet".to_string()),
+            ("type", "video".to_string()),
+            ("q", query.q.clone()),
+            ("maxResults", MAX_RESULTS.to_string()),
+            ("safeSearch", Self::safesearch(query).to_string()),
+            ("key", key.clone()),
+        ];
+        if let Some(language) = query.language_code() {
+            params.push(("relevanceLanguage", language));
+        }
+
+        let resp = http::send(apply_headers(client.get(SEARCH_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let body: Value = resp.json().await?;
+
+        let ids: Vec<&str> = body["items"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|item| item["id"]["videoId"].as_str())
+            .collect();
+        if ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Search results carry no duration, so it comes from the videos endpoint.
+        let params = [
+            ("part", "contentDetails".to_string()),
+            ("id", ids.join(",")),
+            ("key", key.clone()),
+        ];
+        let resp = http::send(apply_headers(client.get(VIDEOS_URL).query(&params), config)).await?;
+        error_for_status(&self.id(), &resp)?;
+        let durations = Self::parse_durations(&resp.json().await?);
+
+        Ok(self.parse_response(&body, &durations))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_iso8601_duration() {
+        assert_eq!(parse_iso8601_duration("PT4M13S"), Some(253));
+        assert_eq!(parse_iso8601_duration("PT1H2M3S"), Some(3723));
+        assert_eq!(parse_iso8601_duration("PT45S"), Some(45));
+        assert_eq!(parse_iso8601_duration("P1DT2H"), Some(93_600));
+        assert_eq!(parse_iso8601_duration("P0D"), Some(0));
+        assert_eq!(parse_iso8601_duration("PT5X"), None);
+        assert_eq!(parse_iso8601_duration("PT12"), None);
+        assert_eq!(parse_iso8601_duration("4:13"), None);
+    }
+
+    #[test]
+    fn test_parse_response_with_durations() {
+        let search = json!({
+            "items": [
+                {
+                    "id": {"kind": "youtube#video", "videoId": "dQw4w9WgXcQ"},
+                    "snippet": {
+                        "publishedAt": "2009-10-25T06:57:33Z",
+                        "title": "Rick Astley - Never Gonna Give You Up (Official Music Video)",
+                        "description": "The official video for &quot;Never Gonna Give You Up&quot;",
+                        "channelTitle": "Rick Astley",
+                        "thumbnails": {
+                            "default": {"url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/default.jpg"},
+                            "high": {"url": "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"}
+                        }
+                    }
+                },
+                {
+                    "id": {"kind": "youtube#channel", "channelId": "UCuAXFkgsw1L7xaCfnd5JJOw"},
+                    "snippet": {"title": "Rick Astley"}
+                },
+                {
+                    "id": {"kind": "youtube#video", "videoId": "y6120QOlsfU"},
+                    "snippet": {"title": "Darude - Sandstorm &amp; more"}
+                }
+            ]
+        });
+        let videos = json!({
+            "items": [
+                {"id": "dQw4w9WgXcQ", "contentDetails": {"duration": "PT3M33S"}},
+                {"id": "y6120QOlsfU", "contentDetails": {"duration": "P0D"}}
+            ]
+        });
+
+        let durations = YouTube::parse_durations(&videos);
+        let results = YouTube.parse_response(&search, &durations);
+        assert_eq!(results.len(), 2);
+
+        let rick = &results[0];
+        assert_eq!(rick.url, "https://www.youtube.com/watch?v=dQw4w9WgXcQ");
+        assert_eq!(rick.metadata["channel"], "Rick Astley");
+        assert_eq!(rick.metadata["description"], "The official video for \"Never Gonna Give You Up\"");
+        match &rick.content {
+            ResultContent::Video { src, thumbnail, duration } => {
+                assert_eq!(src, "https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ");
+                assert_eq!(thumbnail.as_deref(), Some("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"));
+                assert_eq!(duration.as_deref(), Some("3:33"));
+            }
+            other => panic!("Expected video, got {:?}", other),
+        }
+
+        assert_eq!(results[1].title, "Darude - Sandstorm & more");
+    }
+
+    #[tokio::test]
+    async fn test_search_without_token_returns_nothing() {
+        let query = SearchQuery {
+            q: "rust".to_string(),
+            ..Default::default()
+        };
+        let results = YouTube
+            .search(&query, &Client::new(), &EngineConfig::default())
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+    }
+}
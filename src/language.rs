@@ -0,0 +1,121 @@
+use crate::models::{metadata_keys, ResultContent, SearchResult};
+
+/// Common function words used to tell Latin-script languages apart.
+const STOPWORDS: &[(&str, &[&str])] = &[
+    ("en", &["the", "and", "of", "to", "is", "in", "that", "for", "with", "are", "this", "on"]),
+    ("de", &["der", "die", "und", "das", "ist", "nicht", "mit", "ein", "eine", "auf", "für", "von"]),
+    ("fr", &["le", "la", "les", "et", "est", "des", "une", "du", "pour", "dans", "pas", "sur"]),
+    (
+        "es",
+        &[
+            "de", "la", "el", "en", "y", "que", "los", "las", "un", "una", "del", "se", "por", "con", "para", "al",
+            "mi", "su", "es", "como",
+        ],
+    ),
+    ("it", &["il", "di", "che", "è", "per", "una", "gli", "con", "non", "sono", "della", "nel"]),
+    ("nl", &["de", "het", "een", "en", "van", "is", "niet", "met", "voor", "op", "zijn", "dat"]),
+    ("pt", &["o", "os", "as", "e", "um", "uma", "do", "da", "não", "para", "com", "são"]),
+];
+
+/// Minimum number of stopword hits before a Latin-script guess is trusted.
+const MIN_STOPWORD_HITS: usize = 2;
+
+/// Returns the language of `text` as an ISO 639-1 code, or `None` if it cannot tell.
+///
+/// Non-Latin scripts are identified by their Unicode block; Latin-script text is
+/// scored by stopword frequency, so very short snippets are usually undetectable.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let mut letters = 0usize;
+    let mut scripts: [(char, char, &'static str, usize); 8] = [
+        ('\u{3040}', '\u{30FF}', "ja", 0),
+        ('\u{AC00}', '\u{D7AF}', "ko", 0),
+        ('\u{4E00}', '\u{9FFF}', "zh", 0),
+        ('\u{0400}', '\u{04FF}', "ru", 0),
+        ('\u{0370}', '\u{03FF}', "el", 0),
+        ('\u{0600}', '\u{06FF}', "ar", 0),
+        ('\u{0590}', '\u{05FF}', "he", 0),
+        ('\u{0E00}', '\u{0E7F}', "th", 0),
+    ];
+
+    for c in text.chars().filter(|c| c.is_alphabetic()) {
+        letters += 1;
+        if let Some(script) = scripts.iter_mut().find(|(lo, hi, _, _)| (*lo..=*hi).contains(&c)) {
+            script.3 += 1;
+        }
+    }
+
+    if letters == 0 {
+        return None;
+    }
+
+    // Kana marks Japanese even when mixed with Han characters.
+    if scripts[0].3 > 0 {
+        return Some("ja");
+    }
+    if let Some((_, _, lang, count)) = scripts.iter().max_by_key(|(_, _, _, count)| *count) {
+        if *count * 2 >= letters {
+            return Some(*lang);
+        }
+    }
+
+    let lowered = text.to_lowercase();
+    let words: Vec<&str> = lowered
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    let mut best: Option<(&'static str, usize)> = None;
+    for (lang, stopwords) in STOPWORDS {
+        let hits = words.iter().filter(|w| stopwords.contains(w)).count();
+        if hits > best.map(|(_, h)| h).unwrap_or(0) {
+            best = Some((*lang, hits));
+        }
+    }
+
+    best.filter(|(_, hits)| *hits >= MIN_STOPWORD_HITS).map(|(lang, _)| lang)
+}
+
+/// Primary subtag of a language tag, lowercased: `en-US` becomes `en`.
+pub fn primary_subtag(tag: &str) -> String {
+    tag.split(['-', '_']).next().unwrap_or_default().to_lowercase()
+}
+
+/// Language of a result: the engine-provided `language` metadata if present,
+/// otherwise detected from the title and text content.
+pub fn result_language(res: &SearchResult) -> Option<String> {
+    if let Some(lang) = res.metadata.get(metadata_keys::LANGUAGE).filter(|l| !l.is_empty()) {
+        return Some(primary_subtag(lang));
+    }
+
+    let mut text = res.title.clone();
+    if let ResultContent::Text(content) = &res.content {
+        text.push(' ');
+        text.push_str(content);
+    }
+    detect_language(&text).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(detect_language("The quick brown fox jumps over the lazy dog and the cat"), Some("en"));
+        assert_eq!(detect_language("Der Hund ist nicht mit der Katze verwandt"), Some("de"));
+        assert_eq!(detect_language("Le chat est sur la table dans la cuisine"), Some("fr"));
+        assert_eq!(detect_language("La casa de la familia de mi madre"), Some("es"));
+        assert_eq!(detect_language("De kat zit op de tafel in het huis van een vriend"), Some("nl"));
+        assert_eq!(detect_language("Язык программирования Rust"), Some("ru"));
+        assert_eq!(detect_language("プログラミング言語"), Some("ja"));
+        assert_eq!(detect_language("Rust"), None);
+        assert_eq!(detect_language(""), None);
+    }
+
+    #[test]
+    fn test_primary_subtag() {
+        assert_eq!(primary_subtag("en-US"), "en");
+        assert_eq!(primary_subtag("pt_BR"), "pt");
+        assert_eq!(primary_subtag("DE"), "de");
+    }
+}
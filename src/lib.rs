@@ -1,4 +1,10 @@
+pub mod answerers;
+pub mod bangs;
 pub mod config;
 pub mod engines;
+pub mod language;
+pub mod metrics;
 pub mod models;
+pub mod query;
+pub mod shutdown;
 pub mod web;
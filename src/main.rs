@@ -1,18 +1,44 @@
 use arc_swap::ArcSwap;
 use notify::{RecursiveMode, Watcher};
+use searxng_rs::answerers::currency::CurrencyAnswerer;
+use searxng_rs::answerers::duckduckgo::DuckDuckGoAnswerer;
+use searxng_rs::answerers::weather::WeatherAnswerer;
 use searxng_rs::config::Settings;
+use searxng_rs::engines::arxiv::Arxiv;
 use searxng_rs::engines::bing::Bing;
+use searxng_rs::engines::brave::Brave;
+use searxng_rs::engines::cratesio::CratesIo;
+use searxng_rs::engines::crossref::Crossref;
+use searxng_rs::engines::dictionary::Dictionary;
 use searxng_rs::engines::{create_client, DEFAULT_USER_AGENT};
 use searxng_rs::engines::duckduckgo::DuckDuckGo;
 use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::genius::Genius;
 use searxng_rs::engines::google::Google;
+use searxng_rs::engines::mojeek::Mojeek;
+use searxng_rs::engines::openstreetmap::OpenStreetMap;
+use searxng_rs::engines::podcasts::Podcasts;
+use searxng_rs::engines::pypi::PyPi;
 use searxng_rs::engines::qwant::Qwant;
 use searxng_rs::engines::reddit::Reddit;
 use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::engines::searxng::Searxng;
+use searxng_rs::engines::shopping::Shopping;
+use searxng_rs::engines::stackexchange::StackExchange;
+use searxng_rs::engines::startpage::Startpage;
+use searxng_rs::engines::wikidata::Wikidata;
 use searxng_rs::engines::wikipedia::Wikipedia;
+use searxng_rs::engines::wiktionary::Wiktionary;
+use searxng_rs::engines::yandex::Yandex;
+use searxng_rs::engines::youtube::YouTube;
+use searxng_rs::metrics;
+use searxng_rs::shutdown::{drain_with_timeout, shutdown_signal};
 use searxng_rs::web;
 use searxng_rs::web::AppState;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -34,7 +60,16 @@ async fn main() -> anyhow::Result<()> {
             .init();
     }
 
-    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new()?)));
+    metrics::install();
+
+    let missing_assets = web::missing_static_assets();
+    if !missing_assets.is_empty() {
+        tracing::error!("Missing embedded static assets: {:?}", missing_assets);
+    }
+
+    let initial_settings = Settings::new()?;
+    initial_settings.validate()?;
+    let settings = Arc::new(ArcSwap::from(Arc::new(initial_settings)));
 
     // Setup hot reloading
     let settings_clone = settings.clone();
@@ -42,7 +77,7 @@ async fn main() -> anyhow::Result<()> {
         if let Ok(event) = res {
             if event.kind.is_modify() || event.kind.is_create() {
                 tracing::info!("Config file changed, reloading...");
-                match Settings::new() {
+                match Settings::new().and_then(|s| s.validate().map(|_| s)) {
                     Ok(new_settings) => {
                         settings_clone.store(Arc::new(new_settings));
                         tracing::info!("Config reloaded successfully");
@@ -62,14 +97,45 @@ async fn main() -> anyhow::Result<()> {
     registry.register_engine(Box::new(DuckDuckGo));
     registry.register_engine(Box::new(Google));
     registry.register_engine(Box::new(Bing));
+    registry.register_engine(Box::new(Brave));
+    registry.register_engine(Box::new(Startpage::new()));
+    registry.register_engine(Box::new(Mojeek));
+    registry.register_engine(Box::new(Yandex));
     registry.register_engine(Box::new(Wikipedia));
+    registry.register_engine(Box::new(Wikidata));
+    registry.register_engine(Box::new(Wiktionary));
+    registry.register_engine(Box::new(Dictionary));
     registry.register_engine(Box::new(Reddit));
+    registry.register_engine(Box::new(YouTube));
+    registry.register_engine(Box::new(Genius));
     registry.register_engine(Box::new(Qwant));
+    registry.register_engine(Box::new(Crossref));
+    registry.register_engine(Box::new(Arxiv));
+    registry.register_engine(Box::new(CratesIo));
+    registry.register_engine(Box::new(PyPi));
+    registry.register_engine(Box::new(StackExchange));
+    registry.register_engine(Box::new(Podcasts));
+    registry.register_engine(Box::new(OpenStreetMap));
+    registry.register_engine(Box::new(Shopping));
+    registry.register_engine(Box::new(Searxng::new(settings.clone())));
+    registry.register_answerer(Box::new(CurrencyAnswerer::new()));
+    registry.register_answerer(Box::new(WeatherAnswerer::new()));
+    registry.register_answerer(Box::new(DuckDuckGoAnswerer));
     let registry = Arc::new(registry);
 
+    if settings.load().general.warmup_engines {
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            let warmed = registry.warmup().await;
+            tracing::info!("Warmed up connections to {} engine hosts", warmed);
+        });
+    }
+
     let state = AppState {
         settings: settings.clone(),
         registry,
+        started: std::time::Instant::now(),
+        search_slots: web::search_slots(&settings.load()),
     };
 
     let app = web::router(state);
@@ -83,7 +149,27 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        tracing::info!("Shutdown requested, draining in-flight requests");
+        let _ = shutdown_tx.send(true);
+    });
+
+    let wait_for_shutdown = |mut rx: tokio::sync::watch::Receiver<bool>| async move {
+        let _ = rx.wait_for(|requested| *requested).await;
+    };
+
+    let server = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+        .with_graceful_shutdown(wait_for_shutdown(shutdown_rx.clone()))
+        .into_future();
+
+    // Read the timeout at startup; a hot-reloaded value applies on the next run.
+    let drain_timeout = Duration::from_secs(current_settings.server.shutdown_timeout);
+    match drain_with_timeout(server, wait_for_shutdown(shutdown_rx), drain_timeout).await {
+        Some(result) => result?,
+        None => tracing::warn!("In-flight requests did not finish within {:?}, forcing exit", drain_timeout),
+    }
 
     Ok(())
 }
@@ -0,0 +1,63 @@
+use ::metrics::{counter, histogram};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+const REQUESTS: &str = "searxng_engine_requests_total";
+const SUCCESSES: &str = "searxng_engine_successes_total";
+const FAILURES: &str = "searxng_engine_failures_total";
+const TIMEOUTS: &str = "searxng_engine_timeouts_total";
+const CIRCUIT_OPEN: &str = "searxng_engine_circuit_open_total";
+const RESPONSE_TIME: &str = "searxng_engine_response_time_seconds";
+
+/// From 10 ms to 10 s, the range between a cached answer and the longest engine timeout.
+const RESPONSE_TIME_BUCKETS: &[f64] = &[0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// How an engine query ended, as far as the metrics are concerned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineOutcome {
+    Success,
+    /// Errors of any kind, rate limiting included.
+    Failure,
+    Timeout,
+}
+
+/// Installs the Prometheus recorder on first use. Metrics recorded before
+/// this is called are dropped.
+pub fn install() -> &'static PrometheusHandle {
+    HANDLE.get_or_init(|| {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets_for_metric(Matcher::Full(RESPONSE_TIME.to_string()), RESPONSE_TIME_BUCKETS)
+            .expect("response time buckets are not empty")
+            .build_recorder();
+        let handle = recorder.handle();
+        if let Err(e) = ::metrics::set_global_recorder(recorder) {
+            tracing::warn!("Metrics recorder already installed: {}", e);
+        }
+        handle
+    })
+}
+
+/// All metrics in the Prometheus text format.
+pub fn render() -> String {
+    install().render()
+}
+
+/// Records an engine skipped because its circuit breaker is open.
+pub fn record_circuit_open(engine: &str) {
+    counter!(CIRCUIT_OPEN, "engine" => engine.to_string()).increment(1);
+}
+
+/// Records a query sent to an engine, retries included, and how it ended.
+pub fn record_engine_request(engine: &str, outcome: EngineOutcome, elapsed: Duration) {
+    counter!(REQUESTS, "engine" => engine.to_string()).increment(1);
+    let name = match outcome {
+        EngineOutcome::Success => SUCCESSES,
+        EngineOutcome::Failure => FAILURES,
+        EngineOutcome::Timeout => TIMEOUTS,
+    };
+    counter!(name, "engine" => engine.to_string()).increment(1);
+    histogram!(RESPONSE_TIME, "engine" => engine.to_string()).record(elapsed.as_secs_f64());
+}
@@ -1,3 +1,6 @@
+use crate::engines::selectors::SelectorOverrides;
+use crate::query::ParsedQuery;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -8,8 +11,12 @@ fn default_page() -> u32 {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchQuery {
     pub q: String,
-    #[serde(default)]
+    /// Language tag such as `en` or `en-US`; also accepted as `locale`.
+    #[serde(default, alias = "locale")]
     pub language: String,
+    /// Region such as `US`. Derived from `language` when not given.
+    #[serde(default)]
+    pub country: String,
     #[serde(default = "default_page")]
     pub page: u32,
     #[serde(default)]
@@ -20,6 +27,33 @@ pub struct SearchQuery {
     pub time_range: String,
     #[serde(default)]
     pub format: String,
+    /// Result ordering: empty for relevance, `date` for newest first.
+    #[serde(default)]
+    pub sort: String,
+    /// Comma-separated engine ids for this request. An empty value means the defaults.
+    #[serde(default)]
+    pub engines: Option<String>,
+    /// Engines selected in the preferences cookie; `engines` takes precedence.
+    #[serde(skip)]
+    pub cookie_engines: Option<String>,
+    /// Engines chosen with a `!bang`, run regardless of their categories.
+    #[serde(skip)]
+    pub bang_engines: Option<Vec<String>>,
+    /// `1` to fail the request instead of returning partial results when a
+    /// critical engine fails.
+    #[serde(default)]
+    pub strict: u8,
+    /// Number of SearXNG instances this query was forwarded through, taken
+    /// from the hop-count header.
+    #[serde(skip)]
+    pub hops: u8,
+    /// Base64 JSON of per-engine scraper selectors to try for this request.
+    /// Only honored in debug mode.
+    #[serde(default)]
+    pub selector_override: Option<String>,
+    /// Decoded `selector_override`, empty unless in debug mode.
+    #[serde(skip)]
+    pub selector_overrides: SelectorOverrides,
 }
 
 impl Default for SearchQuery {
@@ -27,25 +61,80 @@ impl Default for SearchQuery {
         Self {
             q: "".to_string(),
             language: "".to_string(),
+            country: "".to_string(),
             page: default_page(),
             safesearch: 0,
             categories: "".to_string(),
             time_range: "".to_string(),
             format: "".to_string(),
+            sort: "".to_string(),
+            engines: None,
+            cookie_engines: None,
+            bang_engines: None,
+            strict: 0,
+            hops: 0,
+            selector_override: None,
+            selector_overrides: SelectorOverrides::new(),
         }
     }
 }
 
+/// Invisible characters some clients inject into copied or typed queries.
+const ZERO_WIDTH_CHARS: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
 impl SearchQuery {
+    /// Returns the query text trimmed, with internal whitespace collapsed to a
+    /// single space and zero-width characters removed.
+    pub fn normalized_q(&self) -> String {
+        self.q
+            .split(|c: char| c.is_whitespace())
+            .map(|word| word.replace(ZERO_WIDTH_CHARS, ""))
+            .filter(|word| !word.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Returns the normalized query parsed into words, phrases and `OR` operators.
+    pub fn parsed(&self) -> ParsedQuery {
+        ParsedQuery::parse(&self.normalized_q())
+    }
+
+    /// Lowercased language part of `language`, e.g. `en` for `en-US`.
+    /// `None` when no specific language was requested.
+    pub fn language_code(&self) -> Option<String> {
+        let code = crate::language::primary_subtag(&self.language);
+        if code.is_empty() || code == "all" {
+            None
+        } else {
+            Some(code)
+        }
+    }
+
+    /// Uppercased region: the `country` parameter if set, otherwise the region
+    /// subtag of `language`, e.g. `US` for `en-US`.
+    pub fn country_code(&self) -> Option<String> {
+        if !self.country.trim().is_empty() {
+            return Some(self.country.trim().to_uppercase());
+        }
+        self.language
+            .split(['-', '_'])
+            .nth(1)
+            .filter(|region| region.len() == 2 && region.chars().all(|c| c.is_ascii_alphabetic()))
+            .map(|region| region.to_uppercase())
+    }
+
+    /// Requested categories in order, without repeats. Defaults to `general`.
     pub fn get_categories(&self) -> Vec<String> {
         if self.categories.is_empty() {
             return vec!["general".to_string()];
         }
-        self.categories
-            .split(',')
-            .map(|s| s.trim().to_string())
-            .filter(|s| !s.is_empty())
-            .collect()
+        let mut categories: Vec<String> = Vec::new();
+        for category in self.categories.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            if !categories.iter().any(|c| c == category) {
+                categories.push(category.to_string());
+            }
+        }
+        categories
     }
 }
 
@@ -56,6 +145,9 @@ pub enum ResultContent {
     Image { src: String, thumbnail: Option<String> },
     Video { src: String, thumbnail: Option<String>, duration: Option<String> },
     Map { latitude: f64, longitude: f64, zoom: Option<u8> },
+    Audio { src: String, duration: Option<String> },
+    /// Summary of an entity (person, place, ...) shown ahead of the regular results.
+    Infobox { description: String, attributes: Vec<(String, String)>, image: Option<String> },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,9 +161,191 @@ pub struct SearchResult {
     pub metadata: HashMap<String, String>,
 }
 
+/// Well-known `SearchResult.metadata` keys.
+pub mod metadata_keys {
+    /// ISO 8601 date or date-time; partial dates (`2021`, `2021-03`) are allowed.
+    pub const PUBLISHED_DATE: &str = "published_date";
+    pub const THUMBNAIL: &str = "thumbnail";
+    /// Language tag reported by the engine, e.g. `de-DE`.
+    pub const LANGUAGE: &str = "language";
+    /// Registrable domain of the result URL, e.g. `bbc.co.uk`, for display.
+    pub const SOURCE_NAME: &str = "source_name";
+}
+
+impl SearchResult {
+    pub fn is_infobox(&self) -> bool {
+        matches!(self.content, ResultContent::Infobox { .. })
+    }
+
+    /// The `published_date` metadata parsed as UTC. Partial dates resolve to
+    /// their first day.
+    pub fn published_date(&self) -> Option<DateTime<Utc>> {
+        let value = self.metadata.get(metadata_keys::PUBLISHED_DATE)?.trim();
+
+        if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+            return Some(date_time.with_timezone(&Utc));
+        }
+
+        let padded = match value.len() {
+            4 => format!("{}-01-01", value),
+            7 => format!("{}-01", value),
+            _ => value.to_string(),
+        };
+        NaiveDate::parse_from_str(&padded, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date_time| date_time.and_utc())
+    }
+
+    pub fn set_published_date(&mut self, date: DateTime<Utc>) {
+        self.metadata
+            .insert(metadata_keys::PUBLISHED_DATE.to_string(), date.to_rfc3339());
+    }
+
+    pub fn thumbnail(&self) -> Option<&str> {
+        self.metadata.get(metadata_keys::THUMBNAIL).map(String::as_str)
+    }
+
+    pub fn set_thumbnail(&mut self, url: impl Into<String>) {
+        self.metadata.insert(metadata_keys::THUMBNAIL.to_string(), url.into());
+    }
+
+    /// The engine that first reported the result.
+    pub fn primary_engine(&self) -> Option<&str> {
+        self.engines.first().map(String::as_str)
+    }
+}
+
+/// A direct answer to the query, shown above the regular results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Answer {
+    pub answer: String,
+    pub url: Option<String>,
+    pub answerer: String,
+}
+
+/// Body of the JSON search response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    #[serde(default = "default_page")]
+    pub page: u32,
+    /// Results on this page.
+    #[serde(default)]
+    pub number_of_results: usize,
+    /// Engines that contributed a result to this page, sorted by id.
+    #[serde(default)]
+    pub engines_used: Vec<String>,
+    pub results: Vec<SearchResult>,
+    pub answers: Vec<Answer>,
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Engine id to upstream latency in milliseconds. Engines that were not queried are omitted.
+    #[serde(default)]
+    pub timings: HashMap<String, u64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EngineMetadata {
     pub name: String,
     pub display_name: String,
     pub enabled: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_content_round_trip() {
+        let content = ResultContent::Audio {
+            src: "https://cdn.example.com/ep1.mp3".to_string(),
+            duration: Some("1:02:03".to_string()),
+        };
+
+        let json = serde_json::to_value(&content).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "Audio",
+                "data": {"src": "https://cdn.example.com/ep1.mp3", "duration": "1:02:03"}
+            })
+        );
+
+        let parsed: ResultContent = serde_json::from_value(json).unwrap();
+        assert!(matches!(parsed, ResultContent::Audio { ref src, .. } if src == "https://cdn.example.com/ep1.mp3"));
+    }
+
+    #[test]
+    fn test_locale_splits_into_language_and_country() {
+        let query: SearchQuery = serde_json::from_value(serde_json::json!({"q": "rust", "locale": "en-US"})).unwrap();
+        assert_eq!(query.language, "en-US");
+        assert_eq!(query.language_code().as_deref(), Some("en"));
+        assert_eq!(query.country_code().as_deref(), Some("US"));
+
+        let bare = SearchQuery {
+            language: "en".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(bare.language_code().as_deref(), Some("en"));
+        assert_eq!(bare.country_code(), None);
+
+        let explicit = SearchQuery {
+            language: "en-US".to_string(),
+            country: "gb".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(explicit.country_code().as_deref(), Some("GB"));
+        assert_eq!(SearchQuery::default().language_code(), None);
+    }
+
+    #[test]
+    fn test_metadata_accessors_round_trip() {
+        let mut result = SearchResult {
+            url: "https://example.com/".to_string(),
+            title: "Example".to_string(),
+            content: ResultContent::Text(String::new()),
+            engines: vec!["google".to_string(), "bing".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+        assert_eq!(result.published_date(), None);
+        assert_eq!(result.thumbnail(), None);
+        assert_eq!(result.primary_engine(), Some("google"));
+
+        let date = DateTime::parse_from_rfc3339("2024-02-01T08:00:00Z").unwrap().with_timezone(&Utc);
+        result.set_published_date(date);
+        assert_eq!(result.published_date(), Some(date));
+
+        result.set_thumbnail("https://example.com/thumb.jpg");
+        assert_eq!(result.thumbnail(), Some("https://example.com/thumb.jpg"));
+
+        let first_of = |value: &str| {
+            let mut result = result.clone();
+            result.metadata.insert(metadata_keys::PUBLISHED_DATE.to_string(), value.to_string());
+            result.published_date().map(|d| d.to_rfc3339())
+        };
+        assert_eq!(first_of("2021-03-09").as_deref(), Some("2021-03-09T00:00:00+00:00"));
+        assert_eq!(first_of("2021-03").as_deref(), Some("2021-03-01T00:00:00+00:00"));
+        assert_eq!(first_of("1999").as_deref(), Some("1999-01-01T00:00:00+00:00"));
+        assert_eq!(first_of("last tuesday"), None);
+    }
+
+    #[test]
+    fn test_normalized_q_collapses_whitespace() {
+        let query = SearchQuery {
+            q: "  rust \t  async\n runtime  ".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(query.normalized_q(), "rust async runtime");
+    }
+
+    #[test]
+    fn test_normalized_q_removes_zero_width_chars() {
+        let query = SearchQuery {
+            q: "ru\u{200B}st \u{FEFF} lang\u{200D}".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(query.normalized_q(), "rust lang");
+    }
+}
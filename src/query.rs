@@ -0,0 +1,216 @@
+/// A single element of a parsed query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryToken {
+    /// A bare word.
+    Word(String),
+    /// A double-quoted exact phrase, without the quotes.
+    Phrase(String),
+    /// The `OR` operator between two alternatives.
+    Or,
+}
+
+/// Structured representation of a query supporting `"exact phrases"` and `OR`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParsedQuery {
+    pub tokens: Vec<QueryToken>,
+}
+
+impl ParsedQuery {
+    /// Parses the raw query. An unterminated quote runs to the end of the query.
+    /// `OR` is only treated as an operator when uppercase and between two terms.
+    pub fn parse(q: &str) -> Self {
+        let mut tokens = Vec::new();
+        let mut chars = q.chars().peekable();
+
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() {
+                chars.next();
+            } else if c == '"' {
+                chars.next();
+                let phrase: String = chars.by_ref().take_while(|&c| c != '"').collect();
+                let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !phrase.is_empty() {
+                    tokens.push(QueryToken::Phrase(phrase));
+                }
+            } else {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if word == "OR" {
+                    if matches!(tokens.last(), Some(QueryToken::Word(_) | QueryToken::Phrase(_))) {
+                        tokens.push(QueryToken::Or);
+                    }
+                } else {
+                    tokens.push(QueryToken::Word(word));
+                }
+            }
+        }
+
+        if tokens.last() == Some(&QueryToken::Or) {
+            tokens.pop();
+        }
+
+        Self { tokens }
+    }
+
+    /// Returns the quoted phrases in order.
+    pub fn phrases(&self) -> Vec<&str> {
+        self.tokens
+            .iter()
+            .filter_map(|t| match t {
+                QueryToken::Phrase(p) => Some(p.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Splits the query on `OR` into its alternatives.
+    pub fn alternatives(&self) -> Vec<Vec<QueryToken>> {
+        self.tokens
+            .split(|t| *t == QueryToken::Or)
+            .map(|group| group.to_vec())
+            .collect()
+    }
+
+    /// Reconstructs the query using the given OR operator (e.g. `OR` or `|`).
+    pub fn render(&self, or_operator: &str) -> String {
+        self.tokens
+            .iter()
+            .map(|t| match t {
+                QueryToken::Word(w) => w.clone(),
+                QueryToken::Phrase(p) => format!("\"{}\"", p),
+                QueryToken::Or => or_operator.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Words suggesting the user wants pictures rather than pages.
+const IMAGE_WORDS: &[&str] = &[
+    "wallpaper", "wallpapers", "png", "jpg", "jpeg", "gif", "svg", "webp", "image", "images", "picture", "pictures",
+    "photo", "photos", "icon", "icons", "clipart",
+];
+
+/// Fragments that rarely appear outside source code or compiler output.
+const CODE_MARKERS: &[&str] = &["::", "->", "=>", "()", "[]", "{", "}", "==", "!=", "&&", "||", "#include", "</"];
+
+/// Whether a single-word query looks like a URL or a bare domain such as `example.com`.
+fn looks_like_url(q: &str) -> bool {
+    if q.contains(char::is_whitespace) {
+        return false;
+    }
+    if q.contains("://") {
+        return true;
+    }
+    let host = q.split('/').next().unwrap_or_default();
+    match host.rsplit_once('.') {
+        Some((name, tld)) => !name.is_empty() && tld.len() >= 2 && tld.chars().all(|c| c.is_ascii_alphabetic()),
+        None => false,
+    }
+}
+
+/// Whether the query contains code syntax or looks like a compiler/runtime error.
+fn looks_like_code(q: &str) -> bool {
+    if CODE_MARKERS.iter().any(|marker| q.contains(marker)) {
+        return true;
+    }
+    q.split_whitespace().any(|word| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        // `TypeError`, `NullPointerException`, rustc's `E0382`.
+        word.ends_with("Error")
+            || word.ends_with("Exception")
+            || (word.len() == 5 && word.starts_with('E') && word[1..].chars().all(|c| c.is_ascii_digit()))
+    })
+}
+
+/// Suggests categories for a query that did not specify any: `images` for
+/// picture-related words, `it` for code and error messages, `general` otherwise
+/// (including URLs and domains, which are navigational).
+pub fn infer_categories(q: &str) -> Vec<String> {
+    let q = q.trim();
+    if q.is_empty() || looks_like_url(q) {
+        return vec!["general".to_string()];
+    }
+    if looks_like_code(q) {
+        return vec!["it".to_string()];
+    }
+    let has_image_word = q
+        .split_whitespace()
+        .any(|word| IMAGE_WORDS.contains(&word.trim_start_matches('.').to_lowercase().as_str()));
+    if has_image_word {
+        return vec!["images".to_string()];
+    }
+    vec!["general".to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phrase_extraction() {
+        let parsed = ParsedQuery::parse(r#"rust "async   runtime" tokio "unterminated phrase"#);
+        assert_eq!(parsed.phrases(), vec!["async runtime", "unterminated phrase"]);
+        assert_eq!(
+            parsed.tokens,
+            vec![
+                QueryToken::Word("rust".to_string()),
+                QueryToken::Phrase("async runtime".to_string()),
+                QueryToken::Word("tokio".to_string()),
+                QueryToken::Phrase("unterminated phrase".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_or_splitting() {
+        let parsed = ParsedQuery::parse(r#""exact phrase" OR alternative words"#);
+        assert_eq!(
+            parsed.alternatives(),
+            vec![
+                vec![QueryToken::Phrase("exact phrase".to_string())],
+                vec![
+                    QueryToken::Word("alternative".to_string()),
+                    QueryToken::Word("words".to_string()),
+                ],
+            ]
+        );
+        assert_eq!(parsed.render("OR"), r#""exact phrase" OR alternative words"#);
+        assert_eq!(parsed.render("|"), r#""exact phrase" | alternative words"#);
+    }
+
+    #[test]
+    fn test_dangling_and_lowercase_or() {
+        let parsed = ParsedQuery::parse("OR this or that OR");
+        assert_eq!(
+            parsed.tokens,
+            vec![
+                QueryToken::Word("this".to_string()),
+                QueryToken::Word("or".to_string()),
+                QueryToken::Word("that".to_string()),
+            ]
+        );
+        assert_eq!(parsed.alternatives().len(), 1);
+    }
+
+    #[test]
+    fn test_infer_categories() {
+        assert_eq!(infer_categories("sunset wallpaper"), vec!["images"]);
+        assert_eq!(infer_categories("rust logo PNG"), vec!["images"]);
+        assert_eq!(infer_categories("Vec::with_capacity"), vec!["it"]);
+        assert_eq!(infer_categories("TypeError: undefined is not a function"), vec!["it"]);
+        assert_eq!(infer_categories("rustc E0382"), vec!["it"]);
+        // Navigational queries stay general, even with image-like paths.
+        assert_eq!(infer_categories("example.com"), vec!["general"]);
+        assert_eq!(infer_categories("https://example.com/logo.png"), vec!["general"]);
+        // Neutral queries default to general.
+        assert_eq!(infer_categories("weather in paris"), vec!["general"]);
+        assert_eq!(infer_categories("version 2.0 release notes"), vec!["general"]);
+    }
+}
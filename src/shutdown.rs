@@ -0,0 +1,78 @@
+use std::future::Future;
+use std::time::Duration;
+
+/// Resolves on Ctrl+C, or SIGTERM on Unix.
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Drives `server` to completion, but once `shutdown` resolves gives it at most
+/// `timeout` to drain in-flight requests. Returns `None` if the drain timed out.
+pub async fn drain_with_timeout<F, S>(server: F, shutdown: S, timeout: Duration) -> Option<F::Output>
+where
+    F: Future,
+    S: Future<Output = ()>,
+{
+    tokio::pin!(server);
+
+    tokio::select! {
+        output = &mut server => return Some(output),
+        _ = shutdown => {}
+    }
+
+    tokio::time::timeout(timeout, server).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drain_times_out_on_hung_server() {
+        let server = std::future::pending::<()>();
+        let result = drain_with_timeout(server, async {}, Duration::from_millis(50)).await;
+        assert!(result.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_drain_completes_within_timeout() {
+        let server = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            42
+        };
+        let result = drain_with_timeout(server, async {}, Duration::from_secs(1)).await;
+        assert_eq!(result, Some(42));
+    }
+
+    #[tokio::test]
+    async fn test_server_finishing_before_shutdown() {
+        let result = drain_with_timeout(async { 7 }, std::future::pending(), Duration::from_millis(10)).await;
+        assert_eq!(result, Some(7));
+    }
+}
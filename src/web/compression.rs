@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex, OnceLock};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    Brotli,
+    Gzip,
+}
+
+impl Encoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+        }
+    }
+}
+
+/// Picks the preferred encoding from an `Accept-Encoding` header value.
+/// Brotli is preferred over gzip; codings with `q=0` are treated as refused.
+pub fn negotiate(accept_encoding: &str) -> Option<Encoding> {
+    let accepted: Vec<&str> = accept_encoding
+        .split(',')
+        .filter_map(|part| {
+            let mut params = part.split(';');
+            let coding = params.next()?.trim();
+            let refused = params.any(|p| {
+                p.trim()
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q == 0.0)
+            });
+            (!refused).then_some(coding)
+        })
+        .collect();
+
+    if accepted.iter().any(|c| c.eq_ignore_ascii_case("br")) {
+        Some(Encoding::Brotli)
+    } else if accepted.iter().any(|c| c.eq_ignore_ascii_case("gzip")) {
+        Some(Encoding::Gzip)
+    } else {
+        None
+    }
+}
+
+/// Only text assets are worth compressing; images are already compressed.
+pub fn is_compressible(mime: &str) -> bool {
+    mime.starts_with("text/") || mime.contains("javascript") || mime.contains("json") || mime == "image/svg+xml"
+}
+
+type VariantCache = Mutex<HashMap<(String, Encoding), Arc<[u8]>>>;
+
+fn cache() -> &'static VariantCache {
+    static CACHE: OnceLock<VariantCache> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Returns the compressed variant of an asset, compressing it on first use.
+pub fn compressed_variant(path: &str, data: &[u8], encoding: Encoding) -> Option<Arc<[u8]>> {
+    let key = (path.to_string(), encoding);
+    if let Some(variant) = cache().lock().ok()?.get(&key) {
+        return Some(variant.clone());
+    }
+
+    let compressed: Arc<[u8]> = match compress(data, encoding) {
+        Ok(bytes) => bytes.into(),
+        Err(e) => {
+            tracing::error!("Failed to compress static asset {}: {}", path, e);
+            return None;
+        }
+    };
+
+    cache().lock().ok()?.insert(key, compressed.clone());
+    Some(compressed)
+}
+
+fn compress(data: &[u8], encoding: Encoding) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Brotli => {
+            let mut out = Vec::new();
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 11, 22);
+                writer.write_all(data)?;
+            }
+            Ok(out)
+        }
+        Encoding::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(negotiate("gzip, deflate, br"), Some(Encoding::Brotli));
+        assert_eq!(negotiate("gzip"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("br;q=0, gzip;q=0.8"), Some(Encoding::Gzip));
+        assert_eq!(negotiate("identity"), None);
+        assert_eq!(negotiate(""), None);
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -12,29 +12,67 @@ pub enum WebError {
     Internal(#[from] anyhow::Error),
     #[error("Not Found")]
     NotFound,
+    #[error("Bad Request: {0}")]
+    BadRequest(String),
+    #[error("Search is temporarily unavailable due to maintenance")]
+    Maintenance,
+    #[error("Unauthorized")]
+    Unauthorized,
+    #[error("Critical engines failed: {}", .0.join(", "))]
+    EnginesFailed(Vec<String>),
+    #[error("Federation loop detected")]
+    LoopDetected,
+    /// The client exceeded the instance's rate limit and may retry after the wait.
+    #[error("Too Many Requests")]
+    TooManyRequests(std::time::Duration),
+    #[error("Too many searches in progress, try again shortly")]
+    Overloaded,
+    #[error("Reload failed: {0}")]
+    Reload(String),
     #[error("Engine error: {0}")]
     Engine(#[from] crate::engines::error::EngineError),
+    #[error("Image fetch failed: {0}")]
+    ImageProxy(#[from] crate::web::fetch::FetchError),
 }
 
 impl IntoResponse for WebError {
     fn into_response(self) -> Response {
+        let retry_after = match self {
+            WebError::TooManyRequests(wait) => Some(crate::web::ratelimit::retry_after_secs(wait)),
+            WebError::Overloaded => Some(1),
+            _ => None,
+        };
+
         let (status, message) = match self {
             WebError::Internal(ref e) => {
                 tracing::error!("Internal server error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
             }
             WebError::NotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            WebError::BadRequest(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            WebError::Maintenance => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            WebError::Unauthorized => (StatusCode::UNAUTHORIZED, self.to_string()),
+            WebError::EnginesFailed(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
+            WebError::LoopDetected => (StatusCode::LOOP_DETECTED, self.to_string()),
+            WebError::TooManyRequests(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
+            WebError::Overloaded => (StatusCode::SERVICE_UNAVAILABLE, self.to_string()),
+            WebError::Reload(_) => (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
             WebError::Engine(ref e) => {
                 tracing::error!("Engine error: {:?}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, self.to_string())
             }
+            WebError::ImageProxy(_) => (StatusCode::BAD_GATEWAY, self.to_string()),
         };
 
         let body = Json(json!({
             "error": message,
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let Some(secs) = retry_after {
+            response.headers_mut().insert(header::RETRY_AFTER, secs.into());
+        }
+        response
     }
 }
 
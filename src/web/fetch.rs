@@ -0,0 +1,281 @@
+use crate::engines::DEFAULT_USER_AGENT;
+use reqwest::{header, redirect, Client, Response, StatusCode};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Duration;
+use thiserror::Error;
+use url::Url;
+
+/// Redirects followed by [`fetch_untrusted`], each checked like the first URL.
+const MAX_REDIRECTS: usize = 5;
+
+#[derive(Error, Debug)]
+pub enum FetchError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+    #[error("Upstream returned HTTP {0}")]
+    Status(StatusCode),
+    #[error("Response exceeds the {limit} byte limit")]
+    TooLarge { limit: usize },
+    #[error("Timed out after {0:?}")]
+    Timeout(Duration),
+    /// The URL, or one it redirected to, may not be fetched.
+    #[error("Refused: {0}")]
+    Refused(String),
+}
+
+/// A remote resource fetched within limits.
+#[derive(Debug)]
+pub struct Fetched {
+    pub content_type: Option<String>,
+    pub body: Vec<u8>,
+}
+
+/// Fetches `url` for re-serving to clients, giving up once the body grows past
+/// `max_bytes` or the whole exchange takes longer than `timeout`.
+///
+/// A `Content-Length` over the limit is rejected before reading the body; the
+/// limit is still enforced while streaming since the header may be missing or wrong.
+pub async fn fetch_with_limits(
+    client: &Client,
+    url: &str,
+    max_bytes: usize,
+    timeout: Duration,
+) -> Result<Fetched, FetchError> {
+    tokio::time::timeout(timeout, fetch(client, url, max_bytes))
+        .await
+        .map_err(|_| FetchError::Timeout(timeout))?
+}
+
+async fn fetch(client: &Client, url: &str, max_bytes: usize) -> Result<Fetched, FetchError> {
+    read_body(client.get(url).send().await?, max_bytes).await
+}
+
+async fn read_body(mut resp: Response, max_bytes: usize) -> Result<Fetched, FetchError> {
+    if !resp.status().is_success() {
+        return Err(FetchError::Status(resp.status()));
+    }
+    if resp.content_length().is_some_and(|len| len > max_bytes as u64) {
+        return Err(FetchError::TooLarge { limit: max_bytes });
+    }
+
+    let content_type = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.to_string());
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(FetchError::TooLarge { limit: max_bytes });
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(Fetched { content_type, body })
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    // 100.64.0.0/10 is carrier-grade NAT space.
+    let shared = a == 100 && (64..128).contains(&b);
+    !(ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || shared)
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(v4);
+    }
+    let first = ip.segments()[0];
+    let unique_local = first & 0xfe00 == 0xfc00;
+    let link_local = first & 0xffc0 == 0xfe80;
+    !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || unique_local || link_local)
+}
+
+/// Whether the proxy may connect to `ip`: not loopback, private, link-local
+/// or otherwise internal to the instance's network.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => is_public_ipv6(ip),
+    }
+}
+
+/// Client for [`fetch_untrusted`], which follows redirects itself.
+fn no_redirect_client() -> &'static Client {
+    static CLIENT: OnceLock<Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        Client::builder()
+            .user_agent(DEFAULT_USER_AGENT)
+            .referer(false)
+            .redirect(redirect::Policy::none())
+            .build()
+            .expect("default TLS backend is available")
+    })
+}
+
+/// Refuses anything but http and https URLs whose host resolves only to
+/// addresses `allowed` accepts.
+async fn check_host(url: &Url, allowed: &impl Fn(SocketAddr) -> bool) -> Result<(), FetchError> {
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(FetchError::Refused("URL must be http or https".to_string()));
+    }
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Err(FetchError::Refused("URL has no host".to_string()));
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| FetchError::Refused(format!("{} does not resolve", host)))?
+        .collect();
+    if addrs.is_empty() || !addrs.iter().all(|addr| allowed(*addr)) {
+        return Err(FetchError::Refused(format!("{} is not a public host", host)));
+    }
+    Ok(())
+}
+
+async fn fetch_checked(
+    url: &str,
+    max_bytes: usize,
+    timeout: Duration,
+    allowed: impl Fn(SocketAddr) -> bool,
+) -> Result<Fetched, FetchError> {
+    let fetch = async {
+        let mut url = Url::parse(url).map_err(|e| FetchError::Refused(e.to_string()))?;
+        for _ in 0..=MAX_REDIRECTS {
+            check_host(&url, &allowed).await?;
+            let resp = no_redirect_client().get(url.clone()).send().await?;
+            if !resp.status().is_redirection() {
+                return read_body(resp, max_bytes).await;
+            }
+            let location = resp
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or(FetchError::Status(resp.status()))?;
+            url = url.join(location).map_err(|e| FetchError::Refused(e.to_string()))?;
+        }
+        Err(FetchError::Refused("too many redirects".to_string()))
+    };
+    tokio::time::timeout(timeout, fetch)
+        .await
+        .map_err(|_| FetchError::Timeout(timeout))?
+}
+
+/// [`fetch_with_limits`] for URLs from results or clients: only http and
+/// https URLs on public hosts are fetched, and redirects are followed one at
+/// a time so each hop is checked too. `allow_private_hosts` lifts the host
+/// check for deployments that want to reach their own network.
+///
+/// The client resolves each host again when connecting, so this does not
+/// stop DNS rebinding; it keeps URLs from naming internal hosts.
+pub async fn fetch_untrusted(
+    url: &str,
+    max_bytes: usize,
+    timeout: Duration,
+    allow_private_hosts: bool,
+) -> Result<Fetched, FetchError> {
+    fetch_checked(url, max_bytes, timeout, |addr| allow_private_hosts || is_public_ip(addr.ip())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{response::Redirect, routing::get, Router};
+
+    async fn mock_server() -> String {
+        let app = Router::new()
+            .route("/small", get(|| async { ([(header::CONTENT_TYPE, "image/png")], vec![0u8; 16]) }))
+            .route("/large", get(|| async { vec![0u8; 4096] }))
+            .route("/hop", get(|| async { Redirect::temporary("/small") }))
+            .route("/internal", get(|| async { Redirect::temporary("http://127.0.0.1:9/secret") }))
+            .route("/loop", get(|| async { Redirect::temporary("/loop") }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "late"
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_oversized_body() {
+        let base = mock_server().await;
+        let client = Client::new();
+
+        let small = fetch_with_limits(&client, &format!("{}/small", base), 1024, Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(small.body.len(), 16);
+        assert_eq!(small.content_type.as_deref(), Some("image/png"));
+
+        let large = fetch_with_limits(&client, &format!("{}/large", base), 1024, Duration::from_secs(2)).await;
+        assert!(matches!(large, Err(FetchError::TooLarge { limit: 1024 })));
+    }
+
+    #[test]
+    fn test_internal_addresses_are_not_public() {
+        for internal in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(internal.parse().unwrap()), "{} should be internal", internal);
+        }
+        for public in ["93.184.216.34", "2606:2800:220:1::1", "::ffff:93.184.216.34"] {
+            assert!(is_public_ip(public.parse().unwrap()), "{} should be public", public);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_fetch_checks_every_redirect() {
+        let base = mock_server().await;
+        let server: SocketAddr = base.trim_start_matches("http://").parse().unwrap();
+        // Only the mock server is reachable, standing in for a public host.
+        let allowed = |addr: SocketAddr| addr == server;
+
+        let fetched = fetch_checked(&format!("{}/hop", base), 1024, Duration::from_secs(2), allowed)
+            .await
+            .unwrap();
+        assert_eq!(fetched.body.len(), 16);
+
+        let internal = fetch_checked(&format!("{}/internal", base), 1024, Duration::from_secs(2), allowed).await;
+        assert!(matches!(internal, Err(FetchError::Refused(_))), "{:?}", internal);
+
+        let looping = fetch_checked(&format!("{}/loop", base), 1024, Duration::from_secs(2), allowed).await;
+        assert!(matches!(looping, Err(FetchError::Refused(_))), "{:?}", looping);
+
+        for url in ["file:///etc/passwd", "http://127.0.0.1:9/", "http://localhost/"] {
+            let refused = fetch_untrusted(url, 1024, Duration::from_secs(2), false).await;
+            assert!(matches!(refused, Err(FetchError::Refused(_))), "{} gave {:?}", url, refused);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_times_out() {
+        let base = mock_server().await;
+
+        let slow = fetch_with_limits(&Client::new(), &format!("{}/slow", base), 1024, Duration::from_millis(100)).await;
+        assert!(matches!(slow, Err(FetchError::Timeout(_))));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::config::{Settings, DEFAULT_SECRET_KEY};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+fn mac(secret_key: &str, url: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret_key.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(url.as_bytes());
+    mac
+}
+
+/// The key image URLs are signed with, or `None` while the proxy is off. The
+/// default secret key never signs, as anyone could forge signatures with it.
+pub fn signing_key(settings: &Settings) -> Option<&str> {
+    let key = settings.server.secret_key.as_str();
+    (settings.proxy.image_proxy && key != DEFAULT_SECRET_KEY).then_some(key)
+}
+
+/// Hex HMAC-SHA256 of `url` under `secret_key`, the `h` parameter of `/image_proxy`.
+pub fn sign(secret_key: &str, url: &str) -> String {
+    hex::encode(mac(secret_key, url).finalize().into_bytes())
+}
+
+/// Whether `signature` is the hex HMAC of `url`, compared in constant time.
+pub fn verify(secret_key: &str, url: &str, signature: &str) -> bool {
+    match hex::decode(signature) {
+        Ok(bytes) => mac(secret_key, url).verify_slice(&bytes).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Path serving `url` through the image proxy.
+pub fn proxied_url(secret_key: &str, url: &str) -> String {
+    let params = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("url", url)
+        .append_pair("h", &sign(secret_key, url))
+        .finish();
+    format!("/image_proxy?{}", params)
+}
+
+/// Whether a `Content-Type` names an image the proxy may relay; parameters
+/// are ignored. SVG is refused since it can carry scripts that would run on
+/// this instance's origin.
+pub fn is_image(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    mime.starts_with("image/") && mime != "image/svg+xml"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_is_bound_to_url_and_key() {
+        let url = "https://example.com/cat.jpg";
+        let signature = sign("secret", url);
+
+        assert!(verify("secret", url, &signature));
+        assert!(!verify("secret", "https://example.com/dog.jpg", &signature));
+        assert!(!verify("other", url, &signature));
+        assert!(!verify("secret", url, "not-hex"));
+        assert!(!verify("secret", url, ""));
+    }
+
+    #[test]
+    fn test_proxied_url_encodes_target() {
+        let proxied = proxied_url("secret", "https://example.com/a b.jpg?size=large&x=1");
+        let query = proxied.strip_prefix("/image_proxy?").unwrap();
+        let params: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+        assert_eq!(params[0], ("url".to_string(), "https://example.com/a b.jpg?size=large&x=1".to_string()));
+        assert!(verify("secret", &params[0].1, &params[1].1));
+    }
+
+    #[test]
+    fn test_default_secret_key_never_signs() {
+        let mut settings = Settings::for_tests(std::collections::HashMap::new());
+        assert_eq!(signing_key(&settings), None);
+
+        settings.proxy.image_proxy = true;
+        assert_eq!(signing_key(&settings), Some("secret"));
+
+        settings.server.secret_key = DEFAULT_SECRET_KEY.to_string();
+        assert_eq!(signing_key(&settings), None);
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_is_image() {
+        assert!(is_image("image/png"));
+        assert!(is_image("Image/JPEG; charset=binary"));
+        assert!(!is_image("text/html; charset=utf-8"));
+        assert!(!is_image("application/octet-stream"));
+        assert!(!is_image("image/svg+xml"));
+    }
+}
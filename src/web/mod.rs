@@ -1,36 +1,129 @@
+pub mod compression;
 pub mod error;
+pub mod fetch;
+pub mod image_proxy;
+pub mod ratelimit;
 pub mod templates;
 
 use arc_swap::ArcSwap;
-use crate::config::Settings;
-use crate::engines::registry::EngineRegistry;
-use crate::models::SearchQuery;
+use crate::config::{Settings, DEFAULT_SECRET_KEY};
+use crate::engines::aggregator::sort_by_date;
+use crate::engines::registry::{EngineRegistry, EngineStatus};
+use crate::engines::searxng::HOPS_HEADER;
+use crate::engines::selectors::decode_overrides;
+use crate::models::{ResultContent, SearchQuery, SearchResponse, SearchResult};
 use axum::{
-    extract::{Path, Query, State},
-    http::{header, StatusCode},
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
 use error::{not_found_handler, WebError};
+use fetch::{fetch_untrusted, fetch_with_limits, FetchError};
+use ratelimit::{client_ip, RateLimiter};
 use rust_embed::RustEmbed;
+use serde::Deserialize;
+use serde_json::json;
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 
 #[derive(Clone)]
 pub struct AppState {
     pub settings: Arc<ArcSwap<Settings>>,
     pub registry: Arc<EngineRegistry>,
+    /// When the process started serving, for uptime reporting.
+    pub started: Instant,
+    /// Slots for searches in progress; `None` when unlimited.
+    pub search_slots: Option<Arc<Semaphore>>,
+}
+
+/// Slots for `server.max_concurrent_searches`, or `None` when unlimited.
+pub fn search_slots(settings: &Settings) -> Option<Arc<Semaphore>> {
+    settings
+        .server
+        .max_concurrent_searches
+        .map(|limit| Arc::new(Semaphore::new(limit)))
 }
 
 #[derive(RustEmbed)]
 #[folder = "static/"]
 struct StaticAssets;
 
+/// Static assets referenced by the base template. A missing entry only shows up
+/// as a 404 at runtime, so these are checked on startup.
+pub const REQUIRED_STATIC_ASSETS: &[&str] = &[
+    "sxng-ltr.min.css",
+    "sxng-core.min.js",
+    "img/favicon.png",
+    "img/favicon.svg",
+];
+
+/// Returns the required static assets that are not present in the embedded bundle.
+pub fn missing_static_assets() -> Vec<&'static str> {
+    REQUIRED_STATIC_ASSETS
+        .iter()
+        .copied()
+        .filter(|path| StaticAssets::get(path).is_none())
+        .collect()
+}
+
+/// Client rate limit for the routes that query engines.
+#[derive(Clone)]
+struct SearchLimiter {
+    settings: Arc<ArcSwap<Settings>>,
+    limiter: Arc<RateLimiter>,
+}
+
+/// Rejects clients over `limiter.requests_per_minute` with a 429. Whether the
+/// limit applies is read per request; the rate and burst when the router is built.
+///
+/// The client address comes from the connection, so the router has to be
+/// served with `into_make_service_with_connect_info::<SocketAddr>()`; requests
+/// without an address are let through.
+async fn rate_limit(State(limiter): State<SearchLimiter>, request: Request, next: Next) -> Result<Response, WebError> {
+    let settings = limiter.settings.load();
+    if settings.limiter.enabled {
+        let peer = request.extensions().get::<ConnectInfo<SocketAddr>>().map(|info| info.0);
+        if let Some(client) = client_ip(request.headers(), peer, settings.limiter.behind_proxy) {
+            limiter
+                .limiter
+                .check(client, Instant::now())
+                .map_err(WebError::TooManyRequests)?;
+        }
+    }
+    Ok(next.run(request).await)
+}
+
 pub fn router(state: AppState) -> Router {
+    let limits = state.settings.load().limiter.clone();
+    let limiter = SearchLimiter {
+        settings: state.settings.clone(),
+        limiter: Arc::new(RateLimiter::new(limits.requests_per_minute, limits.burst)),
+    };
+
+    let limited = Router::new()
+        .route("/search", get(search))
+        .route("/search.json", get(search_json))
+        .route("/search.rss", get(search_rss))
+        .route("/search.atom", get(search_atom))
+        .route("/search/:query", get(search_path))
+        .route("/autocomplete", get(autocomplete))
+        .route("/health/deep", get(health_deep))
+        .route_layer(middleware::from_fn_with_state(limiter, rate_limit));
+
     Router::new()
         .route("/", get(index))
         .route("/health", get(health_check))
-        .route("/search", get(search))
+        .route("/health/info", get(health_info))
+        .merge(limited)
+        .route("/engines", get(engines))
+        .route("/metrics", get(prometheus_metrics))
+        .route("/admin/reload", post(admin_reload))
+        .route("/image_proxy", get(image_proxy_handler))
         .route("/opensearch.xml", get(opensearch))
         .route("/static/*file", get(static_handler))
         .fallback(not_found_handler)
@@ -41,11 +134,171 @@ async fn health_check() -> &'static str {
     "OK"
 }
 
-async fn index(State(state): State<AppState>) -> impl IntoResponse {
+/// Build and runtime details for dashboards; `/health` stays plain text for load balancers.
+async fn health_info(State(state): State<AppState>) -> Json<serde_json::Value> {
+    Json(json!({
+        "status": "OK",
+        "version": env!("CARGO_PKG_VERSION"),
+        "uptime_seconds": state.started.elapsed().as_secs(),
+        "engines": state.registry.engine_count(),
+        "run_mode": std::env::var("RUN_MODE").unwrap_or_else(|_| "development".into()),
+    }))
+}
+
+/// Most the deep health check reads from each upstream page.
+const DEEP_CHECK_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+/// Fetches every enabled engine's warmup page and reports which upstreams
+/// answered. Only unavailable, with a 503, when none of them did. Rate limited
+/// like searches, as each call contacts every upstream.
+async fn health_deep(State(state): State<AppState>) -> Response {
+    let mut join_set = tokio::task::JoinSet::new();
+    for probe in state.registry.upstream_probes() {
+        join_set.spawn(async move {
+            let outcome = fetch_with_limits(&probe.client, &probe.url, DEEP_CHECK_MAX_BYTES, probe.timeout).await;
+            (probe.id, outcome.map(|_| ()))
+        });
+    }
+
+    let mut engines = std::collections::BTreeMap::new();
+    while let Some(res) = join_set.join_next().await {
+        if let Ok((id, outcome)) = res {
+            let detail = match outcome {
+                Ok(()) => "OK".to_string(),
+                Err(e) => e.to_string(),
+            };
+            engines.insert(id, detail);
+        }
+    }
+
+    let reachable = engines.values().filter(|detail| *detail == "OK").count();
+    let (status, code) = if reachable == engines.len() {
+        ("OK", StatusCode::OK)
+    } else if reachable > 0 {
+        ("DEGRADED", StatusCode::OK)
+    } else {
+        ("UNAVAILABLE", StatusCode::SERVICE_UNAVAILABLE)
+    };
+
+    (code, Json(json!({ "status": status, "engines": engines }))).into_response()
+}
+
+async fn index(State(state): State<AppState>) -> Response {
     let settings = state.settings.load();
+
+    if !settings.ui.enabled {
+        return Json(json!({
+            "instance_name": settings.general.instance_name,
+            "version": env!("CARGO_PKG_VERSION"),
+            "endpoints": {
+                "search": "/search?q={query}&format=json",
+                "autocomplete": "/autocomplete?q={query}",
+                "engines": "/engines",
+                "metrics": "/metrics",
+                "opensearch": "/opensearch.xml",
+                "health": "/health",
+            },
+        }))
+        .into_response();
+    }
+
     templates::IndexTemplate {
         instance_name: settings.general.instance_name.clone(),
     }
+    .into_response()
+}
+
+/// Suggestions for the query typed so far, in the OpenSearch suggestions
+/// format: `[query, [suggestion, ...]]`.
+async fn autocomplete(State(state): State<AppState>, Query(query): Query<SearchQuery>) -> Json<serde_json::Value> {
+    let suggestions = state.registry.suggestions(&query).await;
+    Json(json!([query.q, suggestions]))
+}
+
+/// Lists registered engines with their weight and upstream details and, for
+/// those not being queried, why.
+async fn engines(State(state): State<AppState>) -> Json<Vec<EngineStatus>> {
+    Json(state.registry.engine_statuses().await)
+}
+
+/// Per-engine counters and response times in the Prometheus text format.
+async fn prometheus_metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::render(),
+    )
+}
+
+/// How long the image proxy waits for an upstream image.
+const IMAGE_PROXY_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Deserialize)]
+struct ImageProxyParams {
+    url: String,
+    /// HMAC of `url` under `server.secret_key`; see [`image_proxy::sign`].
+    h: String,
+}
+
+/// Relays a result image signed by this instance, so clients never contact
+/// the image host. Only served while `proxy.image_proxy` is enabled and the
+/// secret key is not the default.
+async fn image_proxy_handler(
+    State(state): State<AppState>,
+    Query(params): Query<ImageProxyParams>,
+) -> Result<Response, WebError> {
+    let settings = state.settings.load();
+    let Some(key) = image_proxy::signing_key(&settings) else {
+        return Err(WebError::NotFound);
+    };
+    if !image_proxy::verify(key, &params.url, &params.h) {
+        return Err(WebError::BadRequest("invalid image signature".to_string()));
+    }
+
+    let fetched = fetch_untrusted(
+        &params.url,
+        settings.proxy.max_bytes,
+        IMAGE_PROXY_TIMEOUT,
+        settings.proxy.allow_private_hosts,
+    )
+    .await
+    .map_err(|e| match e {
+        FetchError::Refused(reason) => WebError::BadRequest(reason),
+        e => WebError::ImageProxy(e),
+    })?;
+    let content_type = fetched
+        .content_type
+        .filter(|content_type| image_proxy::is_image(content_type))
+        .ok_or_else(|| WebError::BadRequest("upstream did not return an image".to_string()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        ],
+        fetched.body,
+    )
+        .into_response())
+}
+
+/// Header carrying `server.secret_key` for admin endpoints.
+const ADMIN_KEY_HEADER: &str = "x-secret-key";
+
+/// Reloads settings from the config files and environment, for deployments
+/// where the file watcher does not fire. Disabled while the secret key is the default.
+async fn admin_reload(State(state): State<AppState>, headers: HeaderMap) -> Result<Json<serde_json::Value>, WebError> {
+    let secret_key = state.settings.load().server.secret_key.clone();
+    let provided = headers.get(ADMIN_KEY_HEADER).and_then(|v| v.to_str().ok());
+    let authorized = provided.is_some_and(|provided| secrets_match(provided, &secret_key));
+    if secret_key == DEFAULT_SECRET_KEY || !authorized {
+        return Err(WebError::Unauthorized);
+    }
+
+    let settings = Settings::new().map_err(|e| WebError::Reload(e.to_string()))?;
+    settings.validate().map_err(|e| WebError::Reload(e.to_string()))?;
+    state.settings.store(Arc::new(settings));
+    tracing::info!("Config reloaded via admin endpoint");
+
+    Ok(Json(json!({ "status": "reloaded" })))
 }
 
 async fn opensearch(State(state): State<AppState>) -> impl IntoResponse {
@@ -57,17 +310,40 @@ async fn opensearch(State(state): State<AppState>) -> impl IntoResponse {
     ([(header::CONTENT_TYPE, "application/opensearchdescription+xml")], template).into_response()
 }
 
-async fn static_handler(Path(path): Path<String>) -> impl IntoResponse {
+async fn static_handler(Path(path): Path<String>, headers: HeaderMap) -> impl IntoResponse {
     match StaticAssets::get(&path) {
         Some(content) => {
-            let mime = mime_guess::from_path(path).first_or_octet_stream();
-            let etag = format!("\"{}\"", hex::encode(content.metadata.sha256_hash()));
+            let mime = mime_guess::from_path(&path).first_or_octet_stream();
+            let hash = hex::encode(content.metadata.sha256_hash());
+
+            let encoding = headers
+                .get(header::ACCEPT_ENCODING)
+                .and_then(|v| v.to_str().ok())
+                .and_then(compression::negotiate)
+                .filter(|_| compression::is_compressible(mime.as_ref()));
+
+            if let Some(encoding) = encoding {
+                if let Some(data) = compression::compressed_variant(&path, &content.data, encoding) {
+                    return (
+                        [
+                            (header::CONTENT_TYPE, mime.as_ref().to_string()),
+                            (header::CACHE_CONTROL, "public, max-age=31536000".to_string()),
+                            (header::ETAG, format!("\"{}-{}\"", hash, encoding.as_str())),
+                            (header::CONTENT_ENCODING, encoding.as_str().to_string()),
+                            (header::VARY, "Accept-Encoding".to_string()),
+                        ],
+                        data.to_vec(),
+                    )
+                        .into_response();
+                }
+            }
 
             (
                 [
                     (header::CONTENT_TYPE, mime.as_ref().to_string()),
                     (header::CACHE_CONTROL, "public, max-age=31536000".to_string()),
-                    (header::ETAG, etag),
+                    (header::ETAG, format!("\"{}\"", hash)),
+                    (header::VARY, "Accept-Encoding".to_string()),
                 ],
                 content.data,
             )
@@ -77,19 +353,211 @@ async fn static_handler(Path(path): Path<String>) -> impl IntoResponse {
     }
 }
 
+/// Compares a client-supplied secret with the configured one in constant
+/// time. Both are hashed first so the comparison does not leak the length either.
+fn secrets_match(provided: &str, expected: &str) -> bool {
+    use sha2::{Digest, Sha256};
+    use subtle::ConstantTimeEq;
+
+    let provided = Sha256::digest(provided.as_bytes());
+    let expected = Sha256::digest(expected.as_bytes());
+    provided.as_slice().ct_eq(expected.as_slice()).into()
+}
+
+/// Reads a cookie value from the request headers.
+fn cookie_value(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.replace("%2C", ",").replace("%2c", ","))
+}
+
+/// Checks the bearer token for the machine-readable formats when the API is restricted.
+fn check_api_access(settings: &Settings, format: &str, headers: &HeaderMap) -> Result<(), WebError> {
+    if !settings.server.api_requires_token || !matches!(format, "json" | "rss" | "atom" | "csv") {
+        return Ok(());
+    }
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    match (provided, settings.server.api_token.as_deref()) {
+        (Some(provided), Some(expected)) if secrets_match(provided, expected) => Ok(()),
+        _ => Err(WebError::Unauthorized),
+    }
+}
+
+/// Reads the federation hop count, refusing queries that already passed
+/// through more SearXNG instances than allowed.
+fn federation_hops(settings: &Settings, headers: &HeaderMap) -> Result<u8, WebError> {
+    let Some(value) = headers.get(HOPS_HEADER) else {
+        return Ok(0);
+    };
+    match value.to_str().ok().and_then(|v| v.trim().parse::<u8>().ok()) {
+        Some(hops) if hops <= settings.search.max_federation_hops => Ok(hops),
+        _ => Err(WebError::LoopDetected),
+    }
+}
+
+/// One line of text per result for the CSV export; media results give their
+/// source URL. Snippets lose the markup the sanitizer kept.
+fn csv_content(content: &ResultContent) -> String {
+    match content {
+        ResultContent::Text(text) => scraper::Html::parse_fragment(text).root_element().text().collect(),
+        ResultContent::Image { src, .. } | ResultContent::Video { src, .. } | ResultContent::Audio { src, .. } => {
+            src.clone()
+        }
+        ResultContent::Map { latitude, longitude, .. } => format!("{},{}", latitude, longitude),
+        ResultContent::Infobox { description, .. } => description.clone(),
+    }
+}
+
+/// Neutralizes upstream text that a spreadsheet would run as a formula by
+/// prefixing it with `'`.
+fn csv_field(value: &str) -> String {
+    if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Results as CSV with a `title,url,content,engines,score` header. Engines are
+/// joined with commas inside their field.
+fn results_csv(results: &[SearchResult]) -> Result<Vec<u8>, WebError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["title", "url", "content", "engines", "score"])
+        .map_err(anyhow::Error::from)?;
+    for res in results {
+        writer
+            .write_record([
+                &csv_field(&res.title),
+                &csv_field(&res.url),
+                &csv_field(&csv_content(&res.content)),
+                &res.engines.join(","),
+                &res.score.to_string(),
+            ])
+            .map_err(anyhow::Error::from)?;
+    }
+    let body = writer.into_inner().map_err(|e| anyhow::anyhow!("{}", e))?;
+    Ok(body)
+}
+
+/// Failed engines that make a strict request fail: the configured critical
+/// engines, or in debug mode every engine when none are configured.
+/// Strict mode is ignored unless one of the two applies.
+fn critical_failures(settings: &Settings, failed: &[String]) -> Vec<String> {
+    let critical = &settings.search.critical_engines;
+    if critical.is_empty() {
+        return if settings.debug { failed.to_vec() } else { vec![] };
+    }
+    failed.iter().filter(|id| critical.contains(id)).cloned().collect()
+}
+
 async fn search(
     State(state): State<AppState>,
-    Query(query): Query<SearchQuery>,
+    headers: HeaderMap,
+    Query(mut query): Query<SearchQuery>,
 ) -> Result<Response, WebError> {
-    let results = state.registry.search(&query).await;
+    let format = if query.format.is_empty() { "html" } else { query.format.as_str() };
+    if !state.settings.load().server.enabled_formats.iter().any(|enabled| enabled == format) {
+        return Err(WebError::NotFound);
+    }
+    check_api_access(&state.settings.load(), &query.format, &headers)?;
+    query.hops = federation_hops(&state.settings.load(), &headers)?;
+    if let Some(encoded) = query.selector_override.as_deref().filter(|_| state.settings.load().debug) {
+        query.selector_overrides = decode_overrides(encoded).map_err(WebError::BadRequest)?;
+    }
+    query.cookie_engines = cookie_value(&headers, "engines");
+
+    // Before queueing for a slot: nothing will be searched, so there is nothing to wait for.
+    if state.settings.load().general.maintenance_mode {
+        return match format {
+            "html" => {
+                let template = templates::MaintenanceTemplate {
+                    instance_name: state.settings.load().general.instance_name.clone(),
+                };
+                Ok((StatusCode::SERVICE_UNAVAILABLE, template).into_response())
+            }
+            _ => Err(WebError::Maintenance),
+        };
+    }
+
+    // Held until the response is built.
+    let _slot = match &state.search_slots {
+        Some(slots) => {
+            let wait = Duration::from_millis(state.settings.load().server.search_queue_timeout);
+            match tokio::time::timeout(wait, slots.clone().acquire_owned()).await {
+                Ok(Ok(permit)) => Some(permit),
+                _ => return Err(WebError::Overloaded),
+            }
+        }
+        None => None,
+    };
+
+    let (outcome, answers) = tokio::join!(
+        state.registry.search(&query),
+        state.registry.answers(&query)
+    );
 
     let settings = state.settings.load();
+    if query.strict == 1 {
+        let failed = critical_failures(&settings, &outcome.failed);
+        if !failed.is_empty() {
+            return Err(WebError::EnginesFailed(failed));
+        }
+    }
+
+    let mut results = outcome.results;
+    if query.sort == "date" {
+        sort_by_date(&mut results);
+    }
+
     match query.format.as_str() {
-        "json" => Ok(Json(results).into_response()),
+        "json" => {
+            let mut warnings = outcome.warnings;
+            let unsupported = state.registry.safesearch_unsupported(&query);
+            if !unsupported.is_empty() {
+                let verb = if settings.search.strict_safesearch_only { "excluded" } else { "not filtered" };
+                warnings.push(format!(
+                    "Safe search is not supported by these engines ({}): {}",
+                    verb,
+                    unsupported.join(", ")
+                ));
+            }
+
+            let mut engines_used: Vec<String> = results.iter().flat_map(|res| res.engines.clone()).collect();
+            engines_used.sort();
+            engines_used.dedup();
+
+            let mut response = Json(SearchResponse {
+                query: query.q.clone(),
+                page: query.page,
+                number_of_results: results.len(),
+                engines_used,
+                results,
+                answers,
+                warnings,
+                timings: outcome.timings,
+            })
+            .into_response();
+            if let Ok(content_type) = HeaderValue::from_str(&settings.server.json_content_type) {
+                response.headers_mut().insert(header::CONTENT_TYPE, content_type);
+            }
+            Ok(response)
+        }
         "rss" => {
             let template = templates::RssTemplate {
                 query: query.q.clone(),
                 results,
+                answer: answers.into_iter().next(),
                 instance_name: settings.general.instance_name.clone(),
                 base_url: settings.server.base_url.clone(),
             };
@@ -99,18 +567,805 @@ async fn search(
             let template = templates::AtomTemplate {
                 query: query.q.clone(),
                 results,
+                answer: answers.into_iter().next(),
                 instance_name: settings.general.instance_name.clone(),
                 base_url: settings.server.base_url.clone(),
             };
             Ok(([(header::CONTENT_TYPE, "application/atom+xml")], template).into_response())
         }
+        "csv" => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, r#"attachment; filename="results.csv""#),
+            ],
+            results_csv(&results)?,
+        )
+            .into_response()),
         _ => {
+            let mut engines: Vec<String> = outcome.timings.into_keys().collect();
+            engines.sort();
             let template = templates::ResultsTemplate {
                 query: query.q.clone(),
                 results,
+                answers,
                 instance_name: settings.general.instance_name.clone(),
+                snippet_length: settings.ui.snippet_length,
+                engines,
+                max_footer_engines: settings.ui.max_footer_engines,
+                image_proxy_key: image_proxy::signing_key(&settings).map(str::to_string),
             };
             Ok(template.into_response())
         }
     }
 }
+
+/// `/search` with the format taken from the path, ignoring any `format` parameter.
+async fn search_as(
+    format: &str,
+    state: State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SearchQuery>,
+) -> Result<Response, WebError> {
+    let query = SearchQuery {
+        format: format.to_string(),
+        ..query
+    };
+    search(state, headers, Query(query)).await
+}
+
+async fn search_json(state: State<AppState>, headers: HeaderMap, query: Query<SearchQuery>) -> Result<Response, WebError> {
+    search_as("json", state, headers, query).await
+}
+
+async fn search_rss(state: State<AppState>, headers: HeaderMap, query: Query<SearchQuery>) -> Result<Response, WebError> {
+    search_as("rss", state, headers, query).await
+}
+
+async fn search_atom(state: State<AppState>, headers: HeaderMap, query: Query<SearchQuery>) -> Result<Response, WebError> {
+    search_as("atom", state, headers, query).await
+}
+
+/// Decodes a `/search/{query}` path segment, reading `+` as a space the way
+/// the `q` parameter does.
+fn decode_path_query(segment: &str) -> String {
+    let escaped = segment.replace('&', "%26");
+    url::form_urlencoded::parse(format!("q={}", escaped).as_bytes())
+        .next()
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_default()
+}
+
+/// `/search` with the query taken from the path. Other parameters come from
+/// the query string, where a `q` is ignored.
+async fn search_path(state: State<AppState>, headers: HeaderMap, uri: Uri) -> Result<Response, WebError> {
+    let segment = uri.path().rsplit('/').next().unwrap_or_default();
+
+    // The serializer is not `Send`, so it must be gone before the search is awaited.
+    let params = {
+        let mut params = url::form_urlencoded::Serializer::new(String::new());
+        params.append_pair("q", &decode_path_query(segment));
+        for (key, value) in url::form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes()) {
+            if key != "q" {
+                params.append_pair(&key, &value);
+            }
+        }
+        params.finish()
+    };
+
+    let uri: Uri = format!("/search?{}", params)
+        .parse()
+        .map_err(|_| WebError::BadRequest("Invalid search path".to_string()))?;
+    let query = Query::<SearchQuery>::try_from_uri(&uri).map_err(|e| WebError::BadRequest(e.body_text()))?;
+    search(state, headers, query).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::EngineConfig;
+    use crate::engines::error::EngineError;
+    use crate::engines::SearchEngine;
+    use crate::models::SearchResult;
+    use async_trait::async_trait;
+    use reqwest::Client;
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    struct CountingEngine {
+        calls: Arc<AtomicU32>,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl SearchEngine for CountingEngine {
+        fn id(&self) -> String {
+            "counting".to_string()
+        }
+        fn name(&self) -> String {
+            "Counting".to_string()
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            Ok(vec![])
+        }
+        async fn suggest(
+            &self,
+            query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<String>, EngineError> {
+            Ok(vec![format!("{} async", query.q), format!("{} book", query.q), format!("{} Async", query.q)])
+        }
+    }
+
+    /// Reachable at `url` according to its warmup URL.
+    struct ProbedEngine {
+        id: String,
+        url: String,
+    }
+
+    #[async_trait]
+    impl SearchEngine for ProbedEngine {
+        fn id(&self) -> String {
+            self.id.clone()
+        }
+        fn name(&self) -> String {
+            self.id.clone()
+        }
+        fn warmup_url(&self, _config: &EngineConfig) -> Option<String> {
+            Some(self.url.clone())
+        }
+        async fn search(
+            &self,
+            _query: &SearchQuery,
+            _client: &Client,
+            _config: &EngineConfig,
+        ) -> Result<Vec<SearchResult>, EngineError> {
+            Ok(vec![])
+        }
+    }
+
+    fn test_state(settings: Settings, calls: Arc<AtomicU32>) -> AppState {
+        test_state_with_delay(settings, calls, Duration::ZERO)
+    }
+
+    fn test_state_with_delay(settings: Settings, calls: Arc<AtomicU32>, delay: Duration) -> AppState {
+        let settings = Arc::new(ArcSwap::from(Arc::new(settings)));
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        registry.register_engine(Box::new(CountingEngine { calls, delay }));
+        AppState {
+            search_slots: search_slots(&settings.load()),
+            settings,
+            registry: Arc::new(registry),
+            started: Instant::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deep_health_check_fetches_upstreams() {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route("/large", get(|| async { "x".repeat(DEEP_CHECK_MAX_BYTES + 1) }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap().local_addr().unwrap();
+
+        let state = |targets: &[(&str, String)]| {
+            let settings = Arc::new(ArcSwap::from(Arc::new(Settings::for_tests(HashMap::new()))));
+            let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+            for (id, url) in targets {
+                registry.register_engine(Box::new(ProbedEngine {
+                    id: id.to_string(),
+                    url: url.clone(),
+                }));
+            }
+            AppState {
+                search_slots: None,
+                settings,
+                registry: Arc::new(registry),
+                started: Instant::now(),
+            }
+        };
+        let check = |state: AppState| async move {
+            let response = health_deep(State(state)).await;
+            let status = response.status();
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+        };
+
+        let (status, body) = check(state(&[
+            ("up", format!("http://{}/", addr)),
+            ("large", format!("http://{}/large", addr)),
+            ("down", format!("http://{}/", closed)),
+        ]))
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "DEGRADED");
+        assert_eq!(body["engines"]["up"], "OK");
+        assert!(body["engines"]["large"].as_str().unwrap().contains("byte limit"));
+        assert!(body["engines"]["down"].as_str().unwrap().contains("Network error"));
+
+        let (status, body) = check(state(&[("down", format!("http://{}/", closed))])).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "UNAVAILABLE");
+    }
+
+    #[tokio::test]
+    async fn test_static_stylesheet_brotli_variant() {
+        use std::io::Read;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT_ENCODING, "gzip, br".parse().unwrap());
+
+        let response = static_handler(Path("sxng-ltr.min.css".to_string()), headers)
+            .await
+            .into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+        assert!(response.headers().contains_key(header::ETAG));
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut decompressed = Vec::new();
+        brotli::Decompressor::new(&body[..], 4096)
+            .read_to_end(&mut decompressed)
+            .unwrap();
+        let original = StaticAssets::get("sxng-ltr.min.css").unwrap();
+        assert_eq!(decompressed, original.data.as_ref());
+    }
+
+    #[tokio::test]
+    async fn test_api_token_gates_machine_formats() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.server.api_requires_token = true;
+        settings.server.api_token = Some("s3cret".to_string());
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+
+        let query = |format: &str| SearchQuery {
+            q: "test".to_string(),
+            format: format.to_string(),
+            ..Default::default()
+        };
+
+        // HTML stays public
+        let html = search(State(state.clone()), HeaderMap::new(), Query(query(""))).await;
+        assert!(html.is_ok());
+
+        // JSON without a token is rejected
+        let json = search(State(state.clone()), HeaderMap::new(), Query(query("json"))).await;
+        assert!(matches!(json, Err(WebError::Unauthorized)));
+
+        // JSON with the wrong token is rejected
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        let json = search(State(state.clone()), headers, Query(query("json"))).await;
+        assert!(matches!(json, Err(WebError::Unauthorized)));
+
+        // JSON with the configured token is allowed
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer s3cret".parse().unwrap());
+        let json = search(State(state.clone()), headers, Query(query("json"))).await;
+        assert_eq!(json.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_federation_hop_limit_rejects_deep_queries() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let state = test_state(Settings::for_tests(HashMap::new()), calls.clone());
+        let query = || SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            ..Default::default()
+        };
+
+        let mut headers = HeaderMap::new();
+        headers.insert(HOPS_HEADER, "2".parse().unwrap());
+        let at_limit = search(State(state.clone()), headers, Query(query())).await;
+        assert_eq!(at_limit.unwrap().status(), StatusCode::OK);
+
+        let mut headers = HeaderMap::new();
+        headers.insert(HOPS_HEADER, "3".parse().unwrap());
+        let over = search(State(state.clone()), headers, Query(query())).await;
+        match over {
+            Err(err) => assert_eq!(err.into_response().status(), StatusCode::LOOP_DETECTED),
+            Ok(_) => panic!("query past the hop limit should be refused"),
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "refused query must not reach the engines");
+    }
+
+    #[tokio::test]
+    async fn test_disabled_format_returns_not_found() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.server.enabled_formats = vec!["html".to_string(), "json".to_string()];
+        let calls = Arc::new(AtomicU32::new(0));
+        let state = test_state(settings, calls.clone());
+
+        let query = |format: &str| SearchQuery {
+            q: "test".to_string(),
+            format: format.to_string(),
+            ..Default::default()
+        };
+
+        let rss = search(State(state.clone()), HeaderMap::new(), Query(query("rss"))).await;
+        assert!(matches!(rss, Err(WebError::NotFound)));
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "disabled formats must not query engines");
+
+        let json = search(State(state.clone()), HeaderMap::new(), Query(query("json"))).await;
+        assert_eq!(json.unwrap().status(), StatusCode::OK);
+        let html = search(State(state), HeaderMap::new(), Query(query(""))).await;
+        assert_eq!(html.unwrap().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_selector_override_requires_debug() {
+        let query = || SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            selector_override: Some("not base64!".to_string()),
+            ..Default::default()
+        };
+
+        // Ignored entirely outside debug mode.
+        let state = test_state(Settings::for_tests(HashMap::new()), Arc::new(AtomicU32::new(0)));
+        let ignored = search(State(state), HeaderMap::new(), Query(query())).await;
+        assert_eq!(ignored.unwrap().status(), StatusCode::OK);
+
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.debug = true;
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+        let rejected = search(State(state), HeaderMap::new(), Query(query())).await;
+        assert!(matches!(rejected, Err(WebError::BadRequest(_))));
+    }
+
+    #[tokio::test]
+    async fn test_index_returns_json_when_ui_disabled() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.ui.enabled = false;
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+
+        let response = index(State(state)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["instance_name"], "SearXNG");
+        assert!(body["endpoints"]["search"].as_str().unwrap().contains("format=json"));
+    }
+
+    #[tokio::test]
+    async fn test_strict_request_fails_when_critical_engine_fails() {
+        let mut engines = HashMap::new();
+        // A zero timeout makes the delayed engine time out.
+        engines.insert(
+            "counting".to_string(),
+            EngineConfig {
+                timeout: 0,
+                ..Default::default()
+            },
+        );
+        let mut settings = Settings::for_tests(engines);
+        settings.search.critical_engines = vec!["counting".to_string()];
+        let state = test_state_with_delay(settings, Arc::new(AtomicU32::new(0)), Duration::from_millis(50));
+
+        let query = |strict: u8| SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            strict,
+            ..Default::default()
+        };
+
+        let response = match search(State(state.clone()), HeaderMap::new(), Query(query(1))).await {
+            Ok(_) => panic!("strict request should fail"),
+            Err(err) => err.into_response(),
+        };
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+
+        let response = search(State(state), HeaderMap::new(), Query(query(0))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_json_content_type_is_configurable() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.server.json_content_type = "text/json".to_string();
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+        let query = SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            ..Default::default()
+        };
+
+        let response = search(State(state), HeaderMap::new(), Query(query)).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/json");
+    }
+
+    #[tokio::test]
+    async fn test_json_response_reports_engine_timings() {
+        let state = test_state_with_delay(
+            Settings::for_tests(HashMap::new()),
+            Arc::new(AtomicU32::new(0)),
+            Duration::from_millis(100),
+        );
+        let query = SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            ..Default::default()
+        };
+
+        let response = search(State(state), HeaderMap::new(), Query(query)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let timing = body["timings"]["counting"].as_u64().expect("counting engine should report a timing");
+        assert!((100..1000).contains(&timing), "Implausible timing: {}ms", timing);
+    }
+
+    #[tokio::test]
+    async fn test_json_response_reports_paging() {
+        struct ListingEngine(&'static str);
+
+        #[async_trait]
+        impl SearchEngine for ListingEngine {
+            fn id(&self) -> String {
+                self.0.to_string()
+            }
+            fn name(&self) -> String {
+                self.0.to_string()
+            }
+            async fn search(
+                &self,
+                _query: &SearchQuery,
+                _client: &Client,
+                _config: &EngineConfig,
+            ) -> Result<Vec<SearchResult>, EngineError> {
+                Ok(["a", "b"]
+                    .iter()
+                    .map(|path| SearchResult {
+                        url: format!("https://example.com/{}", path),
+                        title: path.to_string(),
+                        content: crate::models::ResultContent::Text(String::new()),
+                        engines: vec![self.id()],
+                        score: 1.0,
+                        metadata: HashMap::new(),
+                    })
+                    .collect())
+            }
+        }
+
+        let settings = Arc::new(ArcSwap::from(Arc::new(Settings::for_tests(HashMap::new()))));
+        let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+        registry.register_engine(Box::new(ListingEngine("second")));
+        registry.register_engine(Box::new(ListingEngine("first")));
+        let state = AppState {
+            search_slots: None,
+            settings,
+            registry: Arc::new(registry),
+            started: Instant::now(),
+        };
+        let query = SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            page: 2,
+            ..Default::default()
+        };
+
+        let response = search(State(state), HeaderMap::new(), Query(query)).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["query"], "test");
+        assert_eq!(body["page"], 2);
+        assert_eq!(body["number_of_results"], 2);
+        assert_eq!(body["results"].as_array().unwrap().len(), 2);
+        assert_eq!(body["engines_used"], json!(["first", "second"]));
+    }
+
+    #[test]
+    fn test_results_csv_escapes_fields() {
+        let results = vec![
+            SearchResult {
+                url: "https://example.com/".to_string(),
+                title: r#"The "Rust" book"#.to_string(),
+                content: ResultContent::Text("Line <b>one</b>\nline two".to_string()),
+                engines: vec!["google".to_string(), "bing".to_string()],
+                score: 1.5,
+                metadata: HashMap::new(),
+            },
+            SearchResult {
+                url: "https://example.com/ferris".to_string(),
+                title: "Ferris".to_string(),
+                content: ResultContent::Image {
+                    src: "https://example.com/ferris.png".to_string(),
+                    thumbnail: None,
+                },
+                engines: vec!["bing".to_string()],
+                score: 0.5,
+                metadata: HashMap::new(),
+            },
+        ];
+
+        let csv = String::from_utf8(results_csv(&results).unwrap()).unwrap();
+        assert_eq!(
+            csv,
+            concat!(
+                "title,url,content,engines,score\n",
+                "\"The \"\"Rust\"\" book\",https://example.com/,\"Line one\nline two\",\"google,bing\",1.5\n",
+                "Ferris,https://example.com/ferris,https://example.com/ferris.png,bing,0.5\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_results_csv_neutralizes_formulas() {
+        let result = |title: &str, snippet: &str| SearchResult {
+            url: "https://example.com/".to_string(),
+            title: title.to_string(),
+            content: ResultContent::Text(snippet.to_string()),
+            engines: vec!["google".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        };
+        let results = vec![
+            result("=HYPERLINK(\"http://evil.example\")", "+1 pro tip"),
+            result("@SUM(A1)", "-2 degrees"),
+        ];
+
+        let csv = String::from_utf8(results_csv(&results).unwrap()).unwrap();
+        let rows: Vec<&str> = csv.lines().skip(1).collect();
+        assert_eq!(
+            rows,
+            vec![
+                "\"'=HYPERLINK(\"\"http://evil.example\"\")\",https://example.com/,'+1 pro tip,google,1",
+                "'@SUM(A1),https://example.com/,'-2 degrees,google,1",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_csv_format_is_an_attachment() {
+        let state = test_state(Settings::for_tests(HashMap::new()), Arc::new(AtomicU32::new(0)));
+        let query = SearchQuery {
+            q: "test".to_string(),
+            format: "csv".to_string(),
+            ..Default::default()
+        };
+
+        let response = search(State(state), HeaderMap::new(), Query(query)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/csv; charset=utf-8");
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            r#"attachment; filename="results.csv""#
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"title,url,content,engines,score\n");
+    }
+
+    fn image_proxy_state() -> AppState {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.proxy.image_proxy = true;
+        // The mock upstreams listen on loopback.
+        settings.proxy.allow_private_hosts = true;
+        test_state(settings, Arc::new(AtomicU32::new(0)))
+    }
+
+    async fn proxy(state: AppState, url: &str, h: String) -> Response {
+        let params = ImageProxyParams { url: url.to_string(), h };
+        match image_proxy_handler(State(state), Query(params)).await {
+            Ok(response) => response,
+            Err(e) => e.into_response(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_proxy_rejects_bad_signatures() {
+        let url = "https://example.com/cat.jpg";
+        let response = proxy(image_proxy_state(), url, image_proxy::sign("another key", url)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let other = image_proxy::sign("secret", "https://example.com/dog.jpg");
+        let response = proxy(image_proxy_state(), url, other).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Disabled proxies serve nothing, signed or not.
+        let state = test_state(Settings::for_tests(HashMap::new()), Arc::new(AtomicU32::new(0)));
+        let response = proxy(state, url, image_proxy::sign("secret", url)).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Nor do proxies signing with the published default key.
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.proxy.image_proxy = true;
+        settings.server.secret_key = DEFAULT_SECRET_KEY.to_string();
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+        let response = proxy(state, url, image_proxy::sign(DEFAULT_SECRET_KEY, url)).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_image_proxy_refuses_internal_hosts() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.proxy.image_proxy = true;
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+
+        for url in [
+            "http://127.0.0.1:8080/cat.png",
+            "http://localhost/cat.png",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]/cat.png",
+            "http://10.0.0.1/cat.png",
+            "file:///etc/passwd",
+        ] {
+            let response = proxy(state.clone(), url, image_proxy::sign("secret", url)).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{} should be refused", url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_image_proxy_relays_only_images() {
+        let app = Router::new()
+            .route("/cat.png", get(|| async { ([(header::CONTENT_TYPE, "image/png")], b"\x89PNG".to_vec()) }))
+            .route("/page", get(|| async { ([(header::CONTENT_TYPE, "text/html")], "<html></html>") }))
+            .route("/moved", get(|| async { axum::response::Redirect::temporary("/cat.png") }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let image = format!("{}/cat.png", base);
+        let response = proxy(image_proxy_state(), &image, image_proxy::sign("secret", &image)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"\x89PNG");
+
+        // Redirects are followed, each hop checked like the first.
+        let moved = format!("{}/moved", base);
+        let response = proxy(image_proxy_state(), &moved, image_proxy::sign("secret", &moved)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let page = format!("{}/page", base);
+        let response = proxy(image_proxy_state(), &page, image_proxy::sign("secret", &page)).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match("token", "token"));
+        assert!(!secrets_match("token", "Token"));
+        assert!(!secrets_match("tok", "token"));
+        assert!(!secrets_match("", "token"));
+    }
+
+    #[tokio::test]
+    async fn test_searches_beyond_the_limit_get_503() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.server.max_concurrent_searches = Some(2);
+        settings.server.search_queue_timeout = 50;
+        let calls = Arc::new(AtomicU32::new(0));
+        let state = test_state_with_delay(settings, calls.clone(), Duration::from_millis(500));
+
+        let query = SearchQuery {
+            q: "test".to_string(),
+            format: "json".to_string(),
+            ..Default::default()
+        };
+        let in_flight: Vec<_> = (0..2)
+            .map(|_| tokio::spawn(search(State(state.clone()), HeaderMap::new(), Query(query.clone()))))
+            .collect();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let rejected = match search(State(state.clone()), HeaderMap::new(), Query(query.clone())).await {
+            Ok(response) => response,
+            Err(e) => e.into_response(),
+        };
+        assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(rejected.headers()[header::RETRY_AFTER], "1");
+
+        for handle in in_flight {
+            assert_eq!(handle.await.unwrap().unwrap().status(), StatusCode::OK);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // Slots are released once the searches finish.
+        assert!(search(State(state), HeaderMap::new(), Query(query)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_autocomplete_uses_configured_provider() {
+        let query = || SearchQuery {
+            q: " rust ".to_string(),
+            ..Default::default()
+        };
+
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.search.autocomplete = "counting".to_string();
+        let state = test_state(settings, Arc::new(AtomicU32::new(0)));
+        let Json(body) = autocomplete(State(state), Query(query())).await;
+        assert_eq!(body, json!([" rust ", ["rust async", "rust book"]]));
+
+        // No suggestions when the configured provider is not registered.
+        let state = test_state(Settings::for_tests(HashMap::new()), Arc::new(AtomicU32::new(0)));
+        let Json(body) = autocomplete(State(state), Query(query())).await;
+        assert_eq!(body, json!([" rust ", []]));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_count_engine_outcomes() {
+        // Metrics are process-wide, so this engine's id is not shared with other tests.
+        struct MeteredEngine;
+
+        #[async_trait]
+        impl SearchEngine for MeteredEngine {
+            fn id(&self) -> String {
+                "metered".to_string()
+            }
+            fn name(&self) -> String {
+                "Metered".to_string()
+            }
+            async fn search(
+                &self,
+                _query: &SearchQuery,
+                _client: &Client,
+                _config: &EngineConfig,
+            ) -> Result<Vec<SearchResult>, EngineError> {
+                Ok(vec![])
+            }
+        }
+
+        crate::metrics::install();
+        let settings = Arc::new(ArcSwap::from(Arc::new(Settings::for_tests(HashMap::new()))));
+        let mut registry = EngineRegistry::new(settings, Client::new());
+        registry.register_engine(Box::new(MeteredEngine));
+        registry.search(&SearchQuery::default()).await;
+
+        let response = prometheus_metrics().await.into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(r#"searxng_engine_requests_total{engine="metered"} 1"#), "{}", body);
+        assert!(body.contains(r#"searxng_engine_successes_total{engine="metered"} 1"#), "{}", body);
+        assert!(!body.contains(r#"searxng_engine_failures_total{engine="metered"}"#), "{}", body);
+        assert!(body.contains(r#"searxng_engine_response_time_seconds_bucket{engine="metered",le="0.01"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_mode_skips_engines() {
+        let mut settings = Settings::for_tests(HashMap::new());
+        settings.general.maintenance_mode = true;
+        // No slot is ever free, so reaching the queue would answer `Overloaded` instead.
+        settings.server.max_concurrent_searches = Some(0);
+        settings.server.search_queue_timeout = 10;
+        let calls = Arc::new(AtomicU32::new(0));
+        let state = test_state(settings, calls.clone());
+
+        let query = |format: &str| SearchQuery {
+            q: "test".to_string(),
+            format: format.to_string(),
+            ..Default::default()
+        };
+
+        let html = search(State(state.clone()), HeaderMap::new(), Query(query(""))).await.unwrap();
+        assert_eq!(html.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert!(html.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/html"));
+
+        for format in ["json", "rss", "atom", "csv"] {
+            let response = search(State(state.clone()), HeaderMap::new(), Query(query(format))).await;
+            assert!(matches!(response, Err(WebError::Maintenance)), "{} should get the plain error", format);
+        }
+
+        assert_eq!(calls.load(Ordering::SeqCst), 0, "Engines should not be queried in maintenance mode");
+    }
+
+    #[test]
+    fn test_required_static_assets_are_embedded() {
+        assert!(StaticAssets::get("sxng-ltr.min.css").is_some(), "main stylesheet should be embedded");
+        assert!(missing_static_assets().is_empty(), "missing assets: {:?}", missing_static_assets());
+    }
+}
@@ -0,0 +1,247 @@
+use axum::http::HeaderMap;
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv6Addr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Token bucket holding up to `capacity` tokens, refilled continuously at
+/// `per_second`. Each request takes one token.
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    capacity: f64,
+    per_second: f64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    /// A full bucket.
+    pub fn new(capacity: u32, per_second: f64, now: Instant) -> Self {
+        Self {
+            capacity: capacity.max(1) as f64,
+            per_second,
+            tokens: capacity.max(1) as f64,
+            updated: now,
+        }
+    }
+
+    /// Whether the bucket has refilled completely, so dropping it loses nothing.
+    fn is_full(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= self.capacity
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_second).min(self.capacity);
+        self.updated = now;
+    }
+
+    /// Takes a token, or returns how long until one is available.
+    pub fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if self.per_second <= 0.0 {
+            return Err(Duration::MAX);
+        }
+        // Rounded to the nanosecond so float error cannot push the wait past a whole second.
+        Err(Duration::from_nanos(((1.0 - self.tokens) / self.per_second * 1e9).round() as u64))
+    }
+}
+
+/// How often buckets that have refilled are dropped.
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+struct Buckets {
+    by_client: HashMap<IpAddr, TokenBucket>,
+    swept: Instant,
+}
+
+/// Token buckets per client address. IPv6 clients share a bucket per /64, the
+/// block a single subscriber is usually assigned.
+pub struct RateLimiter {
+    burst: u32,
+    per_second: f64,
+    buckets: Mutex<Buckets>,
+}
+
+/// The address a client is limited by: IPv4 as is, IPv6 truncated to its /64.
+fn client_key(client: IpAddr) -> IpAddr {
+    match client {
+        IpAddr::V4(_) => client,
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => IpAddr::V4(v4),
+            None => {
+                let [a, b, c, d, ..] = v6.segments();
+                IpAddr::V6(Ipv6Addr::new(a, b, c, d, 0, 0, 0, 0))
+            }
+        },
+    }
+}
+
+impl RateLimiter {
+    /// Allows bursts of `burst` requests, refilled at `requests_per_minute`.
+    pub fn new(requests_per_minute: u32, burst: u32) -> Self {
+        Self {
+            burst,
+            per_second: requests_per_minute as f64 / 60.0,
+            buckets: Mutex::new(Buckets {
+                by_client: HashMap::new(),
+                swept: Instant::now(),
+            }),
+        }
+    }
+
+    /// Admits a request from `client`, or returns how long it has to wait.
+    pub fn check(&self, client: IpAddr, now: Instant) -> Result<(), Duration> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if now.saturating_duration_since(buckets.swept) >= SWEEP_INTERVAL {
+            // A full bucket behaves exactly like a new one.
+            buckets.by_client.retain(|_, bucket| !bucket.is_full(now));
+            buckets.swept = now;
+        }
+        buckets
+            .by_client
+            .entry(client_key(client))
+            .or_insert_with(|| TokenBucket::new(self.burst, self.per_second, now))
+            .try_acquire(now)
+    }
+
+    /// Clients currently tracked.
+    pub fn len(&self) -> usize {
+        self.buckets
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .by_client
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// `Retry-After` seconds for a wait: rounded up, so a client retrying on time
+/// finds a token, and at least 1.
+pub fn retry_after_secs(wait: Duration) -> u64 {
+    let secs = wait.as_secs().saturating_add(u64::from(wait.subsec_nanos() > 0));
+    secs.max(1)
+}
+
+/// The address a request is limited by. Behind a proxy that is the last
+/// `X-Forwarded-For` entry, the one the proxy appended; entries before it
+/// come from the client and cannot be trusted.
+pub fn client_ip(headers: &HeaderMap, peer: Option<SocketAddr>, behind_proxy: bool) -> Option<IpAddr> {
+    if behind_proxy {
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .last()
+            .and_then(|addr| addr.trim().parse().ok());
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    peer.map(|addr| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_after_counts_down_to_refill() {
+        // One request per 10 seconds, no burst.
+        let limiter = RateLimiter::new(6, 1);
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.check(client, start).is_ok());
+
+        let waits: Vec<u64> = [1, 4, 9]
+            .iter()
+            .map(|secs| limiter.check(client, start + Duration::from_secs(*secs)).unwrap_err())
+            .map(retry_after_secs)
+            .collect();
+        assert_eq!(waits, vec![9, 6, 1]);
+
+        // Rejected requests take no tokens, so the bucket refills on schedule.
+        assert!(limiter.check(client, start + Duration::from_millis(10_001)).is_ok());
+
+        // Other clients have their own bucket.
+        let other: IpAddr = "198.51.100.1".parse().unwrap();
+        assert!(limiter.check(other, start + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn test_refilled_buckets_are_swept() {
+        // One request per second, bursts of two.
+        let limiter = RateLimiter::new(60, 2);
+        let start = Instant::now();
+        let idle: IpAddr = "203.0.113.7".parse().unwrap();
+        let busy: IpAddr = "198.51.100.1".parse().unwrap();
+
+        assert!(limiter.check(idle, start).is_ok());
+        assert!(limiter.check(busy, start).is_ok());
+        assert_eq!(limiter.len(), 2);
+
+        // By the next sweep the idle client has refilled; the busy one has not.
+        let later = start + SWEEP_INTERVAL;
+        assert!(limiter.check(busy, later - Duration::from_millis(500)).is_ok());
+        assert!(limiter.check(busy, later - Duration::from_millis(400)).is_ok());
+        assert!(limiter.check(busy, later).is_err());
+        assert_eq!(limiter.len(), 1);
+    }
+
+    #[test]
+    fn test_ipv6_clients_share_a_bucket_per_64() {
+        let limiter = RateLimiter::new(6, 1);
+        let start = Instant::now();
+
+        assert!(limiter.check("2001:db8:1:2::1".parse().unwrap(), start).is_ok());
+        assert!(limiter.check("2001:db8:1:2:ffff::9".parse().unwrap(), start).is_err());
+        assert!(limiter.check("2001:db8:1:3::1".parse().unwrap(), start).is_ok());
+        // IPv4-mapped addresses count as the IPv4 client.
+        assert!(limiter.check("192.0.2.1".parse().unwrap(), start).is_ok());
+        assert!(limiter.check("::ffff:192.0.2.1".parse().unwrap(), start).is_err());
+    }
+
+    #[test]
+    fn test_retry_after_rounds_up() {
+        assert_eq!(retry_after_secs(Duration::from_millis(1)), 1);
+        assert_eq!(retry_after_secs(Duration::from_millis(2500)), 3);
+        assert_eq!(retry_after_secs(Duration::from_secs(4)), 4);
+        assert_eq!(retry_after_secs(Duration::ZERO), 1);
+        assert_eq!(retry_after_secs(Duration::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn test_zero_rate_is_rejected_when_enabled() {
+        let mut settings = crate::config::Settings::for_tests(std::collections::HashMap::new());
+        settings.limiter.requests_per_minute = 0;
+        assert!(settings.validate().is_ok());
+
+        settings.limiter.enabled = true;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_client_ip_trusts_forwarded_for_only_behind_proxy() {
+        let peer: SocketAddr = "10.0.0.2:40000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", "1.2.3.4, 203.0.113.7".parse().unwrap());
+
+        assert_eq!(client_ip(&headers, Some(peer), false), Some(peer.ip()));
+        assert_eq!(client_ip(&headers, Some(peer), true), "203.0.113.7".parse().ok());
+
+        // A malformed header falls back to the socket address.
+        headers.insert("x-forwarded-for", "not-an-ip".parse().unwrap());
+        assert_eq!(client_ip(&headers, Some(peer), true), Some(peer.ip()));
+        assert_eq!(client_ip(&HeaderMap::new(), None, true), None);
+    }
+}
@@ -1,5 +1,5 @@
 use askama::Template;
-use crate::models::SearchResult;
+use crate::models::{Answer, SearchResult};
 
 #[derive(Template)]
 #[template(path = "index.html")]
@@ -12,6 +12,49 @@ pub struct IndexTemplate {
 pub struct ResultsTemplate {
     pub query: String,
     pub results: Vec<SearchResult>,
+    pub answers: Vec<Answer>,
+    pub instance_name: String,
+    /// Words shown per snippet, 0 for no limit.
+    pub snippet_length: usize,
+    /// Engines queried for this page, sorted, for the attribution footer.
+    pub engines: Vec<String>,
+    /// Engines named in the footer, 0 for no limit.
+    pub max_footer_engines: usize,
+    /// Key signing image URLs for `/image_proxy`; `None` links images directly.
+    pub image_proxy_key: Option<String>,
+}
+
+impl ResultsTemplate {
+    /// `url` routed through the image proxy when it is enabled.
+    pub fn image_url(&self, url: &str) -> String {
+        match &self.image_proxy_key {
+            Some(key) => crate::web::image_proxy::proxied_url(key, url),
+            None => url.to_string(),
+        }
+    }
+
+    fn footer_limit(&self) -> usize {
+        if self.max_footer_engines == 0 {
+            self.engines.len()
+        } else {
+            self.max_footer_engines.min(self.engines.len())
+        }
+    }
+
+    /// Engines listed by name in the footer.
+    pub fn footer_engines(&self) -> &[String] {
+        &self.engines[..self.footer_limit()]
+    }
+
+    /// Engines summarized as "and N more".
+    pub fn footer_engines_hidden(&self) -> usize {
+        self.engines.len() - self.footer_limit()
+    }
+}
+
+#[derive(Template)]
+#[template(path = "maintenance.html")]
+pub struct MaintenanceTemplate {
     pub instance_name: String,
 }
 
@@ -27,6 +70,8 @@ pub struct OpenSearchTemplate {
 pub struct AtomTemplate {
     pub query: String,
     pub results: Vec<SearchResult>,
+    /// Top answer, listed before the results.
+    pub answer: Option<Answer>,
     pub instance_name: String,
     pub base_url: String,
 }
@@ -36,10 +81,74 @@ pub struct AtomTemplate {
 pub struct RssTemplate {
     pub query: String,
     pub results: Vec<SearchResult>,
+    /// Top answer, listed before the results.
+    pub answer: Option<Answer>,
     pub instance_name: String,
     pub base_url: String,
 }
 
+mod filters {
+    /// Elements without a closing tag.
+    const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "input", "meta", "link", "wbr"];
+
+    /// Truncates sanitized snippet HTML to `n` words, cutting only between
+    /// words of the text content and closing elements left open by the cut.
+    /// Entities contain no whitespace, so they are never split.
+    pub fn truncate_words<T: std::fmt::Display>(text: T, n: &usize) -> ::askama::Result<String> {
+        let (text, n) = (text.to_string(), *n);
+        if n == 0 {
+            return Ok(text);
+        }
+
+        let mut open: Vec<String> = Vec::new();
+        let mut words = 0;
+        let mut in_word = false;
+        let mut tag_end = 0;
+
+        for (i, c) in text.char_indices() {
+            if i < tag_end {
+                continue;
+            }
+            if c == '<' {
+                let end = text[i..].find('>').map_or(text.len(), |e| i + e + 1);
+                let tag = &text[i..end];
+                let name: String = tag
+                    .trim_start_matches(['<', '/'])
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect::<String>()
+                    .to_lowercase();
+                if tag.starts_with("</") {
+                    if let Some(pos) = open.iter().rposition(|t| *t == name) {
+                        open.truncate(pos);
+                    }
+                } else if !name.is_empty() && !tag.ends_with("/>") && !VOID_ELEMENTS.contains(&name.as_str()) {
+                    open.push(name);
+                }
+                tag_end = end;
+                continue;
+            }
+
+            if c.is_whitespace() {
+                in_word = false;
+            } else if !in_word {
+                in_word = true;
+                words += 1;
+                if words > n {
+                    let mut truncated = text[..i].trim_end().to_string();
+                    truncated.push('…');
+                    for name in open.iter().rev() {
+                        truncated.push_str(&format!("</{}>", name));
+                    }
+                    return Ok(truncated);
+                }
+            }
+        }
+
+        Ok(text)
+    }
+}
+
 pub mod helpers {
     use std::collections::HashMap;
 
@@ -47,7 +156,7 @@ pub mod helpers {
         let catalog = get_icon_catalog();
         catalog.get(name)
             .cloned()
-            .unwrap_or_else(|| "")
+            .unwrap_or("")
             .replace("__jinja_class_placeholder__", "sxng-icon-set")
     }
 
@@ -55,7 +164,7 @@ pub mod helpers {
         let catalog = get_icon_catalog();
         catalog.get(name)
             .cloned()
-            .unwrap_or_else(|| "")
+            .unwrap_or("")
             .replace("__jinja_class_placeholder__", "sxng-icon-set-big")
     }
 
@@ -69,3 +178,283 @@ pub mod helpers {
         m
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{metadata_keys, ResultContent};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_results_images_are_lazy_loaded() {
+        let mut metadata = HashMap::new();
+        metadata.insert("width".to_string(), "640".to_string());
+        metadata.insert("height".to_string(), "480".to_string());
+
+        let template = ResultsTemplate {
+            query: "cats".to_string(),
+            results: vec![SearchResult {
+                url: "https://example.com/cat".to_string(),
+                title: "Cat".to_string(),
+                content: ResultContent::Image {
+                    src: "https://example.com/cat.jpg".to_string(),
+                    thumbnail: None,
+                },
+                engines: vec!["engine1".to_string()],
+                score: 1.0,
+                metadata,
+            }],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 60,
+            engines: vec![],
+            max_footer_engines: 5,
+            image_proxy_key: None,
+        };
+
+        let html = template.render().unwrap();
+        assert!(html.contains(r#"loading="lazy""#));
+        assert!(html.contains(r#"decoding="async""#));
+        assert!(html.contains(r#"width="640""#));
+        assert!(html.contains(r#"height="480""#));
+    }
+
+    #[test]
+    fn test_results_images_go_through_proxy_when_enabled() {
+        let template = |image_proxy_key: Option<String>| ResultsTemplate {
+            query: "cats".to_string(),
+            results: vec![
+                SearchResult {
+                    url: "https://example.com/cat".to_string(),
+                    title: "Cat".to_string(),
+                    content: ResultContent::Image {
+                        src: "https://example.com/cat.jpg".to_string(),
+                        thumbnail: None,
+                    },
+                    engines: vec!["engine1".to_string()],
+                    score: 1.0,
+                    metadata: HashMap::new(),
+                },
+                SearchResult {
+                    url: "https://example.com/rust".to_string(),
+                    title: "Rust".to_string(),
+                    content: ResultContent::Infobox {
+                        description: "A systems programming language".to_string(),
+                        attributes: vec![],
+                        image: Some("https://example.com/logo.png".to_string()),
+                    },
+                    engines: vec!["wikidata".to_string()],
+                    score: 0.9,
+                    metadata: HashMap::new(),
+                },
+                SearchResult {
+                    url: "https://example.com/keyboard".to_string(),
+                    title: "Keyboard".to_string(),
+                    content: ResultContent::Text("Tenkeyless, brown switches".to_string()),
+                    engines: vec!["shopping".to_string()],
+                    score: 0.8,
+                    metadata: HashMap::from([(
+                        metadata_keys::THUMBNAIL.to_string(),
+                        "https://example.com/keyboard.jpg".to_string(),
+                    )]),
+                },
+                SearchResult {
+                    url: "https://example.com/video".to_string(),
+                    title: "Video".to_string(),
+                    content: ResultContent::Video {
+                        src: "https://example.com/video.mp4".to_string(),
+                        thumbnail: Some("https://example.com/poster.jpg".to_string()),
+                        duration: None,
+                    },
+                    engines: vec!["engine1".to_string()],
+                    score: 0.5,
+                    metadata: HashMap::new(),
+                },
+            ],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 60,
+            engines: vec![],
+            max_footer_engines: 5,
+            image_proxy_key,
+        };
+
+        let html = template(None).render().unwrap();
+        assert!(html.contains(r#"<img src="https://example.com/cat.jpg""#));
+        assert!(html.contains(r#"poster="https://example.com/poster.jpg""#));
+        assert!(html.contains(r#"class="thumbnail" src="https://example.com/keyboard.jpg""#));
+        assert!(html.contains(r#"<img src="https://example.com/logo.png""#));
+
+        let html = template(Some("secret".to_string())).render().unwrap();
+        let signature = crate::web::image_proxy::sign("secret", "https://example.com/cat.jpg");
+        assert!(html.contains(&format!(
+            r#"<img src="/image_proxy?url=https%3A%2F%2Fexample.com%2Fcat.jpg&amp;h={}""#,
+            signature
+        )));
+        assert!(html.contains(r#"poster="/image_proxy?url=https%3A%2F%2Fexample.com%2Fposter.jpg&amp;h="#));
+        assert!(html.contains(r#"class="thumbnail" src="/image_proxy?url=https%3A%2F%2Fexample.com%2Fkeyboard.jpg&amp;h="#));
+        assert!(html.contains(r#"<img src="/image_proxy?url=https%3A%2F%2Fexample.com%2Flogo.png&amp;h="#));
+    }
+
+    #[test]
+    fn test_results_render_audio() {
+        let template = ResultsTemplate {
+            query: "rust".to_string(),
+            results: vec![SearchResult {
+                url: "https://podcasts.example.com/ep1".to_string(),
+                title: "Episode 1".to_string(),
+                content: ResultContent::Audio {
+                    src: "https://cdn.example.com/ep1.mp3".to_string(),
+                    duration: Some("42:00".to_string()),
+                },
+                engines: vec!["podcasts".to_string()],
+                score: 1.0,
+                metadata: HashMap::new(),
+            }],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 60,
+            engines: vec![],
+            max_footer_engines: 5,
+            image_proxy_key: None,
+        };
+
+        let html = template.render().unwrap();
+        assert!(html.contains(r#"<audio src="https://cdn.example.com/ep1.mp3" controls"#));
+        assert!(html.contains("42:00"));
+    }
+
+    #[test]
+    fn test_results_render_truncates_snippets() {
+        let template = ResultsTemplate {
+            query: "rust".to_string(),
+            results: vec![SearchResult {
+                url: "https://example.com/".to_string(),
+                title: "Rust".to_string(),
+                content: ResultContent::Text("A <b>language</b> empowering everyone to build software".to_string()),
+                engines: vec!["engine1".to_string()],
+                score: 1.0,
+                metadata: HashMap::new(),
+            }],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 3,
+            engines: vec![],
+            max_footer_engines: 5,
+            image_proxy_key: None,
+        };
+
+        let html = template.render().unwrap();
+        assert!(html.contains("A <b>language</b> empowering…"));
+        assert!(!html.contains("software"));
+    }
+
+    #[test]
+    fn test_results_render_empty_state() {
+        let template = ResultsTemplate {
+            query: "<qwertyuiop>".to_string(),
+            results: vec![],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 60,
+            engines: vec![],
+            max_footer_engines: 5,
+            image_proxy_key: None,
+        };
+
+        let html = template.render().unwrap();
+        assert!(html.contains(r#"id="no_results""#));
+        assert!(html.contains("No results found for \"&lt;qwertyuiop&gt;\"."));
+    }
+
+    #[test]
+    fn test_footer_collapses_engines_past_limit() {
+        let template = |max_footer_engines: usize| ResultsTemplate {
+            query: "rust".to_string(),
+            results: vec![],
+            answers: vec![],
+            instance_name: "SearXNG".to_string(),
+            snippet_length: 60,
+            engines: ["bing", "duckduckgo", "google", "qwant", "wikipedia"].iter().map(|e| e.to_string()).collect(),
+            max_footer_engines,
+            image_proxy_key: None,
+        };
+
+        let html = template(3).render().unwrap();
+        assert!(html.contains("bing, duckduckgo, google and 2 more"));
+        assert!(!html.contains("qwant"));
+
+        let html = template(5).render().unwrap();
+        assert!(html.contains("bing, duckduckgo, google, qwant, wikipedia"));
+        assert!(!html.contains("more</p>"));
+
+        let html = template(0).render().unwrap();
+        assert!(html.contains("google, qwant, wikipedia"));
+    }
+
+    #[test]
+    fn test_feeds_list_answer_first() {
+        let answer = Answer {
+            answer: "2 + 2 = 4".to_string(),
+            url: None,
+            answerer: "calculator".to_string(),
+        };
+        let results = vec![SearchResult {
+            url: "https://example.com/math".to_string(),
+            title: "Arithmetic".to_string(),
+            content: ResultContent::Text("Adding numbers".to_string()),
+            engines: vec!["engine1".to_string()],
+            score: 1.0,
+            metadata: HashMap::new(),
+        }];
+
+        let rss = RssTemplate {
+            query: "2+2".to_string(),
+            results: results.clone(),
+            answer: Some(answer.clone()),
+            instance_name: "SearXNG".to_string(),
+            base_url: "https://search.example.org".to_string(),
+        }
+        .render()
+        .unwrap();
+        let first_item = rss.split("<item>").nth(1).unwrap();
+        assert!(first_item.contains("<title>Answer: 2 + 2 = 4</title>"));
+        assert!(first_item.contains("<category>answer</category>"));
+        assert!(rss.find("Answer: 2 + 2 = 4").unwrap() < rss.find("Arithmetic").unwrap());
+
+        let atom = AtomTemplate {
+            query: "2+2".to_string(),
+            results,
+            answer: Some(answer),
+            instance_name: "SearXNG".to_string(),
+            base_url: "https://search.example.org".to_string(),
+        }
+        .render()
+        .unwrap();
+        let first_entry = atom.split("<entry>").nth(1).unwrap();
+        assert!(first_entry.contains("<title>Answer: 2 + 2 = 4</title>"));
+        assert!(first_entry.contains(r#"<category term="answer"/>"#));
+    }
+
+    #[test]
+    fn test_truncate_words_cuts_on_word_boundary() {
+        let text = "The quick brown fox jumps over the lazy dog";
+        assert_eq!(filters::truncate_words(text, &4).unwrap(), "The quick brown fox…");
+
+        // Markup does not count towards the limit; an entity is part of the
+        // word it stands in and is never split.
+        let html = r#"Fish <b>&amp; chips</b> with <a href="https://example.com">mushy peas</a>"#;
+        assert_eq!(filters::truncate_words(html, &3).unwrap(), "Fish <b>&amp; chips</b>…");
+        assert_eq!(
+            filters::truncate_words(html, &5).unwrap(),
+            r#"Fish <b>&amp; chips</b> with <a href="https://example.com">mushy…</a>"#
+        );
+    }
+
+    #[test]
+    fn test_truncate_words_leaves_short_text_unchanged() {
+        assert_eq!(filters::truncate_words("Short snippet", &60).unwrap(), "Short snippet");
+        assert_eq!(filters::truncate_words("Exactly three words", &3).unwrap(), "Exactly three words");
+        assert_eq!(filters::truncate_words("No limit at all", &0).unwrap(), "No limit at all");
+    }
+}
@@ -1,8 +1,21 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
 use searxng_rs::config::Settings;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::web::{self, AppState};
 use std::env;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Held by every test that touches the process environment. Tests run in
+/// parallel, and `Settings::new()` reading the environment while another test
+/// sets it is a data race.
+static ENV_LOCK: Mutex<()> = Mutex::const_new(());
 
 #[test]
 fn test_env_var_override() {
+    let _env = ENV_LOCK.blocking_lock();
+
     // Set environment variable to override dummy engine weight
     // Note: The config crate uses double underscore as separator for nested keys
     // SEARXNG__ENGINES__DUMMY__WEIGHT corresponds to engines.dummy.weight
@@ -27,3 +40,49 @@ fn test_env_var_override() {
         env::remove_var("SEARXNG__ENGINES__DUMMY__TIMEOUT");
     }
 }
+
+#[tokio::test]
+async fn test_admin_reload_picks_up_env_changes() {
+    let _env = ENV_LOCK.lock().await;
+
+    unsafe {
+        env::set_var("SEARXNG__SERVER__SECRET_KEY", "reload-secret");
+        env::set_var("SEARXNG__GENERAL__INSTANCE_NAME", "Before Reload");
+    }
+
+    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new().expect("Failed to load settings"))));
+    let registry = EngineRegistry::new(settings.clone(), Client::new());
+    let app = web::router(AppState {
+        settings: settings.clone(),
+        registry: Arc::new(registry),
+        started: std::time::Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/admin/reload", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    unsafe {
+        env::set_var("SEARXNG__GENERAL__INSTANCE_NAME", "After Reload");
+    }
+
+    let client = Client::new();
+    let unauthorized = client.post(&url).send().await.unwrap();
+    assert_eq!(unauthorized.status(), 401);
+    assert_eq!(settings.load().general.instance_name, "Before Reload");
+
+    let resp = client
+        .post(&url)
+        .header("X-Secret-Key", "reload-secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(settings.load().general.instance_name, "After Reload");
+
+    unsafe {
+        env::remove_var("SEARXNG__SERVER__SECRET_KEY");
+        env::remove_var("SEARXNG__GENERAL__INSTANCE_NAME");
+    }
+}
@@ -0,0 +1,53 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
+use searxng_rs::config::Settings;
+use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::engines::wikipedia::Wikipedia;
+use searxng_rs::web::{self, AppState};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_engines_route_lists_engine_details() {
+    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new().expect("Failed to load settings"))));
+    let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+    registry.register_engine(Box::new(DummyEngine));
+    registry.register_engine(Box::new(Wikipedia));
+    let app = web::router(AppState {
+        settings,
+        registry: Arc::new(registry),
+        started: Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let engines: serde_json::Value = Client::new()
+        .get(format!("{}/engines", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let engines = engines.as_array().expect("a list of engines");
+    assert_eq!(engines.len(), 2);
+
+    let wikipedia = engines.iter().find(|e| e["id"] == "wikipedia").expect("wikipedia is listed");
+    assert_eq!(wikipedia["name"], "Wikipedia");
+    assert_eq!(wikipedia["categories"], serde_json::json!(["general"]));
+    assert_eq!(wikipedia["weight"], 1.0);
+    assert_eq!(wikipedia["enabled"], true);
+    assert_eq!(wikipedia["about"]["website"], "https://www.wikipedia.org");
+    assert_eq!(wikipedia["about"]["use_official_api"], true);
+    assert_eq!(wikipedia["about"]["results"], "JSON");
+    assert!(wikipedia["about"]["privacy_policy"].is_string());
+
+    // Engines without details still list the fields.
+    let dummy = engines.iter().find(|e| e["id"] == "dummy").expect("dummy is listed");
+    assert!(dummy["about"]["website"].is_null());
+    assert_eq!(dummy["about"]["results"], "HTML");
+}
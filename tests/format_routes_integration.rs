@@ -0,0 +1,52 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
+use searxng_rs::config::Settings;
+use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::web::{self, AppState};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_format_aliases_force_the_format() {
+    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new().expect("Failed to load settings"))));
+    let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+    registry.register_engine(Box::new(DummyEngine));
+    let app = web::router(AppState {
+        settings,
+        registry: Arc::new(registry),
+        started: Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let client = Client::new();
+    for (path, content_type) in [
+        ("/search.json", "application/json"),
+        ("/search.rss", "application/rss+xml"),
+        ("/search.atom", "application/atom+xml"),
+    ] {
+        // A conflicting `format` parameter loses to the path.
+        let response = client
+            .get(format!("{}{}?q=rust&format=html", base, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200, "{} should succeed", path);
+        let actual = response.headers()["content-type"].to_str().unwrap().to_string();
+        assert!(actual.starts_with(content_type), "{} served {}", path, actual);
+    }
+
+    let json: serde_json::Value = client
+        .get(format!("{}/search.json?q=rust", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["query"], "rust");
+}
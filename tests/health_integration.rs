@@ -0,0 +1,43 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
+use searxng_rs::config::Settings;
+use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::web::{self, AppState};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_health_info_reports_version_and_uptime() {
+    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new().expect("Failed to load settings"))));
+    let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+    registry.register_engine(Box::new(DummyEngine));
+    let app = web::router(AppState {
+        settings,
+        registry: Arc::new(registry),
+        started: Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let client = Client::new();
+
+    // The plain endpoint stays as-is for load balancers.
+    let plain = client.get(format!("{}/health", base)).send().await.unwrap();
+    assert_eq!(plain.text().await.unwrap(), "OK");
+
+    let info: serde_json::Value = client
+        .get(format!("{}/health/info", base))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["uptime_seconds"].as_u64().is_some(), "uptime should be a non-negative integer");
+    assert_eq!(info["engines"], 1);
+}
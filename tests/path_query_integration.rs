@@ -0,0 +1,49 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
+use searxng_rs::config::Settings;
+use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::web::{self, AppState};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[tokio::test]
+async fn test_path_query_matches_query_parameter() {
+    let settings = Arc::new(ArcSwap::from(Arc::new(Settings::new().expect("Failed to load settings"))));
+    let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+    registry.register_engine(Box::new(DummyEngine));
+    let app = web::router(AppState {
+        settings,
+        registry: Arc::new(registry),
+        started: Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let client = Client::new();
+    let get_json = |url: String| {
+        let client = client.clone();
+        async move {
+            let response = client.get(url).send().await.unwrap();
+            assert_eq!(response.status(), 200);
+            response.json::<serde_json::Value>().await.unwrap()
+        }
+    };
+
+    let expected = get_json(format!("{}/search?q=rust%20async&format=json", base)).await;
+    assert_eq!(expected["query"], "rust async");
+
+    for path in ["/search/rust%20async", "/search/rust+async"] {
+        // A `q` in the query string loses to the path.
+        let actual = get_json(format!("{}{}?format=json&q=ignored", base, path)).await;
+        assert_eq!(actual["query"], expected["query"], "{} decoded differently", path);
+        assert_eq!(actual["results"], expected["results"], "{} returned different results", path);
+    }
+
+    // An encoded plus stays a plus.
+    let plus = get_json(format!("{}/search/c%2B%2B?format=json", base)).await;
+    assert_eq!(plus["query"], "c++");
+}
@@ -0,0 +1,61 @@
+use arc_swap::ArcSwap;
+use reqwest::Client;
+use searxng_rs::config::Settings;
+use searxng_rs::engines::dummy::DummyEngine;
+use searxng_rs::engines::registry::EngineRegistry;
+use searxng_rs::web::{self, AppState};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Instant;
+
+const BURST: u32 = 3;
+
+#[tokio::test]
+async fn test_search_beyond_burst_gets_429() {
+    let mut settings = Settings::new().expect("Failed to load settings");
+    settings.limiter.enabled = true;
+    settings.limiter.requests_per_minute = 1;
+    settings.limiter.burst = BURST;
+    let settings = Arc::new(ArcSwap::from(Arc::new(settings)));
+    let mut registry = EngineRegistry::new(settings.clone(), Client::new());
+    registry.register_engine(Box::new(DummyEngine));
+    let app = web::router(AppState {
+        settings,
+        registry: Arc::new(registry),
+        started: Instant::now(),
+        search_slots: None,
+    });
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .await
+            .unwrap()
+    });
+
+    let client = Client::new();
+    for _ in 0..BURST {
+        let response = client
+            .get(format!("{}/search?q=rust&format=json", base))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    let response = client
+        .get(format!("{}/search?q=rust&format=json", base))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 429);
+    let retry_after: u64 = response.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after), "Retry-After was {}", retry_after);
+
+    // The limit shares the client's bucket across the limited routes only.
+    let response = client.get(format!("{}/autocomplete?q=rust", base)).send().await.unwrap();
+    assert_eq!(response.status(), 429);
+    let response = client.get(format!("{}/health", base)).send().await.unwrap();
+    assert_eq!(response.status(), 200);
+}